@@ -3,7 +3,12 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use umee_cosmwasm::msg::{ExecuteMsg, InstantiateMsg, OwnerResponse, QueryMsg};
+use cw_umee_types::LeverageResult;
+use umee_cosmwasm::msg::{
+  CompounderResponse, ExecuteMsg, InstantiateMsg, LiquidatorResponse, LoopingResponse, MigrateMsg,
+  OwnerResponse, OwnershipResponse, PauseStatusResponse, QueryMsg, RolesResponse, SharesResponse,
+  VaultResponse,
+};
 use umee_cosmwasm::state::State;
 
 fn main() {
@@ -14,7 +19,17 @@ fn main() {
 
   export_schema(&schema_for!(InstantiateMsg), &out_dir);
   export_schema(&schema_for!(ExecuteMsg), &out_dir);
+  export_schema(&schema_for!(MigrateMsg), &out_dir);
   export_schema(&schema_for!(QueryMsg), &out_dir);
   export_schema(&schema_for!(State), &out_dir);
   export_schema(&schema_for!(OwnerResponse), &out_dir);
+  export_schema(&schema_for!(OwnershipResponse), &out_dir);
+  export_schema(&schema_for!(RolesResponse), &out_dir);
+  export_schema(&schema_for!(PauseStatusResponse), &out_dir);
+  export_schema(&schema_for!(LeverageResult), &out_dir);
+  export_schema(&schema_for!(LiquidatorResponse), &out_dir);
+  export_schema(&schema_for!(LoopingResponse), &out_dir);
+  export_schema(&schema_for!(VaultResponse), &out_dir);
+  export_schema(&schema_for!(SharesResponse), &out_dir);
+  export_schema(&schema_for!(CompounderResponse), &out_dir);
 }
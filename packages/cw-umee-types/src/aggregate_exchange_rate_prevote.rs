@@ -7,7 +7,30 @@ use serde::{Deserialize, Serialize};
 // SHA256("{salt}:{exchange rate}{denom},...,{exchange rate}{denom}:{voter}")
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct AggregateExchangeRatePrevote {
-  hash: String,
-  voter: String,
-  submit_block: u64,
+  pub(crate) hash: String,
+  pub(crate) voter: String,
+  pub(crate) submit_block: u64,
+}
+
+impl AggregateExchangeRatePrevote {
+  // new returns the prevote hash of the voter at the submit block.
+  pub fn new(hash: impl Into<String>, voter: impl Into<String>, submit_block: u64) -> Self {
+    AggregateExchangeRatePrevote {
+      hash: hash.into(),
+      voter: voter.into(),
+      submit_block,
+    }
+  }
+
+  pub fn hash(&self) -> &str {
+    &self.hash
+  }
+
+  pub fn voter(&self) -> &str {
+    &self.voter
+  }
+
+  pub fn submit_block(&self) -> u64 {
+    self.submit_block
+  }
 }
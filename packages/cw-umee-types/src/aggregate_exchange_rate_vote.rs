@@ -6,14 +6,50 @@ use serde::{Deserialize, Serialize};
 // the exchange rates of USD denominated in various assets.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct AggregateExchangeRateVote {
-  exchange_rate_tuples: Vec<ExchangeRateTuple>,
-  voter: String,
+  pub(crate) exchange_rate_tuples: Vec<ExchangeRateTuple>,
+  pub(crate) voter: String,
+}
+
+impl AggregateExchangeRateVote {
+  // new returns the vote of the exchange rates of the voter.
+  pub fn new(exchange_rate_tuples: Vec<ExchangeRateTuple>, voter: impl Into<String>) -> Self {
+    AggregateExchangeRateVote {
+      exchange_rate_tuples,
+      voter: voter.into(),
+    }
+  }
+
+  pub fn exchange_rate_tuples(&self) -> &[ExchangeRateTuple] {
+    &self.exchange_rate_tuples
+  }
+
+  pub fn voter(&self) -> &str {
+    &self.voter
+  }
 }
 
 // ExchangeRateTuple struct to store interpreted
 // exchange rates data to store.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ExchangeRateTuple {
-  denom: String,
-  exchange_rate: Decimal256,
+  pub(crate) denom: String,
+  pub(crate) exchange_rate: Decimal256,
+}
+
+impl ExchangeRateTuple {
+  // new returns the exchange rate of the denom.
+  pub fn new(denom: impl Into<String>, exchange_rate: Decimal256) -> Self {
+    ExchangeRateTuple {
+      denom: denom.into(),
+      exchange_rate,
+    }
+  }
+
+  pub fn denom(&self) -> &str {
+    &self.denom
+  }
+
+  pub fn exchange_rate(&self) -> Decimal256 {
+    self.exchange_rate
+  }
 }
@@ -3,6 +3,24 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct BadDebt {
-  address: String,
-  denom: String,
+  pub(crate) address: String,
+  pub(crate) denom: String,
+}
+
+impl BadDebt {
+  // new returns the bad debt of the denom of the address.
+  pub fn new(address: impl Into<String>, denom: impl Into<String>) -> Self {
+    BadDebt {
+      address: address.into(),
+      denom: denom.into(),
+    }
+  }
+
+  pub fn address(&self) -> &str {
+    &self.address
+  }
+
+  pub fn denom(&self) -> &str {
+    &self.denom
+  }
 }
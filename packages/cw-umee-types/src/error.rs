@@ -1,6 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError};
 use thiserror::Error;
 
+use crate::error_parse::{parse_chain_error, ChainError};
+
 #[derive(Error, Debug)]
 pub enum ContractError {
   #[error("{0}")]
@@ -9,8 +11,64 @@ pub enum ContractError {
   #[error("Unauthorized")]
   Unauthorized {},
 
+  #[error("Paused: {operation}")]
+  Paused { operation: String },
+
+  // the StructUmeeMsg has no params for its assigned msg
+  #[error("Invalid umee msg")]
+  InvalidUmeeMsg {},
+
+  // the querier failed to handle the query, e.g. the umee binding is missing
+  #[error("Querier system error: {message}")]
+  QuerierSystem { message: String },
+
+  // the umee module returned an error for the query
+  #[error("Querier contract error: {module} {query}: {message}")]
+  QuerierContract {
+    module: String,
+    query: String,
+    message: String,
+  },
+
+  // the account has less collateral than the uTokens of the msg
+  #[error("Insufficient collateral: {asset}")]
+  InsufficientCollateral { asset: Coin },
+
+  // the oracle has no valid price of the denom
+  #[error("Oracle price stale: {denom}")]
+  OracleStale { denom: String },
+
+  // the market of the denom doesn't accept the operation
+  #[error("Market disabled: {operation} of {denom}")]
+  MarketDisabled { denom: String, operation: String },
+
   #[error("Custom Error val: {val:?}")]
   CustomError { val: String },
   // Add any other custom errors you like here.
   // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+// the typed errors are returned as generic StdErrors by the functions that
+// return a StdResult, like the query entry points.
+impl From<ContractError> for StdError {
+  fn from(err: ContractError) -> Self {
+    match err {
+      ContractError::Std(err) => err,
+      err => StdError::generic_err(err.to_string()),
+    }
+  }
+}
+
+impl ContractError {
+  // chain_error returns the known umee module error in the text of the
+  // error, like the error of a query rejected by the module.
+  pub fn chain_error(&self) -> Option<ChainError> {
+    match self {
+      ContractError::QuerierContract { message, .. }
+      | ContractError::QuerierSystem { message }
+      | ContractError::CustomError { val: message } => parse_chain_error(message),
+      ContractError::Std(err) => parse_chain_error(&err.to_string()),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,562 @@
+use cosmwasm_std::{Coin, Decimal256, Uint128};
+use std::str::FromStr;
+use thiserror::Error;
+
+// ChainError is a known error of the umee native modules, parsed from the
+// error text the chain returns for a rejected msg or query. The contract
+// only sees the text, like "Querier contract error: ..." or the error of a
+// submsg, the parse recovers the error of the module with its operands when
+// the text has them.
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ChainError {
+  // x/leverage
+  #[error("not a registered Token: {denom:?}")]
+  NotRegisteredToken { denom: Option<String> },
+
+  #[error("denom should be a uToken: {denom:?}")]
+  NotUToken { denom: Option<String> },
+
+  #[error("supplying of Token disabled: {denom:?}")]
+  SupplyNotEnabled { denom: Option<String> },
+
+  #[error("borrowing of Token disabled: {denom:?}")]
+  BorrowNotEnabled { denom: Option<String> },
+
+  #[error("base denom blacklisted: {denom:?}")]
+  Blacklisted { denom: Option<String> },
+
+  #[error("collateral weight of Token is zero: {denom:?}")]
+  CollateralWeightZero { denom: Option<String> },
+
+  #[error("borrow positions are undercollateralized: borrowed value {borrowed_value:?}, borrow limit {borrow_limit:?}")]
+  Undercollateralized {
+    borrowed_value: Option<Decimal256>,
+    borrow_limit: Option<Decimal256>,
+  },
+
+  #[error("market total supply would exceed MaxSupply: {denom:?}")]
+  MaxSupplyExceeded { denom: Option<String> },
+
+  #[error("market total collateral would exceed MaxCollateralShare: {denom:?}")]
+  MaxCollateralShareExceeded { denom: Option<String> },
+
+  #[error("market would fall below MinCollateralLiquidity: {denom:?}")]
+  MinCollateralLiquidity {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("market would exceed MaxSupplyUtilization: {denom:?}")]
+  MaxSupplyUtilization {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("insufficient balance: {denom:?}")]
+  InsufficientBalance {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("insufficient collateral: {denom:?}")]
+  InsufficientCollateral {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("lending pool insufficient: {denom:?}")]
+  LendingPoolInsufficient {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("no active borrow of {denom:?} to repay")]
+  NoBorrowToRepay { denom: Option<String> },
+
+  #[error("borrower not eligible for liquidation: {borrower:?}")]
+  LiquidationIneligible { borrower: Option<String> },
+
+  // x/oracle
+  #[error("invalid oracle price for {denom:?}")]
+  InvalidOraclePrice { denom: Option<String> },
+
+  #[error("insufficient historic medians available for {denom:?}")]
+  NoHistoricMedians { denom: Option<String> },
+
+  #[error("unknown denom: {denom:?}")]
+  UnknownDenom { denom: Option<String> },
+
+  #[error("no aggregate prevote: {voter:?}")]
+  NoAggregatePrevote { voter: Option<String> },
+
+  #[error("no aggregate vote: {voter:?}")]
+  NoAggregateVote { voter: Option<String> },
+
+  // x/incentive
+  #[error("insufficient collateral to bond: {denom:?}")]
+  InsufficientCollateralToBond {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("insufficient bonded: {denom:?}")]
+  InsufficientBonded {
+    denom: Option<String>,
+    amount: Option<Uint128>,
+  },
+
+  #[error("max concurrent unbondings reached: {denom:?}")]
+  MaxUnbondings { denom: Option<String> },
+
+  // x/metoken
+  #[error("index {denom:?} not found")]
+  IndexNotFound { denom: Option<String> },
+
+  #[error("asset {denom:?} is not accepted in the index")]
+  AssetNotAccepted { denom: Option<String> },
+}
+
+impl ChainError {
+  // module returns the umee module of the error.
+  pub fn module(&self) -> &'static str {
+    match self {
+      ChainError::InvalidOraclePrice { .. }
+      | ChainError::NoHistoricMedians { .. }
+      | ChainError::UnknownDenom { .. }
+      | ChainError::NoAggregatePrevote { .. }
+      | ChainError::NoAggregateVote { .. } => "oracle",
+      ChainError::InsufficientCollateralToBond { .. }
+      | ChainError::InsufficientBonded { .. }
+      | ChainError::MaxUnbondings { .. } => "incentive",
+      ChainError::IndexNotFound { .. } | ChainError::AssetNotAccepted { .. } => "metoken",
+      _ => "leverage",
+    }
+  }
+}
+
+type Parser = fn(&[&str], &str) -> ChainError;
+
+// PATTERNS are the error texts of the modules with the parser of their
+// operands, the first matching pattern wins so the longer texts go first.
+// A "{}" in the pattern is the operand word inside the text.
+const PATTERNS: &[(&str, Parser)] = &[
+  ("insufficient collateral to bond", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::InsufficientCollateralToBond { denom, amount }
+  }),
+  ("not a registered Token", |words, _| {
+    ChainError::NotRegisteredToken {
+      denom: denom(words),
+    }
+  }),
+  ("denom should be a uToken", |words, _| {
+    ChainError::NotUToken {
+      denom: denom(words),
+    }
+  }),
+  ("supplying of Token disabled", |words, _| {
+    ChainError::SupplyNotEnabled {
+      denom: denom(words),
+    }
+  }),
+  ("borrowing of Token disabled", |words, _| {
+    ChainError::BorrowNotEnabled {
+      denom: denom(words),
+    }
+  }),
+  ("base denom blacklisted", |words, _| {
+    ChainError::Blacklisted {
+      denom: denom(words),
+    }
+  }),
+  ("collateral weight of Token is zero", |words, _| {
+    ChainError::CollateralWeightZero {
+      denom: denom(words),
+    }
+  }),
+  ("undercollaterized", undercollateralized),
+  ("undercollateralized", undercollateralized),
+  ("would exceed MaxSupplyUtilization", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::MaxSupplyUtilization { denom, amount }
+  }),
+  ("would exceed MaxSupply", |words, _| {
+    ChainError::MaxSupplyExceeded {
+      denom: denom(words),
+    }
+  }),
+  ("would exceed MaxCollateralShare", |words, _| {
+    ChainError::MaxCollateralShareExceeded {
+      denom: denom(words),
+    }
+  }),
+  ("below MinCollateralLiquidity", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::MinCollateralLiquidity { denom, amount }
+  }),
+  ("below minimum collateral liquidity", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::MinCollateralLiquidity { denom, amount }
+  }),
+  ("insufficient balance", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::InsufficientBalance { denom, amount }
+  }),
+  ("insufficient collateral", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::InsufficientCollateral { denom, amount }
+  }),
+  ("lending pool insufficient", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::LendingPoolInsufficient { denom, amount }
+  }),
+  ("no active borrow of {} to repay", |words, _| {
+    ChainError::NoBorrowToRepay {
+      denom: denom(words),
+    }
+  }),
+  ("denom not borrowed", |words, _| {
+    ChainError::NoBorrowToRepay {
+      denom: denom(words),
+    }
+  }),
+  ("borrower not eligible for liquidation", |words, _| {
+    ChainError::LiquidationIneligible {
+      borrower: address(words),
+    }
+  }),
+  ("invalid oracle price for {}", |words, _| {
+    ChainError::InvalidOraclePrice {
+      denom: denom(words),
+    }
+  }),
+  ("invalid oracle price", |words, _| {
+    ChainError::InvalidOraclePrice {
+      denom: denom(words),
+    }
+  }),
+  ("insufficient historic medians", |words, _| {
+    ChainError::NoHistoricMedians {
+      denom: denom(words),
+    }
+  }),
+  ("unknown denom", |words, _| ChainError::UnknownDenom {
+    denom: denom(words),
+  }),
+  ("no aggregate prevote", |words, _| {
+    ChainError::NoAggregatePrevote {
+      voter: address(words),
+    }
+  }),
+  ("no aggregate vote", |words, _| {
+    ChainError::NoAggregateVote {
+      voter: address(words),
+    }
+  }),
+  ("insufficient bonded", |words, _| {
+    let (denom, amount) = asset(words);
+    ChainError::InsufficientBonded { denom, amount }
+  }),
+  ("max concurrent unbondings", |words, _| {
+    ChainError::MaxUnbondings {
+      denom: denom(words),
+    }
+  }),
+  ("index {} not found", |words, _| ChainError::IndexNotFound {
+    denom: denom(words),
+  }),
+  ("asset {} is not accepted", |words, _| {
+    ChainError::AssetNotAccepted {
+      denom: denom(words),
+    }
+  }),
+];
+
+// parse_chain_error returns the known module error of the message, or None
+// when the message has no known error. The operands missing from the
+// message are None.
+pub fn parse_chain_error(message: &str) -> Option<ChainError> {
+  PATTERNS.iter().find_map(|(pattern, parser)| {
+    let words = operands(message, pattern)?;
+    Some(parser(&words, message))
+  })
+}
+
+// operands returns the operand words of the pattern in the message, the
+// ones of the segment after it first and then of the segment before it. The
+// segments are delimited by ":", the mock appends the operands to the error
+// ("insufficient balance: 100uumee") while the chain wraps the error with
+// them ("100uumee: insufficient balance"). A segment is an operand when it
+// is a single word, otherwise only its coins are. A "{}" pattern returns
+// the word in its place.
+fn operands<'a>(message: &'a str, pattern: &str) -> Option<Vec<&'a str>> {
+  if let Some((prefix, suffix)) = pattern.split_once("{}") {
+    return message.match_indices(prefix).find_map(|(start, _)| {
+      let rest = &message[start + prefix.len()..];
+      let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+      rest[end..]
+        .starts_with(suffix)
+        .then(|| vec![rest[..end].trim_end_matches(is_separator)])
+    });
+  }
+  let start = message.find(pattern)?;
+  let after = message[start + pattern.len()..].split(':').nth(1);
+  let before = message[..start].rsplit(':').nth(1);
+  Some(
+    after
+      .into_iter()
+      .chain(before)
+      .flat_map(delimited)
+      .collect(),
+  )
+}
+
+// delimited returns the segment when it is a single word, or the coins of
+// the segment, the other words are the prose of the error.
+fn delimited(segment: &str) -> Vec<&str> {
+  let segment = segment.trim();
+  if !segment.is_empty() && !segment.contains(char::is_whitespace) {
+    return vec![segment];
+  }
+  segment
+    .split(is_separator)
+    .filter(|word| coin(word).is_some())
+    .collect()
+}
+
+fn is_separator(c: char) -> bool {
+  c.is_whitespace() || matches!(c, ':' | ',' | ';')
+}
+
+fn is_denom(word: &str) -> bool {
+  word.len() > 1
+    && word.starts_with(|c: char| c.is_ascii_alphabetic())
+    && word
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'))
+}
+
+// coin parses the word as a coin of the sdk, like 100u/uumee.
+fn coin(word: &str) -> Option<Coin> {
+  let split = word
+    .find(|c: char| !c.is_ascii_digit())
+    .filter(|split| *split > 0)?;
+  let (amount, denom) = word.split_at(split);
+  if !is_denom(denom) {
+    return None;
+  }
+  Some(Coin {
+    denom: denom.to_string(),
+    amount: Uint128::from_str(amount).ok()?,
+  })
+}
+
+// asset returns the denom and amount of the first coin operand, or the
+// first denom operand without amount when there is no coin.
+fn asset(words: &[&str]) -> (Option<String>, Option<Uint128>) {
+  match words.iter().find_map(|word| coin(word)) {
+    Some(coin) => (Some(coin.denom), Some(coin.amount)),
+    None => (
+      words
+        .iter()
+        .find(|word| is_denom(word))
+        .map(|word| word.to_string()),
+      None,
+    ),
+  }
+}
+
+fn denom(words: &[&str]) -> Option<String> {
+  asset(words).0
+}
+
+fn address(words: &[&str]) -> Option<String> {
+  words
+    .iter()
+    .find(|word| is_denom(word) && !word.contains('/'))
+    .map(|word| word.to_string())
+}
+
+// undercollateralized reads the borrowed value and the borrow limit of the
+// message, the decimals after the "value" and "limit" words.
+fn undercollateralized(_: &[&str], message: &str) -> ChainError {
+  let words: Vec<&str> = message
+    .split(is_separator)
+    .filter(|word| !word.is_empty())
+    .collect();
+  let decimal_after = |keys: &[&str]| {
+    words
+      .windows(2)
+      .find(|pair| keys.contains(&pair[0]) && Decimal256::from_str(pair[1]).is_ok())
+      .and_then(|pair| Decimal256::from_str(pair[1]).ok())
+  };
+  ChainError::Undercollateralized {
+    borrowed_value: decimal_after(&["value", "borrowed"]),
+    borrow_limit: decimal_after(&["limit"]),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::ContractError;
+
+  fn some(denom: &str) -> Option<String> {
+    Some(String::from(denom))
+  }
+
+  #[test]
+  fn chain_errors() {
+    // the operands are read after the error as the mock appends them and
+    // before it as the chain wraps the error with them
+    assert_eq!(
+      parse_chain_error("borrowing of Token disabled: uumee"),
+      Some(ChainError::BorrowNotEnabled {
+        denom: some("uumee"),
+      })
+    );
+    let wrapped =
+      "failed to execute message; message index: 0: 100u/uumee: insufficient collateral";
+    let err = parse_chain_error(wrapped).unwrap();
+    assert_eq!(
+      err,
+      ChainError::InsufficientCollateral {
+        denom: some("u/uumee"),
+        amount: Some(Uint128::new(100)),
+      }
+    );
+    assert_eq!(err.module(), "leverage");
+    assert_eq!(
+      parse_chain_error("insufficient collateral to bond: 5u/uumee"),
+      Some(ChainError::InsufficientCollateralToBond {
+        denom: some("u/uumee"),
+        amount: Some(Uint128::new(5)),
+      })
+    );
+    assert_eq!(
+      parse_chain_error(
+        "market would fall below minimum collateral liquidity or exceed max supply utilization: 10uumee"
+      ),
+      Some(ChainError::MinCollateralLiquidity {
+        denom: some("uumee"),
+        amount: Some(Uint128::new(10)),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("market total supply would exceed MaxSupply: uumee"),
+      Some(ChainError::MaxSupplyExceeded {
+        denom: some("uumee"),
+      })
+    );
+    assert_eq!(
+      parse_chain_error(
+        "borrow positions are undercollaterized: borrowed value 1.5 exceeds borrow limit 1.2"
+      ),
+      Some(ChainError::Undercollateralized {
+        borrowed_value: Some(Decimal256::percent(150)),
+        borrow_limit: Some(Decimal256::percent(120)),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("no active borrow of uatom to repay"),
+      Some(ChainError::NoBorrowToRepay {
+        denom: some("uatom"),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("invalid oracle price for uumee"),
+      Some(ChainError::InvalidOraclePrice {
+        denom: some("uumee"),
+      })
+    );
+    let err = parse_chain_error("index me/usd not found").unwrap();
+    assert_eq!(
+      err,
+      ChainError::IndexNotFound {
+        denom: some("me/usd"),
+      }
+    );
+    assert_eq!(err.module(), "metoken");
+    assert_eq!(parse_chain_error("out of gas"), None);
+
+    // the typed errors expose the module error in their message
+    let err = ContractError::QuerierContract {
+      module: String::from("leverage"),
+      query: String::from("market_summary"),
+      message: String::from("not a registered Token: uatom"),
+    };
+    assert_eq!(
+      err.chain_error(),
+      Some(ChainError::NotRegisteredToken {
+        denom: some("uatom"),
+      })
+    );
+    assert_eq!(ContractError::Unauthorized {}.chain_error(), None);
+  }
+
+  #[test]
+  fn wrapped_chain_errors() {
+    // x/leverage wraps its errors with the operand before them, the querier
+    // and the submsg dispatch prefix the wrapped error
+    assert_eq!(
+      parse_chain_error("Querier contract error: uatom: not a registered Token"),
+      Some(ChainError::NotRegisteredToken {
+        denom: some("uatom"),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("dispatch: submessages: 250uumee: lending pool insufficient"),
+      Some(ChainError::LendingPoolInsufficient {
+        denom: some("uumee"),
+        amount: Some(Uint128::new(250)),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("requested 150uumee with 100uumee available: insufficient balance"),
+      Some(ChainError::InsufficientBalance {
+        denom: some("uumee"),
+        amount: Some(Uint128::new(150)),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("borrowed: 1.5, limit: 1.2: borrow positions are undercollaterized"),
+      Some(ChainError::Undercollateralized {
+        borrowed_value: Some(Decimal256::percent(150)),
+        borrow_limit: Some(Decimal256::percent(120)),
+      })
+    );
+    assert_eq!(
+      parse_chain_error("umee1y6xz2ggfc0pcsmyjlekh0j9pxh6hk87ymc9due: no aggregate prevote"),
+      Some(ChainError::NoAggregatePrevote {
+        voter: some("umee1y6xz2ggfc0pcsmyjlekh0j9pxh6hk87ymc9due"),
+      })
+    );
+
+    // the prose around the error is not an operand
+    assert_eq!(
+      parse_chain_error("Querier contract error: insufficient balance"),
+      Some(ChainError::InsufficientBalance {
+        denom: None,
+        amount: None,
+      })
+    );
+    assert_eq!(
+      parse_chain_error("Querier contract error: leverage max_borrow: not a registered Token"),
+      Some(ChainError::NotRegisteredToken { denom: None })
+    );
+    assert_eq!(
+      parse_chain_error("Generic error: market would exceed MaxSupplyUtilization"),
+      Some(ChainError::MaxSupplyUtilization {
+        denom: None,
+        amount: None,
+      })
+    );
+    assert_eq!(
+      parse_chain_error("insufficient balance"),
+      Some(ChainError::InsufficientBalance {
+        denom: None,
+        amount: None,
+      })
+    );
+  }
+}
@@ -0,0 +1,552 @@
+use crate::error::ContractError;
+use crate::interest::dec_quo;
+use crate::msg_leverage::UmeeMsgLeverage;
+use crate::query_leverage::{
+  AccountBalancesResponse, MarketSummaryResponse, RegisteredTokensResponse,
+};
+use crate::query_oracle::ExchangeRatesResponse;
+use crate::token::Token;
+use cosmwasm_std::{Coin, Decimal256, StdError, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// UTOKEN_PREFIX is the denom prefix of the uTokens of the x/leverage module.
+pub(crate) const UTOKEN_PREFIX: &str = "u/";
+
+// PositionMarket is the token settings and the prices used to value the
+// positions of a base denom.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub(crate) struct PositionMarket {
+  pub(crate) token: Token,
+  pub(crate) price: Option<Decimal256>,
+  pub(crate) utoken_exchange_rate: Decimal256,
+  // historic_price is the median of the historic medians, used for the
+  // tokens with historic_medians.
+  #[serde(default)]
+  pub(crate) historic_price: Option<Decimal256>,
+  // median_deviation is the standard deviation of the historic medians
+  // around their median, reported by the oracle.
+  #[serde(default)]
+  pub(crate) median_deviation: Option<Decimal256>,
+}
+
+// PriceMode is the price a position is valued at, as the x/leverage module
+// values the positions of the tokens with historic_medians under both the
+// spot and the historic median prices.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum PriceMode {
+  Spot,
+  Historic,
+  // Low is the lower of the spot and historic prices, High the higher.
+  Low,
+  High,
+}
+
+// PositionSummary is the USD value summary of an AccountPosition, the
+// health_factor is the liquidation threshold over the borrowed value and
+// it is none without borrows.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PositionSummary {
+  pub collateral_value: Decimal256,
+  pub borrowed_value: Decimal256,
+  pub borrow_limit: Decimal256,
+  pub liquidation_threshold: Decimal256,
+  pub health_factor: Option<Decimal256>,
+}
+
+impl PositionSummary {
+  // is_healthy is true while the borrowed value doesn't exceed the borrow
+  // limit, the condition of the chain to borrow, withdraw or decollateralize.
+  pub fn is_healthy(&self) -> bool {
+    self.borrowed_value <= self.borrow_limit
+  }
+
+  // is_liquidatable is true when there are borrows and the borrowed value
+  // reaches the liquidation threshold, where the close factor isn't zero.
+  pub fn is_liquidatable(&self) -> bool {
+    !self.borrowed_value.is_zero() && self.borrowed_value >= self.liquidation_threshold
+  }
+}
+
+// AccountPosition is the x/leverage position of an account, it values the
+// balances of the account with the registered tokens and the oracle prices
+// to evaluate the account health and the result of an action on it without
+// a chain round trip.
+//
+// The amounts are kept by base denom, the collateral in uTokens and the
+// supplied and borrowed in base tokens. The uToken exchange rates are 1
+// unless they are set from the market summaries.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AccountPosition {
+  pub(crate) supplied: BTreeMap<String, Uint128>,
+  pub(crate) collateral: BTreeMap<String, Uint128>,
+  pub(crate) borrowed: BTreeMap<String, Uint128>,
+  pub(crate) markets: BTreeMap<String, PositionMarket>,
+}
+
+pub(crate) fn base_denom(u_denom: &str) -> StdResult<&str> {
+  u_denom
+    .strip_prefix(UTOKEN_PREFIX)
+    .ok_or_else(|| StdError::generic_err(format!("denom should be a uToken: {}", u_denom)))
+}
+
+fn mul_floor(amount: Uint128, rate: Decimal256) -> StdResult<Uint128> {
+  let tokens = Decimal256::from_ratio(amount, 1u128).checked_mul(rate)?;
+  Ok(Uint128::try_from(tokens.to_uint_floor())?)
+}
+
+fn amounts(coins: &[Coin], utokens: bool) -> StdResult<BTreeMap<String, Uint128>> {
+  let mut amounts = BTreeMap::new();
+  for coin in coins {
+    let denom = if utokens {
+      base_denom(&coin.denom)?
+    } else {
+      coin.denom.as_str()
+    };
+    *amounts.entry(denom.to_string()).or_default() += coin.amount;
+  }
+  Ok(amounts)
+}
+
+impl AccountPosition {
+  // new creates the position of the account balances, the prices are the
+  // oracle exchange rates by symbol denom.
+  pub fn new(
+    balances: &AccountBalancesResponse,
+    registry: &RegisteredTokensResponse,
+    prices: &ExchangeRatesResponse,
+  ) -> StdResult<Self> {
+    let prices: BTreeMap<&str, Decimal256> = prices
+      .exchange_rates
+      .iter()
+      .map(|rate| (rate.denom.as_str(), rate.amount))
+      .collect();
+    let markets = registry
+      .registry
+      .iter()
+      .map(|token| {
+        let market = PositionMarket {
+          token: token.clone(),
+          price: prices.get(token.symbol_denom()).copied(),
+          utoken_exchange_rate: Decimal256::one(),
+          historic_price: None,
+          median_deviation: None,
+        };
+        (token.base_denom().to_string(), market)
+      })
+      .collect();
+    let position = AccountPosition {
+      supplied: amounts(&balances.supplied, false)?,
+      collateral: amounts(&balances.collateral, true)?,
+      borrowed: amounts(&balances.borrowed, false)?,
+      markets,
+    };
+    for denom in position
+      .supplied
+      .keys()
+      .chain(position.collateral.keys())
+      .chain(position.borrowed.keys())
+    {
+      position.market(denom)?;
+    }
+    Ok(position)
+  }
+
+  // with_utoken_exchange_rate sets the uToken exchange rate of the base
+  // denom, the collateral uTokens are valued at that rate.
+  pub fn with_utoken_exchange_rate(mut self, denom: &str, rate: Decimal256) -> StdResult<Self> {
+    self.market_mut(denom)?.utoken_exchange_rate = rate;
+    Ok(self)
+  }
+
+  // with_market_summary sets the uToken exchange rate of the base denom
+  // from its market summary.
+  pub fn with_market_summary(
+    self,
+    denom: &str,
+    summary: &MarketSummaryResponse,
+  ) -> StdResult<Self> {
+    self.with_utoken_exchange_rate(denom, summary.utoken_exchange_rate)
+  }
+
+  pub(crate) fn market(&self, denom: &str) -> StdResult<&PositionMarket> {
+    self
+      .markets
+      .get(denom)
+      .ok_or_else(|| StdError::not_found(format!("registered Token {}", denom)))
+  }
+
+  pub(crate) fn market_mut(&mut self, denom: &str) -> StdResult<&mut PositionMarket> {
+    self
+      .markets
+      .get_mut(denom)
+      .ok_or_else(|| StdError::not_found(format!("registered Token {}", denom)))
+  }
+
+  // price returns the price of the base denom in the mode, the tokens
+  // without historic_medians are at the spot price in every mode.
+  fn price(&self, denom: &str, mode: PriceMode) -> StdResult<Decimal256> {
+    let market = self.market(denom)?;
+    let spot = market
+      .price
+      .ok_or_else(|| StdError::not_found(format!("oracle price of {}", denom)))?;
+    if mode == PriceMode::Spot || market.token.historic_medians() == 0 {
+      return Ok(spot);
+    }
+    let historic = market
+      .historic_price
+      .ok_or_else(|| StdError::not_found(format!("historic median of {}", denom)))?;
+    Ok(match mode {
+      PriceMode::Low => spot.min(historic),
+      PriceMode::High => spot.max(historic),
+      _ => historic,
+    })
+  }
+
+  // value returns the USD value of an amount of base tokens, a blacklisted
+  // token is valued at zero.
+  fn value(&self, denom: &str, amount: Uint128, mode: PriceMode) -> StdResult<Decimal256> {
+    let market = self.market(denom)?;
+    if amount.is_zero() || market.token.blacklist() {
+      return Ok(Decimal256::zero());
+    }
+    let price = self.price(denom, mode)?;
+    let amount = Decimal256::from_atomics(amount, market.token.exponent)
+      .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(amount.checked_mul(price)?)
+  }
+
+  // to_tokens converts the uTokens of the base denom to base tokens, as the
+  // chain does rounding down.
+  fn to_tokens(&self, denom: &str, u_tokens: Uint128) -> StdResult<Uint128> {
+    mul_floor(u_tokens, self.market(denom)?.utoken_exchange_rate)
+  }
+
+  // collateral returns the collateral uTokens of the account by uToken denom.
+  pub fn collateral(&self) -> Vec<Coin> {
+    self
+      .collateral
+      .iter()
+      .map(|(denom, amount)| Coin::new(amount.u128(), format!("{}{}", UTOKEN_PREFIX, denom)))
+      .collect()
+  }
+
+  // borrowed returns the borrowed tokens of the account.
+  pub fn borrowed(&self) -> Vec<Coin> {
+    self
+      .borrowed
+      .iter()
+      .map(|(denom, amount)| Coin::new(amount.u128(), denom))
+      .collect()
+  }
+
+  pub fn collateral_value(&self) -> StdResult<Decimal256> {
+    self.collateral_sum(PriceMode::Spot, |_| Decimal256::one())
+  }
+
+  pub fn borrowed_value(&self) -> StdResult<Decimal256> {
+    self.borrowed_value_at(PriceMode::Spot)
+  }
+
+  pub(crate) fn borrowed_value_at(&self, mode: PriceMode) -> StdResult<Decimal256> {
+    let mut total = Decimal256::zero();
+    for (denom, amount) in &self.borrowed {
+      total = total.checked_add(self.value(denom, *amount, mode)?)?;
+    }
+    Ok(total)
+  }
+
+  // borrow_limit is the collateral value weighted by the collateral weight
+  // of each token.
+  pub fn borrow_limit(&self) -> StdResult<Decimal256> {
+    self.borrow_limit_at(PriceMode::Spot)
+  }
+
+  pub(crate) fn borrow_limit_at(&self, mode: PriceMode) -> StdResult<Decimal256> {
+    self.collateral_sum(mode, |token| Decimal256::from(token.collateral_weight()))
+  }
+
+  // liquidation_threshold is the collateral value weighted by the
+  // liquidation threshold of each token.
+  pub fn liquidation_threshold(&self) -> StdResult<Decimal256> {
+    self.collateral_sum(PriceMode::Spot, |token| {
+      Decimal256::from(token.liquidation_threshold())
+    })
+  }
+
+  fn collateral_sum(
+    &self,
+    mode: PriceMode,
+    weight: impl Fn(&Token) -> Decimal256,
+  ) -> StdResult<Decimal256> {
+    let mut total = Decimal256::zero();
+    for (denom, u_tokens) in &self.collateral {
+      let value = self.value(denom, self.to_tokens(denom, *u_tokens)?, mode)?;
+      let token = &self.market(denom)?.token;
+      total = total.checked_add(value.checked_mul(weight(token))?)?;
+    }
+    Ok(total)
+  }
+
+  // health_factor is the liquidation threshold over the borrowed value, the
+  // account can be liquidated under 1. It is none without borrows.
+  pub fn health_factor(&self) -> StdResult<Option<Decimal256>> {
+    let borrowed_value = self.borrowed_value()?;
+    if borrowed_value.is_zero() {
+      return Ok(None);
+    }
+    Ok(Some(dec_quo(
+      self.liquidation_threshold()?,
+      borrowed_value,
+    )?))
+  }
+
+  pub fn summary(&self) -> StdResult<PositionSummary> {
+    self.summary_at(PriceMode::Spot)
+  }
+
+  // summary_at returns the summary with the position valued in the mode.
+  pub(crate) fn summary_at(&self, mode: PriceMode) -> StdResult<PositionSummary> {
+    let borrowed_value = self.borrowed_value_at(mode)?;
+    let liquidation_threshold = self.collateral_sum(mode, |token| {
+      Decimal256::from(token.liquidation_threshold())
+    })?;
+    Ok(PositionSummary {
+      collateral_value: self.collateral_sum(mode, |_| Decimal256::one())?,
+      borrowed_value,
+      borrow_limit: self.borrow_limit_at(mode)?,
+      liquidation_threshold,
+      health_factor: if borrowed_value.is_zero() {
+        None
+      } else {
+        Some(dec_quo(liquidation_threshold, borrowed_value)?)
+      },
+    })
+  }
+
+  // borrow returns the position after borrowing the asset.
+  pub fn borrow(&self, asset: &Coin) -> StdResult<Self> {
+    self.market(&asset.denom)?;
+    let mut position = self.clone();
+    *position.borrowed.entry(asset.denom.clone()).or_default() += asset.amount;
+    Ok(position)
+  }
+
+  // decollateralize returns the position after decollateralizing the
+  // uTokens.
+  pub fn decollateralize(&self, u_tokens: &Coin) -> Result<Self, ContractError> {
+    let denom = base_denom(&u_tokens.denom)?;
+    let mut position = self.clone();
+    let collateral = position.collateral.entry(denom.to_string()).or_default();
+    *collateral = collateral.checked_sub(u_tokens.amount).map_err(|_| {
+      ContractError::InsufficientCollateral {
+        asset: u_tokens.clone(),
+      }
+    })?;
+    Ok(position)
+  }
+
+  // withdraw returns the position after withdrawing the uTokens, the
+  // uTokens out of the collateral are withdrawn first and the rest is
+  // decollateralized, as the chain does.
+  pub fn withdraw(&self, u_tokens: &Coin) -> Result<Self, ContractError> {
+    let denom = base_denom(&u_tokens.denom)?;
+    let collateral = self.collateral.get(denom).copied().unwrap_or_default();
+    let supplied = self.supplied.get(denom).copied().unwrap_or_default();
+    let rate = self.market(denom)?.utoken_exchange_rate;
+    // the uTokens out of the collateral, from the supplied base tokens
+    let wallet = match supplied.checked_sub(self.to_tokens(denom, collateral)?) {
+      Ok(tokens) if !rate.is_zero() => {
+        Uint128::try_from(dec_quo(Decimal256::from_ratio(tokens, 1u128), rate)?.to_uint_floor())
+          .map_err(StdError::from)?
+      }
+      _ => Uint128::zero(),
+    };
+    let mut position = if u_tokens.amount > wallet {
+      self.decollateralize(&Coin::new(
+        (u_tokens.amount - wallet).u128(),
+        u_tokens.denom.clone(),
+      ))?
+    } else {
+      self.clone()
+    };
+    let withdrawn = self.to_tokens(denom, u_tokens.amount)?;
+    let supplied = position.supplied.entry(denom.to_string()).or_default();
+    *supplied = supplied.saturating_sub(withdrawn);
+    Ok(position)
+  }
+
+  // simulate returns the summary of the position after the leverage msg,
+  // only the Borrow, Withdraw and Decollateralize msgs change the health of
+  // the account.
+  pub fn simulate(&self, msg: &UmeeMsgLeverage) -> Result<PositionSummary, ContractError> {
+    Ok(self.apply(msg)?.summary()?)
+  }
+
+  // apply returns the position after the Borrow, Withdraw or Decollateralize
+  // msg.
+  pub(crate) fn apply(&self, msg: &UmeeMsgLeverage) -> Result<Self, ContractError> {
+    match msg {
+      UmeeMsgLeverage::Borrow(params) => Ok(self.borrow(&params.asset)?),
+      UmeeMsgLeverage::Withdraw(params) => self.withdraw(&params.asset),
+      UmeeMsgLeverage::Decollateralize(params) => self.decollateralize(&params.asset),
+      _ => Err(ContractError::CustomError {
+        val: format!("simulation of {:?} is not supported", msg),
+      }),
+    }
+  }
+}
+
+#[cfg(all(test, feature = "multitest"))]
+mod tests {
+  use super::*;
+  use crate::multitest::mock_token;
+  use crate::query_oracle::DecCoin;
+  use cosmwasm_std::{coin, coins};
+
+  fn registry() -> RegisteredTokensResponse {
+    RegisteredTokensResponse {
+      registry: vec![
+        mock_token("uumee", "UMEE", 6).with_historic_medians(3),
+        mock_token("uatom", "ATOM", 6),
+        mock_token("ujuno", "JUNO", 6).with_blacklist(true),
+      ],
+    }
+  }
+
+  fn prices(rates: &[(&str, u128)]) -> ExchangeRatesResponse {
+    ExchangeRatesResponse {
+      exchange_rates: rates
+        .iter()
+        .map(|(denom, amount)| DecCoin {
+          denom: denom.to_string(),
+          amount: Decimal256::from_ratio(*amount, 1u128),
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn position_withdraw() {
+    let balances = AccountBalancesResponse {
+      supplied: coins(3_000_000, "uumee"),
+      collateral: coins(1_000_000, "u/uumee"),
+      borrowed: vec![],
+    };
+    // 2 uumee per uToken, 500_000 uTokens are out of the collateral
+    let position = AccountPosition::new(&balances, &registry(), &prices(&[("UMEE", 1)]))
+      .unwrap()
+      .with_utoken_exchange_rate("uumee", Decimal256::percent(200))
+      .unwrap();
+
+    // the uTokens out of the collateral are withdrawn first
+    let withdrawn = position.withdraw(&coin(400_000, "u/uumee")).unwrap();
+    assert_eq!(withdrawn.collateral(), coins(1_000_000, "u/uumee"));
+    assert_eq!(withdrawn.supplied["uumee"], Uint128::new(2_200_000));
+
+    // the rest is decollateralized
+    let withdrawn = position.withdraw(&coin(700_000, "u/uumee")).unwrap();
+    assert_eq!(withdrawn.collateral(), coins(800_000, "u/uumee"));
+    assert_eq!(withdrawn.supplied["uumee"], Uint128::new(1_600_000));
+    assert_eq!(
+      withdrawn.collateral_value().unwrap(),
+      Decimal256::from_ratio(16u128, 10u128)
+    );
+
+    let res = position.withdraw(&coin(1_600_000, "u/uumee"));
+    match res {
+      Err(ContractError::InsufficientCollateral { .. }) => {}
+      _ => panic!("Must return insufficient collateral error"),
+    }
+  }
+
+  #[test]
+  fn position_values() {
+    let balances = AccountBalancesResponse {
+      supplied: vec![],
+      collateral: vec![coin(1_000_000, "u/uumee"), coin(1_000_000, "u/ujuno")],
+      borrowed: vec![],
+    };
+    // the blacklisted juno is valued at zero, without a price
+    let position = AccountPosition::new(
+      &balances,
+      &registry(),
+      &prices(&[("UMEE", 1), ("ATOM", 10)]),
+    )
+    .unwrap();
+    let summary = position.summary().unwrap();
+    assert_eq!(summary.collateral_value, Decimal256::one());
+    assert_eq!(summary.borrow_limit, Decimal256::percent(70));
+    assert_eq!(summary.liquidation_threshold, Decimal256::percent(80));
+
+    // without borrows there is no health factor
+    assert_eq!(summary.health_factor, None);
+    assert_eq!(position.health_factor().unwrap(), None);
+    assert!(summary.is_healthy());
+    assert!(!summary.is_liquidatable());
+
+    let borrowed = position.borrow(&coin(50_000, "uatom")).unwrap();
+    let summary = borrowed.summary().unwrap();
+    assert_eq!(summary.borrowed_value, Decimal256::percent(50));
+    assert_eq!(summary.health_factor, Some(Decimal256::percent(160)));
+
+    // a token without a price can't be valued
+    let position = AccountPosition::new(&balances, &registry(), &prices(&[("ATOM", 10)])).unwrap();
+    position.summary().unwrap_err();
+    let borrowed = AccountPosition::new(
+      &AccountBalancesResponse {
+        supplied: vec![],
+        collateral: vec![],
+        borrowed: coins(50_000, "uatom"),
+      },
+      &registry(),
+      &prices(&[("UMEE", 1)]),
+    )
+    .unwrap();
+    borrowed.borrowed_value().unwrap_err();
+  }
+
+  #[test]
+  fn position_price_modes() {
+    let balances = AccountBalancesResponse {
+      supplied: vec![],
+      collateral: coins(1_000_000, "u/uumee"),
+      borrowed: coins(50_000, "uatom"),
+    };
+    let position = AccountPosition::new(
+      &balances,
+      &registry(),
+      &prices(&[("UMEE", 1), ("ATOM", 10)]),
+    )
+    .unwrap();
+    // the historic mode needs the historic median of umee
+    position.summary_at(PriceMode::Historic).unwrap_err();
+
+    let mut position = position;
+    position.market_mut("uumee").unwrap().historic_price = Some(Decimal256::percent(80));
+    let collateral_value = |mode| position.summary_at(mode).unwrap().collateral_value;
+    assert_eq!(collateral_value(PriceMode::Spot), Decimal256::one());
+    assert_eq!(
+      collateral_value(PriceMode::Historic),
+      Decimal256::percent(80)
+    );
+    assert_eq!(collateral_value(PriceMode::Low), Decimal256::percent(80));
+    assert_eq!(collateral_value(PriceMode::High), Decimal256::one());
+
+    // atom has no historic medians, it is at the spot price in every mode
+    for mode in [PriceMode::Spot, PriceMode::Historic, PriceMode::Low] {
+      assert_eq!(
+        position.summary_at(mode).unwrap().borrowed_value,
+        Decimal256::percent(50)
+      );
+    }
+    assert_eq!(
+      position
+        .summary_at(PriceMode::Historic)
+        .unwrap()
+        .health_factor,
+      Some(Decimal256::from_ratio(128u128, 100u128))
+    );
+  }
+}
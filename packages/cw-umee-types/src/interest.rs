@@ -0,0 +1,380 @@
+use crate::leverage_parameters::LeverageParameters;
+use crate::query_leverage::MarketSummaryResponse;
+use crate::token::Token;
+use cosmwasm_std::{Decimal256, DivideByZeroError, StdError, StdResult, Uint256, Uint512};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Defines the interest rate model of the x/leverage module. The math follows
+// the sdk.Dec arithmetic of the chain, 18 decimal places where the results
+// of Mul and Quo are rounded half to even, so the simulated rates match the
+// rates the chain derives to the last decimal place.
+
+// DEC_PRECISION is the 10^18 scale of the sdk.Dec and Decimal256 atomics.
+const DEC_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+// chop_precision_and_round removes the 18 rightmost digits of the value,
+// rounding the removed digits half to even as the sdk.Dec does.
+fn chop_precision_and_round(value: Uint512) -> Uint512 {
+  let precision = Uint512::from(DEC_PRECISION);
+  let quotient = value / precision;
+  let remainder = value % precision;
+  let half = precision / Uint512::from(2u8);
+  if remainder < half {
+    return quotient;
+  }
+  if remainder > half || quotient % Uint512::from(2u8) != Uint512::zero() {
+    return quotient + Uint512::one();
+  }
+  quotient
+}
+
+fn from_atomics(atomics: Uint512) -> StdResult<Decimal256> {
+  Ok(Decimal256::new(Uint256::try_from(atomics)?))
+}
+
+// dec_mul multiplies as the sdk.Dec Mul.
+pub fn dec_mul(a: Decimal256, b: Decimal256) -> StdResult<Decimal256> {
+  from_atomics(chop_precision_and_round(a.atomics().full_mul(b.atomics())))
+}
+
+// dec_quo divides as the sdk.Dec Quo, the quotient is truncated at 36
+// decimal places before the rounding.
+pub fn dec_quo(a: Decimal256, b: Decimal256) -> StdResult<Decimal256> {
+  if b.is_zero() {
+    return Err(StdError::divide_by_zero(DivideByZeroError::new(a)));
+  }
+  let precision = Uint512::from(DEC_PRECISION);
+  let quotient = Uint512::from(a.atomics()) * precision * precision / Uint512::from(b.atomics());
+  from_atomics(chop_precision_and_round(quotient))
+}
+
+// interpolate returns the y of the x on the line from (x_min, y_start) to
+// (x_max, y_end), the x can't be lower than x_min. As the Interpolate of the
+// chain, the ends of the line are returned as they are, without the rounding
+// of the slope.
+pub fn interpolate(
+  x: Decimal256,
+  x_min: Decimal256,
+  y_start: Decimal256,
+  x_max: Decimal256,
+  y_end: Decimal256,
+) -> StdResult<Decimal256> {
+  if x == x_min {
+    return Ok(y_start);
+  }
+  if x == x_max || x_max == x_min {
+    return Ok(y_end);
+  }
+  let delta_x = x.checked_sub(x_min)?;
+  let width = x_max.checked_sub(x_min)?;
+  // the decreasing lines are computed on the absolute slope, as the
+  // rounding of the sdk.Dec is symmetric around zero
+  if y_end >= y_start {
+    let slope = dec_quo(y_end - y_start, width)?;
+    Ok(y_start.checked_add(dec_mul(delta_x, slope)?)?)
+  } else {
+    let slope = dec_quo(y_start - y_end, width)?;
+    Ok(y_start.checked_sub(dec_mul(delta_x, slope)?)?)
+  }
+}
+
+// supply_utilization returns the borrowed share of the token supply, that
+// is the borrowed plus the available liquidity, excluding the reserves.
+// The amounts can be in base or symbol units, as long as they are the same.
+// As on the chain, a borrowed amount not lower than the supply, which
+// includes the empty markets, is a 100% utilization.
+pub fn supply_utilization(borrowed: Decimal256, available: Decimal256) -> StdResult<Decimal256> {
+  let supply = borrowed.checked_add(available)?;
+  if borrowed >= supply {
+    return Ok(Decimal256::one());
+  }
+  dec_quo(borrowed, supply)
+}
+
+// MarketLiquidity is the borrowed amount and the available liquidity of a
+// token, from which the supply utilization is derived.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+pub struct MarketLiquidity {
+  pub borrowed: Decimal256,
+  pub available: Decimal256,
+}
+
+impl MarketLiquidity {
+  pub fn new(borrowed: Decimal256, available: Decimal256) -> Self {
+    MarketLiquidity {
+      borrowed,
+      available,
+    }
+  }
+
+  // from_market_summary takes the borrowed and liquidity of the market,
+  // both in symbol units.
+  pub fn from_market_summary(summary: &MarketSummaryResponse) -> Self {
+    MarketLiquidity::new(summary.borrowed, summary.liquidity)
+  }
+
+  pub fn utilization(&self) -> StdResult<Decimal256> {
+    supply_utilization(self.borrowed, self.available)
+  }
+
+  // borrow returns the liquidity after a borrow of the amount.
+  pub fn borrow(&self, amount: Decimal256) -> StdResult<Self> {
+    Ok(MarketLiquidity::new(
+      self.borrowed.checked_add(amount)?,
+      self.available.checked_sub(amount)?,
+    ))
+  }
+
+  // repay returns the liquidity after a repay of the amount, the amount
+  // over the borrowed is not repaid.
+  pub fn repay(&self, amount: Decimal256) -> StdResult<Self> {
+    let amount = amount.min(self.borrowed);
+    Ok(MarketLiquidity::new(
+      self.borrowed - amount,
+      self.available.checked_add(amount)?,
+    ))
+  }
+
+  // supply returns the liquidity after a supply of the amount.
+  pub fn supply(&self, amount: Decimal256) -> StdResult<Self> {
+    Ok(MarketLiquidity::new(
+      self.borrowed,
+      self.available.checked_add(amount)?,
+    ))
+  }
+
+  // withdraw returns the liquidity after a withdraw of the amount.
+  pub fn withdraw(&self, amount: Decimal256) -> StdResult<Self> {
+    Ok(MarketLiquidity::new(
+      self.borrowed,
+      self.available.checked_sub(amount)?,
+    ))
+  }
+}
+
+// InterestRates are the supply utilization of a token and the interest
+// rates derived from it.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+pub struct InterestRates {
+  pub utilization: Decimal256,
+  pub borrow_apy: Decimal256,
+  pub supply_apy: Decimal256,
+}
+
+impl Token {
+  // borrow_apy returns the borrow interest rate of the token at the
+  // utilization, following the piecewise-linear curve from the base rate
+  // at 0% to the kink rate at the kink utilization and the max rate at
+  // 100%, as the DeriveBorrowAPY of the chain. The blacklisted tokens don't
+  // accrue interest.
+  pub fn borrow_apy(&self, utilization: Decimal256) -> StdResult<Decimal256> {
+    if self.blacklist() {
+      return Ok(Decimal256::zero());
+    }
+    let kink_utilization = Decimal256::from(self.kink_utilization);
+    let kink_borrow_rate = Decimal256::from(self.kink_borrow_rate);
+    if utilization >= Decimal256::one() {
+      return Ok(Decimal256::from(self.max_borrow_rate));
+    }
+    if utilization >= kink_utilization {
+      return interpolate(
+        utilization,
+        kink_utilization,
+        kink_borrow_rate,
+        Decimal256::one(),
+        Decimal256::from(self.max_borrow_rate),
+      );
+    }
+    interpolate(
+      utilization,
+      Decimal256::zero(),
+      Decimal256::from(self.base_borrow_rate),
+      kink_utilization,
+      kink_borrow_rate,
+    )
+  }
+
+  // supply_apy returns the supply interest rate of the token at the
+  // utilization, the interest paid by the borrowers spread over the supply
+  // and reduced by the reserve factor and the oracle reward factor.
+  pub fn supply_apy(
+    &self,
+    utilization: Decimal256,
+    params: &LeverageParameters,
+  ) -> StdResult<Decimal256> {
+    let borrow_apy = self.borrow_apy(utilization)?;
+    let reduction = params
+      .oracle_reward_factor
+      .checked_add(Decimal256::from(self.reserve_factor))?;
+    // a reduction over 100% leaves no interest to the suppliers
+    let kept = Decimal256::one().saturating_sub(reduction);
+    dec_mul(dec_mul(borrow_apy, utilization)?, kept)
+  }
+
+  // interest_rates returns the rates of the token with the liquidity.
+  pub fn interest_rates(
+    &self,
+    liquidity: &MarketLiquidity,
+    params: &LeverageParameters,
+  ) -> StdResult<InterestRates> {
+    let utilization = liquidity.utilization()?;
+    Ok(InterestRates {
+      utilization,
+      borrow_apy: self.borrow_apy(utilization)?,
+      supply_apy: self.supply_apy(utilization, params)?,
+    })
+  }
+
+  // project_borrow returns the rates of the token after a borrow of the
+  // amount, in the units of the liquidity.
+  pub fn project_borrow(
+    &self,
+    liquidity: &MarketLiquidity,
+    amount: Decimal256,
+    params: &LeverageParameters,
+  ) -> StdResult<InterestRates> {
+    self.interest_rates(&liquidity.borrow(amount)?, params)
+  }
+
+  // project_supply returns the rates of the token after a supply of the
+  // amount, in the units of the liquidity.
+  pub fn project_supply(
+    &self,
+    liquidity: &MarketLiquidity,
+    amount: Decimal256,
+    params: &LeverageParameters,
+  ) -> StdResult<InterestRates> {
+    self.interest_rates(&liquidity.supply(amount)?, params)
+  }
+}
+
+#[cfg(all(test, feature = "multitest"))]
+mod tests {
+  use super::*;
+  use crate::multitest::{mock_leverage_parameters, mock_token};
+
+  #[test]
+  fn interest_rate_model() {
+    let token = mock_token("uumee", "UMEE", 6);
+    let params = mock_leverage_parameters();
+    let liquidity = MarketLiquidity::new(
+      Decimal256::from_ratio(500_000u128, 1u128),
+      Decimal256::from_ratio(500_000u128, 1u128),
+    );
+
+    // 2% base rate plus half of the way to the 20% kink rate at 80%
+    let rates = token.interest_rates(&liquidity, &params).unwrap();
+    assert_eq!(rates.utilization, Decimal256::percent(50));
+    assert_eq!(
+      rates.borrow_apy,
+      Decimal256::from_ratio(1_325u128, 10_000u128)
+    );
+    // the 10% reserve factor and the 1% oracle reward factor are deducted
+    assert_eq!(
+      rates.supply_apy,
+      Decimal256::from_ratio(589_625u128, 10_000_000u128)
+    );
+
+    let rates = token
+      .project_borrow(
+        &liquidity,
+        Decimal256::from_ratio(300_000u128, 1u128),
+        &params,
+      )
+      .unwrap();
+    assert_eq!(rates.utilization, Decimal256::percent(80));
+    assert_eq!(rates.borrow_apy, Decimal256::percent(20));
+
+    // over the kink the rate goes up to the 150% max rate
+    let rates = token
+      .project_borrow(
+        &liquidity,
+        Decimal256::from_ratio(400_000u128, 1u128),
+        &params,
+      )
+      .unwrap();
+    assert_eq!(rates.borrow_apy, Decimal256::percent(85));
+    token
+      .project_borrow(
+        &liquidity,
+        Decimal256::from_ratio(500_001u128, 1u128),
+        &params,
+      )
+      .unwrap_err();
+
+    // a third of utilization is rounded as the sdk.Dec
+    let rates = token
+      .project_supply(
+        &liquidity,
+        Decimal256::from_ratio(500_000u128, 1u128),
+        &params,
+      )
+      .unwrap();
+    assert_eq!(rates.utilization.to_string(), "0.333333333333333333");
+    assert_eq!(rates.borrow_apy.to_string(), "0.095");
+  }
+
+  #[test]
+  fn interest_rate_bounds() {
+    let token = mock_token("uumee", "UMEE", 6);
+    let params = mock_leverage_parameters();
+
+    // an empty market is fully utilized, as on the chain
+    let rates = token
+      .interest_rates(
+        &MarketLiquidity::new(Decimal256::zero(), Decimal256::zero()),
+        &params,
+      )
+      .unwrap();
+    assert_eq!(rates.utilization, Decimal256::one());
+    assert_eq!(rates.borrow_apy, Decimal256::percent(150));
+
+    // no liquidity left is a 100% utilization
+    let rates = token
+      .interest_rates(
+        &MarketLiquidity::new(Decimal256::percent(100), Decimal256::zero()),
+        &params,
+      )
+      .unwrap();
+    assert_eq!(rates.utilization, Decimal256::one());
+    assert_eq!(rates.borrow_apy, Decimal256::percent(150));
+    // the 11% of the 150% paid by the borrowers is deducted
+    assert_eq!(rates.supply_apy, Decimal256::permille(1335));
+
+    // at the kink the rate is the kink rate
+    let rates = token
+      .interest_rates(
+        &MarketLiquidity::new(Decimal256::percent(80), Decimal256::percent(20)),
+        &params,
+      )
+      .unwrap();
+    assert_eq!(rates.utilization, Decimal256::percent(80));
+    assert_eq!(rates.borrow_apy, Decimal256::percent(20));
+
+    // the ends of the line aren't rounded through the slope
+    let third = dec_quo(Decimal256::one(), Decimal256::percent(300)).unwrap();
+    assert_eq!(
+      interpolate(
+        third,
+        third,
+        Decimal256::percent(7),
+        Decimal256::one(),
+        Decimal256::percent(9),
+      )
+      .unwrap(),
+      Decimal256::percent(7)
+    );
+    assert_eq!(
+      interpolate(
+        third,
+        Decimal256::zero(),
+        Decimal256::percent(7),
+        third,
+        Decimal256::percent(9),
+      )
+      .unwrap(),
+      Decimal256::percent(9)
+    );
+  }
+}
@@ -3,26 +3,78 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // LeverageParameters defines the parameters for the leverage module.
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
 pub struct LeverageParameters {
   // The complete_liquidation_threshold determines how far over their borrow
   // limit a borrower must be in order for their positions to be liquidated
   // fully in a single event.
-  complete_liquidation_threshold: Decimal256,
+  pub(crate) complete_liquidation_threshold: Decimal256,
   // The minimum_close_factor determines the portion of a borrower's position
   // that can be liquidated in a single event, when the borrower is just barely
   // over their borrow limit.
-  minimum_close_factor: Decimal256,
+  pub(crate) minimum_close_factor: Decimal256,
   // The oracle_reward_factor determines the portion of interest accrued on
   // borrows that is sent to the oracle module to fund its reward pool.
-  oracle_reward_factor: Decimal256,
+  pub(crate) oracle_reward_factor: Decimal256,
   // The small_liquidation_size determines the USD value at which a borrow is
   // considered small enough to be liquidated in a single transaction, bypassing
   // dynamic close factor.
-  small_liquidation_size: Decimal256,
+  pub(crate) small_liquidation_size: Decimal256,
   // Direct Liquidation Fee is a reduction factor in liquidation incentive
   // experienced by liquidators who choose to receive base assets instead of
   // uTokens as liquidation rewards.
   // Valid values: 0-1.
-  direct_liquidation_fee: Decimal256,
+  pub(crate) direct_liquidation_fee: Decimal256,
+}
+
+impl LeverageParameters {
+  pub fn complete_liquidation_threshold(&self) -> Decimal256 {
+    self.complete_liquidation_threshold
+  }
+
+  pub fn minimum_close_factor(&self) -> Decimal256 {
+    self.minimum_close_factor
+  }
+
+  pub fn oracle_reward_factor(&self) -> Decimal256 {
+    self.oracle_reward_factor
+  }
+
+  pub fn small_liquidation_size(&self) -> Decimal256 {
+    self.small_liquidation_size
+  }
+
+  pub fn direct_liquidation_fee(&self) -> Decimal256 {
+    self.direct_liquidation_fee
+  }
+
+  // the with_ builders set a field over the Default zero values, to build
+  // the parameters of the tests.
+  pub fn with_complete_liquidation_threshold(
+    mut self,
+    complete_liquidation_threshold: Decimal256,
+  ) -> Self {
+    self.complete_liquidation_threshold = complete_liquidation_threshold;
+    self
+  }
+
+  pub fn with_minimum_close_factor(mut self, minimum_close_factor: Decimal256) -> Self {
+    self.minimum_close_factor = minimum_close_factor;
+    self
+  }
+
+  pub fn with_oracle_reward_factor(mut self, oracle_reward_factor: Decimal256) -> Self {
+    self.oracle_reward_factor = oracle_reward_factor;
+    self
+  }
+
+  pub fn with_small_liquidation_size(mut self, small_liquidation_size: Decimal256) -> Self {
+    self.small_liquidation_size = small_liquidation_size;
+    self
+  }
+
+  pub fn with_direct_liquidation_fee(mut self, direct_liquidation_fee: Decimal256) -> Self {
+    self.direct_liquidation_fee = direct_liquidation_fee;
+    self
+  }
 }
@@ -2,32 +2,62 @@ pub mod aggregate_exchange_rate_prevote;
 pub mod aggregate_exchange_rate_vote;
 pub mod bad_debt;
 pub mod error;
+pub mod error_parse;
+pub mod health;
+pub mod interest;
 pub mod leverage_parameters;
+pub mod liquidation;
+#[cfg(feature = "multitest")]
+pub mod mock_oracle;
 pub mod msg;
+pub mod msg_incentive;
 pub mod msg_leverage;
+pub mod msg_metoken;
+pub mod msg_result;
+#[cfg(feature = "stargate")]
+pub mod msg_stargate;
+#[cfg(feature = "multitest")]
+pub mod multitest;
 pub mod oracle_parameters;
+#[cfg(feature = "stargate")]
+pub mod proto;
+pub mod querier;
 pub mod query;
 pub mod query_incentive;
 pub mod query_leverage;
 pub mod query_metoken;
 pub mod query_oracle;
+#[cfg(feature = "stargate")]
+pub mod query_stargate;
+pub mod simulate;
 pub mod token;
 
 pub use aggregate_exchange_rate_prevote::AggregateExchangeRatePrevote;
 pub use aggregate_exchange_rate_vote::{AggregateExchangeRateVote, ExchangeRateTuple};
 pub use bad_debt::BadDebt;
+pub use error_parse::{parse_chain_error, ChainError};
+pub use health::{AccountPosition, PositionSummary};
+pub use interest::{InterestRates, MarketLiquidity};
 pub use leverage_parameters::LeverageParameters;
+pub use liquidation::LiquidationPlan;
 pub use oracle_parameters::{Denom, OracleParameters};
+pub use simulate::{HealthConstraint, HistoricSimulation};
 pub use token::Token;
 
 pub use query::{StructUmeeQuery, UmeeQuery};
 
+#[cfg(feature = "stargate")]
+pub use querier::QueryTransport;
+pub use querier::UmeeQuerier;
+#[cfg(feature = "stargate")]
+pub use query_stargate::StargateQuery;
+
 pub use query_leverage::{
   AccountBalancesParams, AccountBalancesResponse, AccountSummaryParams, AccountSummaryResponse,
   BadDebtsParams, BadDebtsResponse, LeverageParametersParams, LeverageParametersResponse,
   LiquidationTargetsParams, LiquidationTargetsResponse, MarketSummaryParams, MarketSummaryResponse,
-  MaxBorrowParams, MaxWithdrawParams, MaxWithdrawResponse, RegisteredTokensParams,
-  RegisteredTokensResponse, UmeeQueryLeverage,
+  MaxBorrowParams, MaxBorrowResponse, MaxWithdrawParams, MaxWithdrawResponse,
+  RegisteredTokensParams, RegisteredTokensResponse, UmeeQueryLeverage,
 };
 
 pub use query_oracle::{
@@ -40,17 +70,29 @@ pub use query_oracle::{
   SlashWindowParams, SlashWindowResponse, UmeeQueryOracle,
 };
 
+pub use msg_incentive::{
+  BeginUnbondingParams, BondParams, ClaimParams, EmergencyUnbondParams, SponsorParams,
+  UmeeMsgIncentive,
+};
 pub use msg_leverage::{
   BorrowParams, CollateralizeParams, DecollateralizeParams, LiquidateParams, MsgMaxBorrowParams,
   MsgMaxWithdrawParams, MsgTypes, RepayParams, SupplyCollateralParams, SupplyParams,
   UmeeMsgLeverage, WithdrawParams,
 };
+pub use msg_metoken::{RedeemParams, SwapParams, UmeeMsgMeToken};
+pub use msg_result::{
+  parse_reply, BorrowResult, LeverageResult, LiquidateResult, MsgResult, RepayResult,
+  SupplyCollateralResult, SupplyResult, WithdrawResult,
+};
 pub use query_incentive::UmeeQueryIncentive;
 
-pub use msg::{StructUmeeMsg, UmeeMsg};
+pub use msg::{msg_batch, BatchStep, StructUmeeMsg, UmeeMsg};
 
 // This is a signal, such that any contract that imports these helpers will only run on the
 // umee blockchain, it makes mandatory that the blockchain have the "umee" inside
-// the supported features when instantiating a new wasm keeper
+// the supported features when instantiating a new wasm keeper.
+// It is also exported with the stargate feature, because the StructUmeeMsg
+// constructors still send the msgs through the custom wasm binding, only the
+// msgs encoded with UmeeMsgLeverage::to_stargate don't need it.
 #[no_mangle]
 extern "C" fn requires_umee() {}
@@ -0,0 +1,398 @@
+use crate::error::ContractError;
+use crate::health::{base_denom, AccountPosition, PositionMarket, UTOKEN_PREFIX};
+use crate::interest::{dec_mul, dec_quo, interpolate};
+use crate::leverage_parameters::LeverageParameters;
+use crate::msg_leverage::LiquidateParams;
+use cosmwasm_std::{Addr, Coin, Decimal256, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+// Defines the liquidation math of the x/leverage module, the close factor
+// that limits the repayment of a liquidation and the reward the liquidator
+// receives for it, on the sdk.Dec arithmetic of the chain.
+
+// close_factor returns the portion of the borrowed value that can be repaid
+// in a single liquidation, as the ComputeCloseFactor of the chain. It is
+// zero for a borrower under the liquidation threshold and grows from the minimum close factor, when the borrower is
+// just over the threshold, to 1 when the borrowed value reaches the
+// complete liquidation threshold of the way from the liquidation threshold
+// to the collateral value. The small borrows are liquidated completely.
+pub fn close_factor(
+  borrowed_value: Decimal256,
+  collateral_value: Decimal256,
+  liquidation_threshold: Decimal256,
+  params: &LeverageParameters,
+) -> StdResult<Decimal256> {
+  if borrowed_value < liquidation_threshold {
+    return Ok(Decimal256::zero());
+  }
+  if borrowed_value <= params.small_liquidation_size || liquidation_threshold >= collateral_value {
+    return Ok(Decimal256::one());
+  }
+  let portion_over_limit = dec_quo(
+    borrowed_value - liquidation_threshold,
+    collateral_value - liquidation_threshold,
+  )?;
+  if portion_over_limit >= params.complete_liquidation_threshold {
+    return Ok(Decimal256::one());
+  }
+  interpolate(
+    portion_over_limit,
+    Decimal256::zero(),
+    params.minimum_close_factor,
+    params.complete_liquidation_threshold,
+    Decimal256::one(),
+  )
+}
+
+// LiquidationPlan is the result of a liquidation, the repayment of the
+// liquidator, the collateral uTokens the borrower loses and the reward of
+// the liquidator, in uTokens or base tokens for a direct liquidation. The
+// profit is the USD value of the reward over the value of the repayment.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LiquidationPlan {
+  pub repayment: Coin,
+  pub collateral_burn: Coin,
+  pub reward: Coin,
+  pub repayment_value: Decimal256,
+  pub reward_value: Decimal256,
+  pub profit: Decimal256,
+}
+
+impl LiquidationPlan {
+  // liquidate_params returns the params of the liquidation of the borrower.
+  pub fn liquidate_params(&self, borrower: Addr) -> LiquidateParams {
+    LiquidateParams {
+      borrower,
+      repayment: self.repayment.clone(),
+      reward: self.reward.clone(),
+    }
+  }
+}
+
+// base_price returns the USD price of one base token of the market, a
+// missing or zero oracle price is stale.
+fn base_price(denom: &str, market: &PositionMarket) -> Result<Decimal256, ContractError> {
+  let price = match market.price {
+    Some(price) if !price.is_zero() && !market.token.blacklist() => price,
+    _ => {
+      return Err(ContractError::OracleStale {
+        denom: denom.to_string(),
+      })
+    }
+  };
+  let units = Decimal256::from_ratio(10u128.pow(market.token.exponent), 1u128);
+  Ok(dec_quo(price, units)?)
+}
+
+fn dec(amount: Uint128) -> Decimal256 {
+  Decimal256::from_ratio(amount, 1u128)
+}
+
+fn floor(value: Decimal256) -> StdResult<Uint128> {
+  Ok(Uint128::try_from(value.to_uint_floor())?)
+}
+
+fn ceil(value: Decimal256) -> StdResult<Uint128> {
+  Ok(Uint128::try_from(value.to_uint_ceil())?)
+}
+
+impl AccountPosition {
+  // close_factor returns the close factor of the position.
+  pub fn close_factor(&self, params: &LeverageParameters) -> StdResult<Decimal256> {
+    let summary = self.summary()?;
+    close_factor(
+      summary.borrowed_value,
+      summary.collateral_value,
+      summary.liquidation_threshold,
+      params,
+    )
+  }
+
+  // liquidation returns the liquidation of the borrowed repay_denom for the
+  // collateral of the reward_denom. The reward is paid in uTokens for a
+  // uToken reward_denom, or in base tokens for a base reward_denom with the
+  // liquidation incentive reduced by the direct liquidation fee.
+  //
+  // The amounts follow the getLiquidationAmounts and ComputeLiquidation of
+  // the chain: the repayment is the borrowed amount, or the max_repay of the
+  // liquidator, truncated to the close factor, then reduced with the reward
+  // and the collateral burn by the ratio that keeps them under the
+  // collateral. The repayment is rounded up, the collateral burn and the
+  // reward are truncated. The direct rewards are also limited by the
+  // liquidity of the leverage module on chain, that is not part of the
+  // position.
+  pub fn liquidation(
+    &self,
+    params: &LeverageParameters,
+    repay_denom: &str,
+    reward_denom: &str,
+    max_repay: Option<Uint128>,
+  ) -> Result<LiquidationPlan, ContractError> {
+    let (collateral_denom, direct) = match reward_denom.strip_prefix(UTOKEN_PREFIX) {
+      Some(denom) => (denom, false),
+      None => (reward_denom, true),
+    };
+    let repay_market = self.market(repay_denom)?;
+    let reward_market = self.market(collateral_denom)?;
+
+    let summary = self.summary()?;
+    let close_factor = close_factor(
+      summary.borrowed_value,
+      summary.collateral_value,
+      summary.liquidation_threshold,
+      params,
+    )?;
+    let mut available_repay = self.borrowed.get(repay_denom).copied().unwrap_or_default();
+    if let Some(max_repay) = max_repay {
+      available_repay = available_repay.min(max_repay);
+    }
+    let available_collateral = self
+      .collateral
+      .get(collateral_denom)
+      .copied()
+      .unwrap_or_default();
+    let nothing_to_liquidate = || ContractError::CustomError {
+      val: format!(
+        "nothing to liquidate for {} and {}",
+        repay_denom, reward_denom
+      ),
+    };
+    if close_factor.is_zero() || available_repay.is_zero() || available_collateral.is_zero() {
+      return Err(nothing_to_liquidate());
+    }
+
+    let mut incentive = Decimal256::from(reward_market.token.liquidation_incentive);
+    if direct {
+      let fee = Decimal256::one().saturating_sub(params.direct_liquidation_fee);
+      incentive = dec_mul(incentive, fee)?;
+    }
+    let repay_price = base_price(repay_denom, repay_market)?;
+    let reward_price = base_price(collateral_denom, reward_market)?;
+    let exchange_rate = reward_market.utoken_exchange_rate;
+
+    // the repaid value can't exceed the close factor of the borrowed value
+    let repay_value = dec_mul(dec(available_repay), repay_price)?;
+    let max_repay_value = dec_mul(summary.borrowed_value, close_factor)?;
+    if repay_value > max_repay_value {
+      available_repay = floor(dec_quo(
+        dec_mul(dec(available_repay), max_repay_value)?,
+        repay_value,
+      )?)?;
+    }
+    let available_reward = floor(dec_mul(dec(available_collateral), exchange_rate)?)?;
+
+    let mut max_repay = dec(available_repay);
+    let mut max_reward = dec_quo(
+      dec_mul(
+        dec_mul(max_repay, repay_price)?,
+        Decimal256::one() + incentive,
+      )?,
+      reward_price,
+    )?;
+    let mut max_collateral = dec_quo(max_reward, exchange_rate)?;
+    if max_repay.is_zero()
+      || max_reward.is_zero()
+      || max_collateral.is_zero()
+      || available_reward.is_zero()
+    {
+      return Err(nothing_to_liquidate());
+    }
+    // the most severe of the limits reduces the three amounts
+    let ratio = Decimal256::one()
+      .min(dec_quo(dec(available_collateral), max_collateral)?)
+      .min(dec_quo(dec(available_reward), max_reward)?);
+    max_repay = dec_mul(max_repay, ratio)?;
+    max_collateral = dec_mul(max_collateral, ratio)?;
+    max_reward = dec_mul(max_reward, ratio)?;
+
+    let repayment = ceil(max_repay)?;
+    let collateral_burn = floor(max_collateral)?;
+    let reward_tokens = floor(max_reward)?;
+    let reward = if direct {
+      Coin::new(reward_tokens.u128(), collateral_denom)
+    } else {
+      Coin::new(collateral_burn.u128(), reward_denom)
+    };
+    let repayment_value = dec_mul(dec(repayment), repay_price)?;
+    let reward_value = dec_mul(dec(reward_tokens), reward_price)?;
+    Ok(LiquidationPlan {
+      repayment: Coin::new(repayment.u128(), repay_denom),
+      collateral_burn: Coin::new(
+        collateral_burn.u128(),
+        format!("{}{}", UTOKEN_PREFIX, collateral_denom),
+      ),
+      reward,
+      repayment_value,
+      reward_value,
+      profit: reward_value.saturating_sub(repayment_value),
+    })
+  }
+
+  // liquidations returns the liquidations of every borrowed denom for every
+  // collateral, rewarded in uTokens and in base tokens, the most profitable
+  // first. It is empty for a healthy borrower.
+  pub fn liquidations(
+    &self,
+    params: &LeverageParameters,
+    max_repay: Option<Uint128>,
+  ) -> StdResult<Vec<LiquidationPlan>> {
+    if self.close_factor(params)?.is_zero() {
+      return Ok(vec![]);
+    }
+    let mut plans = vec![];
+    for repay_denom in self.borrowed.keys() {
+      for u_denom in self.collateral() {
+        let collateral_denom = base_denom(&u_denom.denom)?;
+        for reward_denom in [u_denom.denom.as_str(), collateral_denom] {
+          // the blacklisted and unpriced tokens can't be liquidated
+          if let Ok(plan) = self.liquidation(params, repay_denom, reward_denom, max_repay) {
+            plans.push(plan);
+          }
+        }
+      }
+    }
+    plans.sort_by_key(|plan| Reverse(plan.profit));
+    Ok(plans)
+  }
+}
+
+#[cfg(all(test, feature = "multitest"))]
+mod tests {
+  use super::*;
+  use crate::multitest::{mock_leverage_parameters, mock_token};
+  use crate::query_leverage::{AccountBalancesResponse, RegisteredTokensResponse};
+  use crate::query_oracle::{DecCoin, ExchangeRatesResponse};
+  use cosmwasm_std::{coin, coins};
+
+  #[test]
+  fn liquidation_plan() {
+    let registry = RegisteredTokensResponse {
+      registry: vec![
+        mock_token("uumee", "UMEE", 6),
+        mock_token("uatom", "ATOM", 6),
+      ],
+    };
+    let prices = |atom_price| ExchangeRatesResponse {
+      exchange_rates: vec![
+        DecCoin {
+          denom: String::from("UMEE"),
+          amount: Decimal256::one(),
+        },
+        DecCoin {
+          denom: String::from("ATOM"),
+          amount: Decimal256::from_ratio(atom_price, 1u128),
+        },
+      ],
+    };
+    let balances = AccountBalancesResponse {
+      supplied: coins(1_000_000, "uumee"),
+      collateral: coins(1_000_000, "u/uumee"),
+      borrowed: coins(60_000, "uatom"),
+    };
+    let params = mock_leverage_parameters();
+
+    // 0.84 borrowed under the 0.8 liquidation threshold
+    let position = AccountPosition::new(&balances, &registry, &prices(10u128)).unwrap();
+    assert!(position.liquidations(&params, None).unwrap().is_empty());
+
+    // 0.84 borrowed over the 0.8 liquidation threshold, below the small
+    // liquidation size the borrow is liquidated completely
+    let position = AccountPosition::new(&balances, &registry, &prices(14u128)).unwrap();
+    assert_eq!(position.close_factor(&params).unwrap(), Decimal256::one());
+    let plans = position.liquidations(&params, None).unwrap();
+    assert_eq!(plans.len(), 2);
+    // the uToken reward has the 10% liquidation incentive
+    assert_eq!(plans[0].repayment, coin(60_000, "uatom"));
+    assert_eq!(plans[0].reward, coin(924_000, "u/uumee"));
+    assert_eq!(plans[0].profit, Decimal256::permille(84));
+    // the direct reward has the incentive reduced by the 5% fee
+    assert_eq!(plans[1].reward, coin(919_800, "uumee"));
+    assert_eq!(plans[1].collateral_burn, coin(919_800, "u/uumee"));
+
+    let plan = position
+      .liquidation(&params, "uatom", "u/uumee", Some(Uint128::new(10_000)))
+      .unwrap();
+    let liquidate = plan.liquidate_params(Addr::unchecked("borrower"));
+    assert_eq!(liquidate.repayment, coin(10_000, "uatom"));
+    assert_eq!(liquidate.reward, coin(154_000, "u/uumee"));
+  }
+
+  #[test]
+  fn liquidation_bounds() {
+    let registry = RegisteredTokensResponse {
+      registry: vec![
+        mock_token("uumee", "UMEE", 6),
+        mock_token("uatom", "ATOM", 6),
+      ],
+    };
+    let prices = ExchangeRatesResponse {
+      exchange_rates: vec![
+        DecCoin {
+          denom: String::from("UMEE"),
+          amount: Decimal256::one(),
+        },
+        DecCoin {
+          denom: String::from("ATOM"),
+          amount: Decimal256::from_ratio(10u128, 1u128),
+        },
+      ],
+    };
+    let params = mock_leverage_parameters();
+
+    // a borrow at the liquidation threshold is liquidated with the minimum
+    // close factor, as the portion over the limit is zero
+    let threshold = Decimal256::from_ratio(800u128, 1u128);
+    let collateral_value = Decimal256::from_ratio(1_000u128, 1u128);
+    assert_eq!(
+      close_factor(threshold, collateral_value, threshold, &params).unwrap(),
+      Decimal256::percent(5)
+    );
+    let under = threshold - Decimal256::permille(1);
+    assert_eq!(
+      close_factor(under, collateral_value, threshold, &params).unwrap(),
+      Decimal256::zero()
+    );
+
+    // 800 USD borrowed at the 800 USD liquidation threshold
+    let balances = AccountBalancesResponse {
+      supplied: vec![],
+      collateral: coins(1_000_000_000, "u/uumee"),
+      borrowed: coins(80_000_000, "uatom"),
+    };
+    let position = AccountPosition::new(&balances, &registry, &prices).unwrap();
+    assert!(position.summary().unwrap().is_liquidatable());
+    let plan = position
+      .liquidation(&params, "uatom", "u/uumee", None)
+      .unwrap();
+    // 5% of the borrowed value is repaid
+    assert_eq!(plan.repayment, coin(4_000_000, "uatom"));
+    assert_eq!(plan.reward, coin(44_000_000, "u/uumee"));
+
+    // the collateral limits the liquidation, the repayment is rounded up
+    // and the collateral burn and the reward are truncated
+    let balances = AccountBalancesResponse {
+      supplied: vec![],
+      collateral: coins(100_000, "u/uumee"),
+      borrowed: coins(30_000, "uatom"),
+    };
+    let position = AccountPosition::new(&balances, &registry, &prices)
+      .unwrap()
+      .with_utoken_exchange_rate("uumee", Decimal256::percent(300))
+      .unwrap();
+    let plan = position
+      .liquidation(&params, "uatom", "u/uumee", None)
+      .unwrap();
+    assert_eq!(plan.repayment, coin(27_273, "uatom"));
+    assert_eq!(plan.collateral_burn, coin(100_000, "u/uumee"));
+    assert_eq!(plan.reward, coin(100_000, "u/uumee"));
+    let plan = position
+      .liquidation(&params, "uatom", "uumee", None)
+      .unwrap();
+    // the ratio of the direct liquidation is rounded down, so is the burn
+    assert_eq!(plan.repayment, coin(27_398, "uatom"));
+    assert_eq!(plan.collateral_burn, coin(99_999, "u/uumee"));
+    assert_eq!(plan.reward, coin(299_999, "uumee"));
+  }
+}
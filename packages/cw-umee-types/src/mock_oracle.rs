@@ -0,0 +1,404 @@
+use crate::aggregate_exchange_rate_prevote::AggregateExchangeRatePrevote;
+use crate::aggregate_exchange_rate_vote::{AggregateExchangeRateVote, ExchangeRateTuple};
+use crate::oracle_parameters::OracleParameters;
+use crate::query::{StructUmeeQuery, UmeeQuery};
+use crate::query_oracle::{
+  ActiveExchangeRatesResponse, AggregatePrevoteResponse, AggregatePrevotesResponse,
+  AggregateVoteResponse, AggregateVotesResponse, DecCoin, ExchangeRatesResponse,
+  FeederDelegationResponse, MedianDeviationsParamsResponse, MediansParamsResponse,
+  MissCounterResponse, OracleParametersResponse, SlashWindowResponse, UmeeQueryOracle,
+};
+use crate::simulate::median_of_medians;
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockQuerierCustomHandlerResult, MockStorage};
+use cosmwasm_std::{
+  to_json_binary, Binary, ContractResult, Decimal256, OwnedDeps, StdError, StdResult, SystemError,
+  SystemResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+// mock_oracle_parameters returns the default x/oracle parameters.
+pub fn mock_oracle_parameters() -> OracleParameters {
+  OracleParameters {
+    vote_period: 5,
+    vote_threshold: Decimal256::percent(50),
+    reward_band: Decimal256::percent(2),
+    reward_distribution_window: 5_256_000,
+    accept_list: vec![],
+    slash_fraction: Decimal256::permille(1),
+    slash_window: 100_800,
+    min_valid_per_window: Decimal256::percent(5),
+    stamp_period: 10,
+    prune_period: 100_800,
+    median_period: 43_200,
+    historic_accept_list: vec![],
+  }
+}
+
+// PriceDrift multiplies an exchange rate by the factor once per block,
+// starting at the start_height.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PriceDrift {
+  pub factor: Decimal256,
+  pub start_height: u64,
+}
+
+// MockOracle holds the x/oracle state used by the tests, the exchange rates,
+// medians and median deviations are set per symbol denom, e.g. "UMEE".
+// It answers every UmeeQueryOracle from that state, so it can be used as the
+// custom handler of a MockQuerier or by the UmeeLeverageModule of
+// cw-multi-test.
+//
+// The exchange rates can drift every block, the rates are evaluated at the
+// height of the oracle, that is moved with advance_blocks or at_height.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct MockOracle {
+  height: u64,
+  params: OracleParameters,
+  exchange_rates: BTreeMap<String, Decimal256>,
+  drifts: BTreeMap<String, PriceDrift>,
+  medians: BTreeMap<String, Vec<Decimal256>>,
+  median_deviations: BTreeMap<String, Decimal256>,
+  feeder_delegations: BTreeMap<String, String>,
+  miss_counters: BTreeMap<String, u64>,
+  aggregate_prevotes: BTreeMap<String, AggregateExchangeRatePrevote>,
+  aggregate_votes: BTreeMap<String, AggregateExchangeRateVote>,
+}
+
+impl Default for MockOracle {
+  fn default() -> Self {
+    MockOracle::new()
+  }
+}
+
+impl MockOracle {
+  pub fn new() -> Self {
+    MockOracle {
+      height: 0,
+      params: mock_oracle_parameters(),
+      exchange_rates: BTreeMap::new(),
+      drifts: BTreeMap::new(),
+      medians: BTreeMap::new(),
+      median_deviations: BTreeMap::new(),
+      feeder_delegations: BTreeMap::new(),
+      miss_counters: BTreeMap::new(),
+      aggregate_prevotes: BTreeMap::new(),
+      aggregate_votes: BTreeMap::new(),
+    }
+  }
+
+  pub fn height(&self) -> u64 {
+    self.height
+  }
+
+  // advance_blocks moves the oracle height, the drifting exchange rates
+  // change once per block.
+  pub fn advance_blocks(&mut self, blocks: u64) {
+    self.height += blocks;
+  }
+
+  // at_height returns the oracle at the given height, it never goes back
+  // to a height before the current one.
+  pub fn at_height(&self, height: u64) -> Self {
+    let mut oracle = self.clone();
+    oracle.height = height.max(self.height);
+    oracle
+  }
+
+  pub fn set_params(&mut self, params: OracleParameters) {
+    self.params = params;
+  }
+
+  // set_exchange_rate sets the rate of the denom at the current height, a
+  // drift of the denom restarts from that rate.
+  pub fn set_exchange_rate(&mut self, denom: &str, rate: Decimal256) {
+    self.exchange_rates.insert(denom.to_string(), rate);
+    if let Some(drift) = self.drifts.get_mut(denom) {
+      drift.start_height = self.height;
+    }
+  }
+
+  // remove_exchange_rate removes the denom from the active exchange rates,
+  // as it happens when the validators stop voting for it.
+  pub fn remove_exchange_rate(&mut self, denom: &str) {
+    self.exchange_rates.remove(denom);
+    self.drifts.remove(denom);
+  }
+
+  // set_price_drift multiplies the rate of the denom by the factor every
+  // block after the current height, a factor of 1.01 raises the price 1%
+  // per block and 0.99 lowers it 1% per block.
+  pub fn set_price_drift(&mut self, denom: &str, factor: Decimal256) -> StdResult<()> {
+    let rate = self.exchange_rate(denom)?;
+    self.exchange_rates.insert(denom.to_string(), rate);
+    self.drifts.insert(
+      denom.to_string(),
+      PriceDrift {
+        factor,
+        start_height: self.height,
+      },
+    );
+    Ok(())
+  }
+
+  pub fn set_median(&mut self, denom: &str, median: Decimal256) {
+    self.set_medians(denom, vec![median]);
+  }
+
+  // set_medians sets the historic medians of the denom, the newest first as
+  // the chain lists them.
+  pub fn set_medians(&mut self, denom: &str, medians: Vec<Decimal256>) {
+    self.medians.insert(denom.to_string(), medians);
+  }
+
+  // historic_median returns the median of the newest count historic medians
+  // of the denom, of the ones available when there are fewer.
+  pub fn historic_median(&self, denom: &str, count: u32) -> Option<Decimal256> {
+    let medians = self.medians.get(denom)?;
+    median_of_medians(&medians[..medians.len().min(count as usize)])
+  }
+
+  pub fn set_median_deviation(&mut self, denom: &str, deviation: Decimal256) {
+    self.median_deviations.insert(denom.to_string(), deviation);
+  }
+
+  pub fn set_feeder_delegation(&mut self, validator: &str, feeder: &str) {
+    self
+      .feeder_delegations
+      .insert(validator.to_string(), feeder.to_string());
+  }
+
+  pub fn set_miss_counter(&mut self, validator: &str, miss_counter: u64) {
+    self
+      .miss_counters
+      .insert(validator.to_string(), miss_counter);
+  }
+
+  // set_aggregate_prevote sets the prevote hash of the validator, it is
+  // submitted at the current height.
+  pub fn set_aggregate_prevote(&mut self, validator: &str, hash: &str) {
+    self.aggregate_prevotes.insert(
+      validator.to_string(),
+      AggregateExchangeRatePrevote {
+        hash: hash.to_string(),
+        voter: validator.to_string(),
+        submit_block: self.height,
+      },
+    );
+  }
+
+  pub fn set_aggregate_vote(&mut self, validator: &str, rates: Vec<(String, Decimal256)>) {
+    self.aggregate_votes.insert(
+      validator.to_string(),
+      AggregateExchangeRateVote {
+        exchange_rate_tuples: rates
+          .into_iter()
+          .map(|(denom, exchange_rate)| ExchangeRateTuple {
+            denom,
+            exchange_rate,
+          })
+          .collect(),
+        voter: validator.to_string(),
+      },
+    );
+  }
+
+  // exchange_rate returns the rate of the denom at the oracle height.
+  pub fn exchange_rate(&self, denom: &str) -> StdResult<Decimal256> {
+    let rate = match self.exchange_rates.get(denom) {
+      Some(rate) => *rate,
+      None => return Err(StdError::not_found(format!("exchange rate of {}", denom))),
+    };
+    let drift = match self.drifts.get(denom) {
+      Some(drift) if self.height > drift.start_height => drift,
+      _ => return Ok(rate),
+    };
+    let blocks = u32::try_from(self.height - drift.start_height)
+      .map_err(|_| StdError::generic_err("price drift over too many blocks"))?;
+    let factor = drift
+      .factor
+      .checked_pow(blocks)
+      .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(rate * factor)
+  }
+
+  fn exchange_rates(&self, denom: &str) -> StdResult<Vec<DecCoin>> {
+    if !denom.is_empty() {
+      return Ok(vec![DecCoin {
+        denom: denom.to_string(),
+        amount: self.exchange_rate(denom)?,
+      }]);
+    }
+    self
+      .exchange_rates
+      .keys()
+      .map(|denom| {
+        Ok(DecCoin {
+          denom: denom.clone(),
+          amount: self.exchange_rate(denom)?,
+        })
+      })
+      .collect()
+  }
+
+  fn dec_coins(values: &BTreeMap<String, Decimal256>, denom: &str) -> Vec<DecCoin> {
+    values
+      .iter()
+      .filter(|(d, _)| denom.is_empty() || d.as_str() == denom)
+      .map(|(d, amount)| DecCoin {
+        denom: d.clone(),
+        amount: *amount,
+      })
+      .collect()
+  }
+
+  // query answers the oracle query from the oracle state.
+  pub fn query(&self, query: &UmeeQueryOracle) -> StdResult<Binary> {
+    match query {
+      UmeeQueryOracle::ExchangeRates(p) => to_json_binary(&ExchangeRatesResponse {
+        exchange_rates: self.exchange_rates(&p.denom)?,
+      }),
+      UmeeQueryOracle::ActiveExchangeRates(_) => to_json_binary(&ActiveExchangeRatesResponse {
+        active_rates: self.exchange_rates.keys().cloned().collect(),
+      }),
+      UmeeQueryOracle::FeederDelegation(p) => {
+        let validator = p.validator_addr.to_string();
+        to_json_binary(&FeederDelegationResponse {
+          feeder_addr: self
+            .feeder_delegations
+            .get(&validator)
+            .cloned()
+            .unwrap_or(validator),
+        })
+      }
+      UmeeQueryOracle::MissCounter(p) => to_json_binary(&MissCounterResponse {
+        miss_counter: self
+          .miss_counters
+          .get(p.validator_addr.as_str())
+          .copied()
+          .unwrap_or_default(),
+      }),
+      UmeeQueryOracle::SlashWindow(_) => {
+        let params = &self.params;
+        let window_progress = if params.slash_window == 0 || params.vote_period == 0 {
+          0
+        } else {
+          (self.height % params.slash_window) / params.vote_period
+        };
+        to_json_binary(&SlashWindowResponse { window_progress })
+      }
+      UmeeQueryOracle::AggregatePrevote(p) => {
+        match self.aggregate_prevotes.get(p.validator_addr.as_str()) {
+          Some(prevote) => to_json_binary(&AggregatePrevoteResponse {
+            aggregate_prevote: prevote.clone(),
+          }),
+          None => Err(StdError::not_found(format!(
+            "aggregate prevote of {}",
+            p.validator_addr
+          ))),
+        }
+      }
+      UmeeQueryOracle::AggregatePrevotes(_) => to_json_binary(&AggregatePrevotesResponse {
+        aggregate_prevotes: self.aggregate_prevotes.values().cloned().collect(),
+      }),
+      UmeeQueryOracle::AggregateVote(p) => {
+        match self.aggregate_votes.get(p.validator_addr.as_str()) {
+          Some(vote) => to_json_binary(&AggregateVoteResponse {
+            aggregate_vote: vote.clone(),
+          }),
+          None => Err(StdError::not_found(format!(
+            "aggregate vote of {}",
+            p.validator_addr
+          ))),
+        }
+      }
+      UmeeQueryOracle::AggregateVotes(_) => to_json_binary(&AggregateVotesResponse {
+        aggregate_votes: self.aggregate_votes.values().cloned().collect(),
+      }),
+      UmeeQueryOracle::OracleParameters(_) => to_json_binary(&OracleParametersResponse {
+        params: self.params.clone(),
+      }),
+      UmeeQueryOracle::Medians(p) => to_json_binary(&MediansParamsResponse {
+        medians: self
+          .medians
+          .iter()
+          .filter(|(d, _)| p.denom.is_empty() || d.as_str() == p.denom)
+          .flat_map(|(d, medians)| {
+            medians.iter().map(move |amount| DecCoin {
+              denom: d.clone(),
+              amount: *amount,
+            })
+          })
+          .collect(),
+      }),
+      UmeeQueryOracle::MedianDeviations(p) => to_json_binary(&MedianDeviationsParamsResponse {
+        median_deviations: Self::dec_coins(&self.median_deviations, &p.denom),
+      }),
+    }
+  }
+
+  // handle is the custom handler of a MockQuerier, it answers the oracle
+  // queries and rejects the queries of the other umee modules.
+  pub fn handle(&self, query: &StructUmeeQuery) -> MockQuerierCustomHandlerResult {
+    match query.umee_query() {
+      Some(UmeeQuery::Oracle(oracle_query)) => {
+        SystemResult::Ok(ContractResult::from(self.query(&oracle_query)))
+      }
+      _ => SystemResult::Err(SystemError::UnsupportedRequest {
+        kind: format!("{:?}", query),
+      }),
+    }
+  }
+}
+
+// mock_dependencies_with_oracle creates the mock deps of a contract that
+// uses the StructUmeeQuery, the oracle queries are answered by the oracle.
+pub fn mock_dependencies_with_oracle(
+  oracle: MockOracle,
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier<StructUmeeQuery>, StructUmeeQuery> {
+  OwnedDeps {
+    storage: MockStorage::default(),
+    api: MockApi::default(),
+    querier: MockQuerier::new(&[]).with_custom_handler(move |query| oracle.handle(query)),
+    custom_query_type: PhantomData,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::querier::UmeeQuerier;
+  use crate::query_oracle::{ExchangeRatesParams, MediansParams};
+  use cosmwasm_std::QuerierWrapper;
+
+  #[test]
+  fn oracle_mock_querier() {
+    let mut oracle = MockOracle::new();
+    oracle.set_exchange_rate("UMEE", Decimal256::percent(50));
+    oracle.set_median("UMEE", Decimal256::percent(48));
+    // the price of umee raises 10% per block
+    oracle
+      .set_price_drift("UMEE", Decimal256::percent(110))
+      .unwrap();
+    oracle.advance_blocks(2);
+    let deps = mock_dependencies_with_oracle(oracle);
+    let wrapper = QuerierWrapper::<StructUmeeQuery>::new(&deps.querier);
+    let querier = UmeeQuerier::custom(&wrapper);
+
+    let rates = querier
+      .exchange_rates(ExchangeRatesParams {
+        denom: String::from("UMEE"),
+      })
+      .unwrap();
+    assert_eq!(rates.exchange_rates[0].amount, Decimal256::permille(605));
+
+    let medians = querier
+      .medians(MediansParams {
+        denom: String::new(),
+      })
+      .unwrap();
+    assert_eq!(medians.medians.len(), 1);
+    assert_eq!(medians.medians[0].amount, Decimal256::percent(48));
+  }
+}
@@ -1,12 +1,17 @@
 use crate::{
   error::ContractError,
+  msg_incentive::{
+    BeginUnbondingParams, BondParams, ClaimParams, EmergencyUnbondParams, SponsorParams,
+    UmeeMsgIncentive,
+  },
   msg_leverage::{
     BorrowParams, CollateralizeParams, DecollateralizeParams, LiquidateParams, MsgMaxBorrowParams,
     MsgMaxWithdrawParams, MsgTypes, RepayParams, SupplyCollateralParams, SupplyParams,
     UmeeMsgLeverage, WithdrawParams,
   },
+  msg_metoken::{RedeemParams, SwapParams, UmeeMsgMeToken},
 };
-use cosmwasm_std::{CosmosMsg, CustomMsg, Response};
+use cosmwasm_std::{CosmosMsg, CustomMsg, Response, SubMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +25,10 @@ impl CustomMsg for StructUmeeMsg {}
 pub enum UmeeMsg {
   // Leverage wraps all the msg enums from the leverage module
   Leverage(UmeeMsgLeverage),
+  // Metoken wraps all the msg enums from the metoken module
+  Metoken(UmeeMsgMeToken),
+  // Incentive wraps all the msg enums from the incentive module
+  Incentive(UmeeMsgIncentive),
 }
 
 // StructUmeeMsg expected structure to send messages to the umee native modules.
@@ -37,6 +46,13 @@ pub struct StructUmeeMsg {
   repay: Option<RepayParams>,
   liquidate: Option<LiquidateParams>,
   supply_collateral: Option<SupplyCollateralParams>,
+  metoken_swap: Option<SwapParams>,
+  metoken_redeem: Option<RedeemParams>,
+  bond: Option<BondParams>,
+  begin_unbonding: Option<BeginUnbondingParams>,
+  emergency_unbond: Option<EmergencyUnbondParams>,
+  claim: Option<ClaimParams>,
+  sponsor: Option<SponsorParams>,
 }
 
 fn default_struct_umee_msg(m: MsgTypes) -> StructUmeeMsg {
@@ -52,15 +68,20 @@ fn default_struct_umee_msg(m: MsgTypes) -> StructUmeeMsg {
     liquidate: None,
     max_withdraw: None,
     supply_collateral: None,
+    metoken_swap: None,
+    metoken_redeem: None,
+    bond: None,
+    begin_unbonding: None,
+    emergency_unbond: None,
+    claim: None,
+    sponsor: None,
   }
 }
 
 // msg_chain sends any message in the chain native modules
 pub fn msg_chain(umee_msg: StructUmeeMsg) -> Result<Response<StructUmeeMsg>, ContractError> {
   if !umee_msg.valid() {
-    return Err(ContractError::CustomError {
-      val: String::from("invalid umee msg"),
-    });
+    return Err(ContractError::InvalidUmeeMsg {});
   }
 
   let res = Response::new()
@@ -70,6 +91,56 @@ pub fn msg_chain(umee_msg: StructUmeeMsg) -> Result<Response<StructUmeeMsg>, Con
   Ok(res)
 }
 
+// BatchStep is a msg of a batch with the optional id of the reply to the
+// step, that is only sent on success.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BatchStep {
+  pub msg: UmeeMsg,
+  pub reply_id: Option<u64>,
+}
+
+impl BatchStep {
+  pub fn new(msg: UmeeMsg) -> Self {
+    BatchStep {
+      msg,
+      reply_id: None,
+    }
+  }
+
+  pub fn with_reply(msg: UmeeMsg, reply_id: u64) -> Self {
+    BatchStep {
+      msg,
+      reply_id: Some(reply_id),
+    }
+  }
+}
+
+// msg_batch sends the msgs of the steps in order in a single response, the
+// msgs are validated before any is added. The steps never reply on error,
+// so any failed step reverts the whole batch.
+pub fn msg_batch(steps: Vec<BatchStep>) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let msgs = steps
+    .into_iter()
+    .map(|step| {
+      let umee_msg = StructUmeeMsg::from(step.msg);
+      if !umee_msg.valid() {
+        return Err(ContractError::InvalidUmeeMsg {});
+      }
+      Ok((umee_msg, step.reply_id))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let mut res = Response::new().add_attribute("method", "batch");
+  for (umee_msg, reply_id) in msgs {
+    res = res.add_attribute("step", umee_msg.assigned_str());
+    res = res.add_submessage(match reply_id {
+      Some(id) => SubMsg::reply_on_success(umee_msg, id),
+      None => SubMsg::new(umee_msg),
+    });
+  }
+  Ok(res)
+}
+
 // Defines all the implementation related to the StructUmeeMsg
 // like creating new messages structs, it is needed because
 // the fields inside the struct are private, to avoid missmatching
@@ -92,8 +163,90 @@ impl StructUmeeMsg {
       MsgTypes::AssignedMsgRepay => String::from("repay"),
       MsgTypes::AssignedMsgLiquidate => String::from("liquidate"),
       MsgTypes::AssignedMsgSupplyCollateralize => String::from("supply_collateral"),
+      MsgTypes::AssignedMsgMetokenSwap => String::from("metoken_swap"),
+      MsgTypes::AssignedMsgMetokenRedeem => String::from("metoken_redeem"),
+      MsgTypes::AssignedMsgBond => String::from("bond"),
+      MsgTypes::AssignedMsgBeginUnbonding => String::from("begin_unbonding"),
+      MsgTypes::AssignedMsgEmergencyUnbond => String::from("emergency_unbond"),
+      MsgTypes::AssignedMsgClaim => String::from("claim"),
+      MsgTypes::AssignedMsgSponsor => String::from("sponsor"),
+    }
+  }
+  // umee_msg returns the msg as the UmeeMsg enum, it is None when the
+  // params of the assigned msg are not set.
+  pub fn umee_msg(&self) -> Option<UmeeMsg> {
+    match self.assigned_msg {
+      MsgTypes::AssignedMsgSupply => self
+        .supply
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Supply(p))),
+      MsgTypes::AssignedMsgWithdraw => self
+        .withdraw
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Withdraw(p))),
+      MsgTypes::AssignedMsgMaxWithdraw => self
+        .max_withdraw
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::MaxWithdraw(p))),
+      MsgTypes::AssignedMsgCollateralize => self
+        .collateralize
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Collateralize(p))),
+      MsgTypes::AssignedMsgDecollateralize => self
+        .decollateralize
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Decollateralize(p))),
+      MsgTypes::AssignedMsgBorrow => self
+        .borrow
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(p))),
+      MsgTypes::AssignedMsgMaxBorrow => self
+        .max_borrow
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::MaxBorrow(p))),
+      MsgTypes::AssignedMsgRepay => self
+        .repay
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Repay(p))),
+      MsgTypes::AssignedMsgLiquidate => self
+        .liquidate
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::Liquidate(p))),
+      MsgTypes::AssignedMsgSupplyCollateralize => self
+        .supply_collateral
+        .clone()
+        .map(|p| UmeeMsg::Leverage(UmeeMsgLeverage::SupplyCollateral(p))),
+      MsgTypes::AssignedMsgMetokenSwap => self
+        .metoken_swap
+        .clone()
+        .map(|p| UmeeMsg::Metoken(UmeeMsgMeToken::Swap(p))),
+      MsgTypes::AssignedMsgMetokenRedeem => self
+        .metoken_redeem
+        .clone()
+        .map(|p| UmeeMsg::Metoken(UmeeMsgMeToken::Redeem(p))),
+      MsgTypes::AssignedMsgBond => self
+        .bond
+        .clone()
+        .map(|p| UmeeMsg::Incentive(UmeeMsgIncentive::Bond(p))),
+      MsgTypes::AssignedMsgBeginUnbonding => self
+        .begin_unbonding
+        .clone()
+        .map(|p| UmeeMsg::Incentive(UmeeMsgIncentive::BeginUnbonding(p))),
+      MsgTypes::AssignedMsgEmergencyUnbond => self
+        .emergency_unbond
+        .clone()
+        .map(|p| UmeeMsg::Incentive(UmeeMsgIncentive::EmergencyUnbond(p))),
+      MsgTypes::AssignedMsgClaim => self
+        .claim
+        .clone()
+        .map(|p| UmeeMsg::Incentive(UmeeMsgIncentive::Claim(p))),
+      MsgTypes::AssignedMsgSponsor => self
+        .sponsor
+        .clone()
+        .map(|p| UmeeMsg::Incentive(UmeeMsgIncentive::Sponsor(p))),
     }
   }
+
   // creates a new lend message.
   pub fn supply(supply_params: SupplyParams) -> Result<Response<StructUmeeMsg>, ContractError> {
     let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgSupply);
@@ -170,6 +323,61 @@ impl StructUmeeMsg {
     m.supply_collateral = Some(supply_collateral_params);
     return msg_chain(m);
   }
+
+  // creates a new metoken swap message.
+  pub fn metoken_swap(swap_params: SwapParams) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgMetokenSwap);
+    m.metoken_swap = Some(swap_params);
+    return msg_chain(m);
+  }
+
+  // creates a new metoken redeem message.
+  pub fn metoken_redeem(
+    redeem_params: RedeemParams,
+  ) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgMetokenRedeem);
+    m.metoken_redeem = Some(redeem_params);
+    return msg_chain(m);
+  }
+
+  // creates a new incentive bond message.
+  pub fn bond(bond_params: BondParams) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgBond);
+    m.bond = Some(bond_params);
+    return msg_chain(m);
+  }
+
+  // creates a new incentive begin unbonding message.
+  pub fn begin_unbonding(
+    begin_unbonding_params: BeginUnbondingParams,
+  ) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgBeginUnbonding);
+    m.begin_unbonding = Some(begin_unbonding_params);
+    return msg_chain(m);
+  }
+
+  // creates a new incentive emergency unbond message.
+  pub fn emergency_unbond(
+    emergency_unbond_params: EmergencyUnbondParams,
+  ) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgEmergencyUnbond);
+    m.emergency_unbond = Some(emergency_unbond_params);
+    return msg_chain(m);
+  }
+
+  // creates a new incentive claim message.
+  pub fn claim(claim_params: ClaimParams) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgClaim);
+    m.claim = Some(claim_params);
+    return msg_chain(m);
+  }
+
+  // creates a new incentive sponsor message.
+  pub fn sponsor(sponsor_params: SponsorParams) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgSponsor);
+    m.sponsor = Some(sponsor_params);
+    return msg_chain(m);
+  }
 }
 
 impl From<StructUmeeMsg> for CosmosMsg<StructUmeeMsg> {
@@ -178,6 +386,99 @@ impl From<StructUmeeMsg> for CosmosMsg<StructUmeeMsg> {
   }
 }
 
+// builds the StructUmeeMsg of the assigned msg of the enum
+impl From<UmeeMsg> for StructUmeeMsg {
+  fn from(msg: UmeeMsg) -> Self {
+    match msg {
+      UmeeMsg::Leverage(UmeeMsgLeverage::Supply(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgSupply);
+        m.supply = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::Withdraw(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgWithdraw);
+        m.withdraw = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::MaxWithdraw(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgMaxWithdraw);
+        m.max_withdraw = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::Collateralize(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgCollateralize);
+        m.collateralize = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::Decollateralize(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgDecollateralize);
+        m.decollateralize = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgBorrow);
+        m.borrow = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::MaxBorrow(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgMaxBorrow);
+        m.max_borrow = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::Repay(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgRepay);
+        m.repay = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::Liquidate(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgLiquidate);
+        m.liquidate = Some(params);
+        m
+      }
+      UmeeMsg::Leverage(UmeeMsgLeverage::SupplyCollateral(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgSupplyCollateralize);
+        m.supply_collateral = Some(params);
+        m
+      }
+      UmeeMsg::Metoken(UmeeMsgMeToken::Swap(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgMetokenSwap);
+        m.metoken_swap = Some(params);
+        m
+      }
+      UmeeMsg::Metoken(UmeeMsgMeToken::Redeem(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgMetokenRedeem);
+        m.metoken_redeem = Some(params);
+        m
+      }
+      UmeeMsg::Incentive(UmeeMsgIncentive::Bond(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgBond);
+        m.bond = Some(params);
+        m
+      }
+      UmeeMsg::Incentive(UmeeMsgIncentive::BeginUnbonding(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgBeginUnbonding);
+        m.begin_unbonding = Some(params);
+        m
+      }
+      UmeeMsg::Incentive(UmeeMsgIncentive::EmergencyUnbond(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgEmergencyUnbond);
+        m.emergency_unbond = Some(params);
+        m
+      }
+      UmeeMsg::Incentive(UmeeMsgIncentive::Claim(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgClaim);
+        m.claim = Some(params);
+        m
+      }
+      UmeeMsg::Incentive(UmeeMsgIncentive::Sponsor(params)) => {
+        let mut m = default_struct_umee_msg(MsgTypes::AssignedMsgSponsor);
+        m.sponsor = Some(params);
+        m
+      }
+    }
+  }
+}
+
 impl From<UmeeMsg> for CosmosMsg<UmeeMsg> {
   fn from(msg: UmeeMsg) -> Self {
     CosmosMsg::Custom(msg)
@@ -0,0 +1,55 @@
+use cosmwasm_std::Coin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// UmeeMsgIncentive defines all the available msgs
+// for the umee incentive native module.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum UmeeMsgIncentive {
+  // Bond bonds an amount of collateral uTokens to the incentive module.
+  Bond(BondParams),
+  // BeginUnbonding starts unbonding an amount of bonded uTokens, they are
+  // released after the unbonding duration of the incentive module.
+  BeginUnbonding(BeginUnbondingParams),
+  // EmergencyUnbond instantly unbonds an amount of bonded or unbonding
+  // uTokens, paying the emergency unbond fee.
+  EmergencyUnbond(EmergencyUnbondParams),
+  // Claim claims all the pending incentive rewards of the account.
+  Claim(ClaimParams),
+  // Sponsor funds a not yet funded incentive program with its total rewards.
+  Sponsor(SponsorParams),
+}
+
+// BondParams params to bond collateral uTokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BondParams {
+  // UToken is the amount of collateral uTokens of the sender to bond.
+  pub u_token: Coin,
+}
+
+// BeginUnbondingParams params to start unbonding bonded uTokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BeginUnbondingParams {
+  // UToken is the amount of bonded uTokens of the sender to start unbonding.
+  pub u_token: Coin,
+}
+
+// EmergencyUnbondParams params to instantly unbond uTokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EmergencyUnbondParams {
+  // UToken is the amount of bonded or unbonding uTokens of the sender to
+  // unbond instantly.
+  pub u_token: Coin,
+}
+
+// ClaimParams params to claim pending incentive rewards.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClaimParams {}
+
+// SponsorParams params to fund an incentive program.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SponsorParams {
+  // Program is the id of the incentive program the sender funds.
+  pub program: u32,
+}
@@ -15,6 +15,13 @@ pub enum MsgTypes {
   AssignedMsgLiquidate,
   AssignedMsgSupplyCollateralize,
   AssignedMsgMaxWithdraw,
+  AssignedMsgMetokenSwap,
+  AssignedMsgMetokenRedeem,
+  AssignedMsgBond,
+  AssignedMsgBeginUnbonding,
+  AssignedMsgEmergencyUnbond,
+  AssignedMsgClaim,
+  AssignedMsgSponsor,
 }
 // UmeeMsgLeverage defines all the available msgs
 // for the umee leverage native module.
@@ -0,0 +1,32 @@
+use cosmwasm_std::Coin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// UmeeMsgMeToken defines all the available msgs
+// for the umee metoken native module.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum UmeeMsgMeToken {
+  // Swap defines a method for swapping an accepted asset for Index's meToken.
+  Swap(SwapParams),
+  // Redeem defines a method for redeeming Index's meToken for an accepted asset.
+  Redeem(RedeemParams),
+}
+
+// SwapParams params to swap an accepted asset for meTokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SwapParams {
+  // Asset is the accepted asset supplied in exchange for meTokens.
+  pub asset: Coin,
+  // MetokenDenom is the denom of the meToken index to swap into.
+  pub metoken_denom: String,
+}
+
+// RedeemParams params to redeem meTokens for an accepted asset.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RedeemParams {
+  // Metoken is the amount of meTokens to be redeemed.
+  pub metoken: Coin,
+  // AssetDenom is the denom of the accepted asset to receive.
+  pub asset_denom: String,
+}
@@ -0,0 +1,323 @@
+use cosmwasm_std::{Coin, Event, StdError, StdResult, SubMsgResponse, SubMsgResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::msg_leverage::UmeeMsgLeverage;
+
+// MsgResult is the result of a msg of the umee native modules, parsed from
+// the response of the submsg that sent it. The data of the response is the
+// protobuf Msg*Response of the module with the stargate feature, otherwise
+// and when the data is missing the result is read from the attributes of
+// the module events.
+pub trait MsgResult: Sized {
+  // from_events reads the result from the attributes of the module events.
+  fn from_events(events: &[Event]) -> StdResult<Self>;
+
+  // from_data decodes the result from the Msg*Response of the module.
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self>;
+
+  fn parse(response: &SubMsgResponse) -> StdResult<Self> {
+    #[cfg(feature = "stargate")]
+    if let Some(data) = &response.data {
+      if let Ok(result) = Self::from_data(data.as_slice()) {
+        return Ok(result);
+      }
+    }
+    Self::from_events(&response.events)
+  }
+}
+
+// parse_reply parses the result of a submsg that replied on success, the
+// failed submsgs are returned as errors.
+pub fn parse_reply<T: MsgResult>(result: SubMsgResult) -> StdResult<T> {
+  match result {
+    SubMsgResult::Ok(response) => T::parse(&response),
+    SubMsgResult::Err(err) => Err(StdError::generic_err(format!("submsg failed: {}", err))),
+  }
+}
+
+// SupplyResult is the uTokens received for the supplied tokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SupplyResult {
+  pub received: Coin,
+}
+
+// WithdrawResult is the tokens received for the withdrawn uTokens, of a
+// withdraw or a max withdraw.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct WithdrawResult {
+  pub received: Coin,
+}
+
+// BorrowResult is the tokens borrowed, of a borrow or a max borrow.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BorrowResult {
+  pub borrowed: Coin,
+}
+
+// RepayResult is the tokens repaid, that are capped to the borrowed tokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RepayResult {
+  pub repaid: Coin,
+}
+
+// SupplyCollateralResult is the uTokens collateralized for the supplied
+// tokens.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SupplyCollateralResult {
+  pub collateralized: Coin,
+}
+
+// LiquidateResult is the tokens repaid by the liquidator, the collateral
+// uTokens of the borrower liquidated and the reward of the liquidator.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LiquidateResult {
+  pub repaid: Coin,
+  pub collateral: Coin,
+  pub reward: Coin,
+}
+
+// LeverageResult is the result of any leverage msg that returns one.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LeverageResult {
+  Supply(SupplyResult),
+  Withdraw(WithdrawResult),
+  Borrow(BorrowResult),
+  Repay(RepayResult),
+  Liquidate(LiquidateResult),
+  SupplyCollateral(SupplyCollateralResult),
+}
+
+impl LeverageResult {
+  // parse parses the result of the leverage msg from the response of its
+  // submsg, the collateralize and decollateralize msgs have no result.
+  pub fn parse(msg: &UmeeMsgLeverage, response: &SubMsgResponse) -> StdResult<Option<Self>> {
+    let result = match msg {
+      UmeeMsgLeverage::Supply(_) => LeverageResult::Supply(SupplyResult::parse(response)?),
+      UmeeMsgLeverage::Withdraw(_) | UmeeMsgLeverage::MaxWithdraw(_) => {
+        LeverageResult::Withdraw(WithdrawResult::parse(response)?)
+      }
+      UmeeMsgLeverage::Borrow(_) | UmeeMsgLeverage::MaxBorrow(_) => {
+        LeverageResult::Borrow(BorrowResult::parse(response)?)
+      }
+      UmeeMsgLeverage::Repay(_) => LeverageResult::Repay(RepayResult::parse(response)?),
+      UmeeMsgLeverage::Liquidate(_) => LeverageResult::Liquidate(LiquidateResult::parse(response)?),
+      UmeeMsgLeverage::SupplyCollateral(_) => {
+        LeverageResult::SupplyCollateral(SupplyCollateralResult::parse(response)?)
+      }
+      UmeeMsgLeverage::Collateralize(_) | UmeeMsgLeverage::Decollateralize(_) => return Ok(None),
+    };
+    Ok(Some(result))
+  }
+}
+
+// parse_coin parses a coin of an event attribute, e.g. "1000u/uumee".
+fn parse_coin(value: &str) -> StdResult<Coin> {
+  let split = value
+    .find(|c: char| !c.is_ascii_digit())
+    .filter(|split| *split > 0)
+    .ok_or_else(|| StdError::generic_err(format!("invalid coin: {}", value)))?;
+  let (amount, denom) = value.split_at(split);
+  Ok(Coin {
+    denom: denom.to_string(),
+    amount: Uint128::from_str(amount)?,
+  })
+}
+
+// attribute returns the coin of the first attribute of the key in the
+// module events, the wasm events of the contracts are skipped.
+fn attribute(events: &[Event], key: &str) -> StdResult<Coin> {
+  let value = events
+    .iter()
+    .filter(|event| !event.ty.starts_with("wasm"))
+    .flat_map(|event| event.attributes.iter())
+    .find(|attr| attr.key == key)
+    .ok_or_else(|| StdError::not_found(format!("event attribute {}", key)))?;
+  parse_coin(&value.value)
+}
+
+#[cfg(feature = "stargate")]
+mod data {
+  use cosmwasm_std::{Coin, StdError, StdResult, Uint128};
+  use prost::Message;
+  use std::str::FromStr;
+
+  use crate::proto;
+
+  pub fn decode<T: Message + Default>(data: &[u8]) -> StdResult<T> {
+    T::decode(data).map_err(|err| StdError::parse_err(std::any::type_name::<T>(), err))
+  }
+
+  // coin returns the coin of a field of the response, the field must be set.
+  pub fn coin(field: &str, value: Option<proto::ProtoCoin>) -> StdResult<Coin> {
+    let value = value.ok_or_else(|| StdError::not_found(field))?;
+    Ok(Coin {
+      amount: Uint128::from_str(&value.amount)?,
+      denom: value.denom,
+    })
+  }
+}
+
+impl MsgResult for SupplyResult {
+  fn from_events(events: &[Event]) -> StdResult<Self> {
+    Ok(SupplyResult {
+      received: attribute(events, "received")?,
+    })
+  }
+
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self> {
+    let res: crate::proto::MsgSupplyResponse = data::decode(data)?;
+    Ok(SupplyResult {
+      received: data::coin("received", res.received)?,
+    })
+  }
+}
+
+impl MsgResult for WithdrawResult {
+  fn from_events(events: &[Event]) -> StdResult<Self> {
+    Ok(WithdrawResult {
+      received: attribute(events, "received")?,
+    })
+  }
+
+  // the MsgWithdrawResponse and the MsgMaxWithdrawResponse have the
+  // received tokens at different tags, the max withdraw is tried first as
+  // its withdrawn uTokens are a coin that can't be read as received tokens.
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self> {
+    let max: crate::proto::MsgMaxWithdrawResponse = data::decode(data)?;
+    if max.received.is_some() {
+      return Ok(WithdrawResult {
+        received: data::coin("received", max.received)?,
+      });
+    }
+    let res: crate::proto::MsgWithdrawResponse = data::decode(data)?;
+    Ok(WithdrawResult {
+      received: data::coin("received", res.received)?,
+    })
+  }
+}
+
+impl MsgResult for BorrowResult {
+  fn from_events(events: &[Event]) -> StdResult<Self> {
+    Ok(BorrowResult {
+      borrowed: attribute(events, "borrowed")?,
+    })
+  }
+
+  // the MsgBorrowResponse is empty, only the max borrow has a result.
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self> {
+    let res: crate::proto::MsgMaxBorrowResponse = data::decode(data)?;
+    Ok(BorrowResult {
+      borrowed: data::coin("borrowed", res.borrowed)?,
+    })
+  }
+}
+
+impl MsgResult for RepayResult {
+  fn from_events(events: &[Event]) -> StdResult<Self> {
+    Ok(RepayResult {
+      repaid: attribute(events, "repaid")?,
+    })
+  }
+
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self> {
+    let res: crate::proto::MsgRepayResponse = data::decode(data)?;
+    Ok(RepayResult {
+      repaid: data::coin("repaid", res.repaid)?,
+    })
+  }
+}
+
+impl MsgResult for SupplyCollateralResult {
+  fn from_events(events: &[Event]) -> StdResult<Self> {
+    Ok(SupplyCollateralResult {
+      collateralized: attribute(events, "collateralized")?,
+    })
+  }
+
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self> {
+    let res: crate::proto::MsgSupplyCollateralResponse = data::decode(data)?;
+    Ok(SupplyCollateralResult {
+      collateralized: data::coin("collateralized", res.collateralized)?,
+    })
+  }
+}
+
+impl MsgResult for LiquidateResult {
+  fn from_events(events: &[Event]) -> StdResult<Self> {
+    Ok(LiquidateResult {
+      repaid: attribute(events, "repaid")?,
+      collateral: attribute(events, "liquidated")?,
+      reward: attribute(events, "reward")?,
+    })
+  }
+
+  #[cfg(feature = "stargate")]
+  fn from_data(data: &[u8]) -> StdResult<Self> {
+    let res: crate::proto::MsgLiquidateResponse = data::decode(data)?;
+    Ok(LiquidateResult {
+      repaid: data::coin("repaid", res.repaid)?,
+      collateral: data::coin("collateral", res.collateral)?,
+      reward: data::coin("reward", res.reward)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::msg_leverage::{CollateralizeParams, LiquidateParams, SupplyParams};
+  use cosmwasm_std::{coin, Addr};
+
+  #[test]
+  fn leverage_results() {
+    let liquidate = UmeeMsgLeverage::Liquidate(LiquidateParams {
+      borrower: Addr::unchecked("borrower"),
+      repayment: coin(100, "uatom"),
+      reward: coin(0, "u/uumee"),
+    });
+    let response = SubMsgResponse {
+      events: vec![
+        Event::new("wasm").add_attribute("repaid", "not a coin"),
+        Event::new("umee_leverage")
+          .add_attribute("action", "liquidate")
+          .add_attribute("borrower", "borrower")
+          .add_attribute("repaid", "100uatom")
+          .add_attribute("liquidated", "1100u/uumee")
+          .add_attribute("reward", "1100u/uumee"),
+      ],
+      data: None,
+    };
+    let result = LeverageResult::parse(&liquidate, &response).unwrap();
+    assert_eq!(
+      result,
+      Some(LeverageResult::Liquidate(LiquidateResult {
+        repaid: coin(100, "uatom"),
+        collateral: coin(1_100, "u/uumee"),
+        reward: coin(1_100, "u/uumee"),
+      }))
+    );
+
+    // the collateralize has no result and a missing attribute fails
+    let collateralize = UmeeMsgLeverage::Collateralize(CollateralizeParams {
+      asset: coin(100, "u/uumee"),
+    });
+    assert_eq!(
+      LeverageResult::parse(&collateralize, &response).unwrap(),
+      None
+    );
+    let supply = UmeeMsgLeverage::Supply(SupplyParams {
+      asset: coin(100, "uumee"),
+    });
+    LeverageResult::parse(&supply, &response).unwrap_err();
+    parse_reply::<SupplyResult>(SubMsgResult::Err(String::from("out of gas"))).unwrap_err();
+  }
+}
@@ -0,0 +1,221 @@
+use crate::msg_leverage::UmeeMsgLeverage;
+use crate::proto::{
+  MsgBorrow, MsgCollateralize, MsgDecollateralize, MsgLiquidate, MsgMaxBorrow, MsgMaxWithdraw,
+  MsgRepay, MsgSupply, MsgSupplyCollateral, MsgWithdraw,
+};
+use cosmwasm_std::{Addr, Binary, CosmosMsg};
+use prost::Message;
+
+// Defines the encoding of the leverage msgs as CosmosMsg::Stargate, it allows
+// the contracts to send the msgs to the x/leverage module without the custom
+// "umee" wasm message handler. The msgs are only encoded so when the contract
+// calls to_stargate, the StructUmeeMsg constructors send CosmosMsg::Custom.
+impl UmeeMsgLeverage {
+  // type_url returns the protobuf Any type URL of the x/leverage msg.
+  pub fn type_url(&self) -> &'static str {
+    match self {
+      UmeeMsgLeverage::Supply(_) => "/umee.leverage.v1.MsgSupply",
+      UmeeMsgLeverage::Withdraw(_) => "/umee.leverage.v1.MsgWithdraw",
+      UmeeMsgLeverage::MaxWithdraw(_) => "/umee.leverage.v1.MsgMaxWithdraw",
+      UmeeMsgLeverage::Collateralize(_) => "/umee.leverage.v1.MsgCollateralize",
+      UmeeMsgLeverage::Decollateralize(_) => "/umee.leverage.v1.MsgDecollateralize",
+      UmeeMsgLeverage::Borrow(_) => "/umee.leverage.v1.MsgBorrow",
+      UmeeMsgLeverage::MaxBorrow(_) => "/umee.leverage.v1.MsgMaxBorrow",
+      UmeeMsgLeverage::Repay(_) => "/umee.leverage.v1.MsgRepay",
+      UmeeMsgLeverage::Liquidate(_) => "/umee.leverage.v1.MsgLiquidate",
+      UmeeMsgLeverage::SupplyCollateral(_) => "/umee.leverage.v1.MsgSupplyCollateral",
+    }
+  }
+
+  // encode_proto returns the protobuf encoded x/leverage msg, the sender is
+  // the signer of the msg, usually the contract address.
+  pub fn encode_proto(&self, sender: &Addr) -> Vec<u8> {
+    let sender = sender.to_string();
+    match self.clone() {
+      UmeeMsgLeverage::Supply(p) => MsgSupply {
+        supplier: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::Withdraw(p) => MsgWithdraw {
+        supplier: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::MaxWithdraw(p) => MsgMaxWithdraw {
+        supplier: sender,
+        denom: p.denom,
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::Collateralize(p) => MsgCollateralize {
+        borrower: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::Decollateralize(p) => MsgDecollateralize {
+        borrower: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::Borrow(p) => MsgBorrow {
+        borrower: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::MaxBorrow(p) => MsgMaxBorrow {
+        borrower: sender,
+        denom: p.denom.denom,
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::Repay(p) => MsgRepay {
+        borrower: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::Liquidate(p) => MsgLiquidate {
+        liquidator: sender,
+        borrower: p.borrower.into_string(),
+        repayment: Some(p.repayment.into()),
+        reward_denom: p.reward.denom,
+      }
+      .encode_to_vec(),
+      UmeeMsgLeverage::SupplyCollateral(p) => MsgSupplyCollateral {
+        supplier: sender,
+        asset: Some(p.asset.into()),
+      }
+      .encode_to_vec(),
+    }
+  }
+
+  // to_stargate creates the CosmosMsg::Stargate of the x/leverage msg
+  // signed by the sender.
+  pub fn to_stargate<T>(&self, sender: &Addr) -> CosmosMsg<T> {
+    CosmosMsg::Stargate {
+      type_url: self.type_url().to_string(),
+      value: Binary::from(self.encode_proto(sender)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::msg_leverage::{
+    BorrowParams, CollateralizeParams, DecollateralizeParams, LiquidateParams, MsgMaxBorrowParams,
+    MsgMaxWithdrawParams, RepayParams, SupplyCollateralParams, SupplyParams, WithdrawParams,
+  };
+  use cosmwasm_std::{coin, Empty};
+
+  // field returns the protobuf length delimited field of the tag, the
+  // expected encodings are written by hand so they pin the wire format.
+  fn field(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![tag << 3 | 2, value.len() as u8];
+    bytes.extend_from_slice(value);
+    bytes
+  }
+
+  fn proto_coin(amount: &str, denom: &str) -> Vec<u8> {
+    [field(1, denom.as_bytes()), field(2, amount.as_bytes())].concat()
+  }
+
+  // sender_asset is the encoding of the msgs of a signer and a coin.
+  fn sender_asset(amount: &str, denom: &str) -> Vec<u8> {
+    [field(1, b"contract"), field(2, &proto_coin(amount, denom))].concat()
+  }
+
+  #[test]
+  fn leverage_msgs_encoding() {
+    let sender = Addr::unchecked("contract");
+    let cases = vec![
+      (
+        UmeeMsgLeverage::Supply(SupplyParams {
+          asset: coin(100, "uumee"),
+        }),
+        "/umee.leverage.v1.MsgSupply",
+        sender_asset("100", "uumee"),
+      ),
+      (
+        UmeeMsgLeverage::Withdraw(WithdrawParams {
+          asset: coin(100, "u/uumee"),
+        }),
+        "/umee.leverage.v1.MsgWithdraw",
+        sender_asset("100", "u/uumee"),
+      ),
+      (
+        UmeeMsgLeverage::MaxWithdraw(MsgMaxWithdrawParams {
+          denom: String::from("uumee"),
+        }),
+        "/umee.leverage.v1.MsgMaxWithdraw",
+        [field(1, b"contract"), field(2, b"uumee")].concat(),
+      ),
+      (
+        UmeeMsgLeverage::Collateralize(CollateralizeParams {
+          asset: coin(7, "u/uumee"),
+        }),
+        "/umee.leverage.v1.MsgCollateralize",
+        sender_asset("7", "u/uumee"),
+      ),
+      (
+        UmeeMsgLeverage::Decollateralize(DecollateralizeParams {
+          asset: coin(7, "u/uumee"),
+        }),
+        "/umee.leverage.v1.MsgDecollateralize",
+        sender_asset("7", "u/uumee"),
+      ),
+      (
+        UmeeMsgLeverage::Borrow(BorrowParams {
+          asset: coin(50, "uatom"),
+        }),
+        "/umee.leverage.v1.MsgBorrow",
+        sender_asset("50", "uatom"),
+      ),
+      (
+        UmeeMsgLeverage::MaxBorrow(MsgMaxBorrowParams {
+          denom: coin(0, "uatom"),
+        }),
+        "/umee.leverage.v1.MsgMaxBorrow",
+        [field(1, b"contract"), field(2, b"uatom")].concat(),
+      ),
+      (
+        UmeeMsgLeverage::Repay(RepayParams {
+          asset: coin(50, "uatom"),
+        }),
+        "/umee.leverage.v1.MsgRepay",
+        sender_asset("50", "uatom"),
+      ),
+      (
+        UmeeMsgLeverage::Liquidate(LiquidateParams {
+          borrower: Addr::unchecked("borrower"),
+          repayment: coin(10, "uatom"),
+          reward: coin(0, "u/uumee"),
+        }),
+        "/umee.leverage.v1.MsgLiquidate",
+        [
+          field(1, b"contract"),
+          field(2, b"borrower"),
+          field(3, &proto_coin("10", "uatom")),
+          field(4, b"u/uumee"),
+        ]
+        .concat(),
+      ),
+      (
+        UmeeMsgLeverage::SupplyCollateral(SupplyCollateralParams {
+          asset: coin(100, "uumee"),
+        }),
+        "/umee.leverage.v1.MsgSupplyCollateral",
+        sender_asset("100", "uumee"),
+      ),
+    ];
+    for (msg, type_url, value) in cases {
+      assert_eq!(msg.type_url(), type_url);
+      assert_eq!(msg.encode_proto(&sender), value, "{}", type_url);
+      assert_eq!(
+        msg.to_stargate::<Empty>(&sender),
+        CosmosMsg::Stargate {
+          type_url: type_url.to_string(),
+          value: Binary::from(value),
+        }
+      );
+    }
+  }
+}
@@ -0,0 +1,1456 @@
+use crate::health::AccountPosition;
+use crate::interest::MarketLiquidity;
+use crate::leverage_parameters::LeverageParameters;
+use crate::mock_oracle::MockOracle;
+use crate::msg::{StructUmeeMsg, UmeeMsg};
+use crate::msg_incentive::UmeeMsgIncentive;
+use crate::msg_leverage::UmeeMsgLeverage;
+use crate::msg_metoken::UmeeMsgMeToken;
+use crate::query::{StructUmeeQuery, UmeeQuery};
+use crate::query_incentive::{
+  AccountBondsResponse, CurrentRatesResponse, LastRewardTimeResponse, PendingRewardsResponse,
+  UmeeQueryIncentive,
+};
+use crate::query_leverage::{
+  AccountBalancesResponse, AccountSummaryResponse, LeverageParametersResponse,
+  LiquidationTargetsResponse, MarketSummaryResponse, MaxBorrowResponse, MaxWithdrawResponse,
+  RegisteredTokensResponse, UmeeQueryLeverage,
+};
+use crate::query_metoken::{Index, MetokenIndexesResponse, UmeeQueryMeToken};
+use crate::query_oracle::{ExchangeRatesParams, ExchangeRatesResponse, UmeeQueryOracle};
+use crate::token::Token;
+use anyhow::{bail, Result as AnyResult};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+  from_json, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, ContractResult,
+  CustomQuery, Decimal, Decimal256, Empty, Event, GovMsg, IbcMsg, IbcQuery, Order, Querier,
+  QuerierResult, QuerierWrapper, QueryRequest, Storage, SystemError, SystemResult, Uint128,
+};
+use cw_multi_test::{
+  App, AppResponse, BankKeeper, BankSudo, BasicAppBuilder, CosmosRouter, DistributionKeeper,
+  FailingModule, Module, Router, StakeKeeper, SudoMsg, WasmKeeper,
+};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+// LEVERAGE_MODULE_ADDR is the account holding the supplied liquidity and
+// the collateral uTokens of the simulated x/leverage module.
+pub const LEVERAGE_MODULE_ADDR: &str = "umee_leverage_module";
+
+// METOKEN_MODULE_ADDR is the account holding the reserves of the meToken
+// indexes of the simulated x/metoken module.
+pub const METOKEN_MODULE_ADDR: &str = "umee_metoken_module";
+
+// UTOKEN_PREFIX is the denom prefix of the uTokens minted on supply.
+pub const UTOKEN_PREFIX: &str = "u/";
+
+const REGISTRY: Map<&str, Token> = Map::new("umee_leverage_registry");
+const ORACLE: Item<MockOracle> = Item::new("umee_oracle");
+const PARAMS: Item<LeverageParameters> = Item::new("umee_leverage_params");
+// collateral uTokens by account and uToken denom
+const COLLATERAL: Map<(&Addr, &str), Uint128> = Map::new("umee_leverage_collateral");
+// borrowed tokens by account and base denom
+const BORROWED: Map<(&Addr, &str), Uint128> = Map::new("umee_leverage_borrowed");
+// market totals by base denom
+const UTOKEN_SUPPLY: Map<&str, Uint128> = Map::new("umee_leverage_utoken_supply");
+const TOTAL_COLLATERAL: Map<&str, Uint128> = Map::new("umee_leverage_total_collateral");
+const TOTAL_BORROWED: Map<&str, Uint128> = Map::new("umee_leverage_total_borrowed");
+const LIQUIDITY: Map<&str, Uint128> = Map::new("umee_leverage_liquidity");
+// bonded uTokens by account and uToken denom
+const BONDED: Map<(&Addr, &str), Uint128> = Map::new("umee_incentive_bonded");
+// incentive rewards to claim by account
+const PENDING_REWARDS: Map<&Addr, Vec<Coin>> = Map::new("umee_incentive_pending_rewards");
+const LAST_REWARD_TIME: Item<i64> = Item::new("umee_incentive_last_reward_time");
+// incentive rates by uToken denom
+const CURRENT_RATES: Map<&str, CurrentRatesResponse> = Map::new("umee_incentive_current_rates");
+// meToken indexes by meToken denom
+const METOKEN_INDEXES: Map<&str, Index> = Map::new("umee_metoken_indexes");
+
+// UmeeApp is a cw-multi-test App that handles the StructUmeeMsg and
+// StructUmeeQuery of the contracts with the UmeeLeverageModule.
+pub type UmeeApp = App<
+  BankKeeper,
+  MockApi,
+  MockStorage,
+  UmeeLeverageModule,
+  WasmKeeper<StructUmeeMsg, StructUmeeQuery>,
+>;
+
+// UmeeRouter is the router of the UmeeApp, it is received by the init_fn.
+pub type UmeeRouter = Router<
+  BankKeeper,
+  UmeeLeverageModule,
+  WasmKeeper<StructUmeeMsg, StructUmeeQuery>,
+  StakeKeeper,
+  DistributionKeeper,
+  FailingModule<IbcMsg, IbcQuery, Empty>,
+  FailingModule<GovMsg, Empty, Empty>,
+>;
+
+// umee_app creates an UmeeApp, the init_fn can set the bank balances,
+// register the tokens of the leverage module and set the oracle prices.
+pub fn umee_app<F>(init_fn: F) -> UmeeApp
+where
+  F: FnOnce(&mut UmeeRouter, &dyn Api, &mut dyn Storage),
+{
+  BasicAppBuilder::<StructUmeeMsg, StructUmeeQuery>::new_custom()
+    .with_custom(UmeeLeverageModule::new())
+    .build(init_fn)
+}
+
+// mock_token returns a registered token with the parameters commonly used
+// by the umee chain, borrowing and supplying are enabled and the supply
+// is not capped.
+pub fn mock_token(base_denom: &str, symbol_denom: &str, exponent: u32) -> Token {
+  Token {
+    base_denom: Some(base_denom.to_string()),
+    reserve_factor: Decimal::percent(10),
+    collateral_weight: Decimal::percent(70),
+    liquidation_threshold: Decimal::percent(80),
+    base_borrow_rate: Decimal::percent(2),
+    kink_borrow_rate: Decimal::percent(20),
+    max_borrow_rate: Decimal::percent(150),
+    kink_utilization: Decimal::percent(80),
+    liquidation_incentive: Decimal::percent(10),
+    symbol_denom: Some(symbol_denom.to_string()),
+    exponent,
+    enable_msg_supply: Some(true),
+    enable_msg_borrow: Some(true),
+    blacklist: Some(false),
+    max_collateral_share: Decimal::one(),
+    max_supply_utilization: Decimal::percent(90),
+    min_collateral_liquidity: Decimal::zero(),
+    max_supply: Decimal::zero(),
+    historic_medians: 0,
+  }
+}
+
+// mock_leverage_parameters returns the default x/leverage parameters.
+pub fn mock_leverage_parameters() -> LeverageParameters {
+  LeverageParameters {
+    complete_liquidation_threshold: Decimal256::percent(40),
+    minimum_close_factor: Decimal256::percent(5),
+    oracle_reward_factor: Decimal256::percent(1),
+    small_liquidation_size: Decimal256::from_ratio(500u128, 1u128),
+    direct_liquidation_fee: Decimal256::percent(5),
+  }
+}
+
+// UmeeLeverageModule simulates the x/leverage module in cw-multi-test, it
+// keeps the collateral and borrowed balances of every account, mints and
+// burns the uTokens in the bank and answers the leverage queries from the
+// same state. Interest is not accrued, so the uToken exchange rate only
+// changes when tokens are sent to the module account.
+//
+// The prices come from a MockOracle, evaluated at the block height, that
+// also answers the oracle queries.
+//
+// The x/incentive module is simulated as far as bonding collateral and
+// claiming rewards, the rewards to claim and the last reward time are set
+// by the tests instead of the incentive programs. The bonded collateral
+// can't be decollateralized or withdrawn, as in x/incentive.
+//
+// The x/metoken module is simulated as far as swapping and redeeming, the
+// accepted assets are exchanged 1:1 with the meTokens less the min fee of
+// the index and the redeems are paid from the reserves of the module.
+#[derive(Default)]
+pub struct UmeeLeverageModule {}
+
+impl UmeeLeverageModule {
+  pub fn new() -> Self {
+    UmeeLeverageModule {}
+  }
+
+  // register_token adds or replaces a token of the leverage registry.
+  pub fn register_token(&self, storage: &mut dyn Storage, token: Token) -> AnyResult<()> {
+    let denom = match token.base_denom.clone() {
+      Some(denom) if !denom.is_empty() => denom,
+      _ => bail!("token without base denom"),
+    };
+    REGISTRY.save(storage, &denom, &token)?;
+    Ok(())
+  }
+
+  // set_price sets the oracle exchange rate of the symbol denom of a
+  // registered token, it is the USD price of one symbol unit.
+  pub fn set_price(
+    &self,
+    storage: &mut dyn Storage,
+    base_denom: &str,
+    price: Decimal256,
+  ) -> AnyResult<()> {
+    let token = match REGISTRY.may_load(storage, base_denom)? {
+      Some(token) => token,
+      None => bail!("not a registered Token: {}", base_denom),
+    };
+    let symbol_denom = token.symbol_denom.unwrap_or_default();
+    let mut oracle = self.oracle(storage)?;
+    oracle.set_exchange_rate(&symbol_denom, price);
+    ORACLE.save(storage, &oracle)?;
+    Ok(())
+  }
+
+  // oracle returns the stored oracle, it is not moved to the block height.
+  pub fn oracle(&self, storage: &dyn Storage) -> AnyResult<MockOracle> {
+    Ok(ORACLE.may_load(storage)?.unwrap_or_default())
+  }
+
+  // update_oracle moves the oracle to the height, usually the current block
+  // height of the app, and applies the changes of the update_fn.
+  pub fn update_oracle<F>(
+    &self,
+    storage: &mut dyn Storage,
+    height: u64,
+    update_fn: F,
+  ) -> AnyResult<()>
+  where
+    F: FnOnce(&mut MockOracle) -> AnyResult<()>,
+  {
+    let mut oracle = self.oracle(storage)?.at_height(height);
+    update_fn(&mut oracle)?;
+    ORACLE.save(storage, &oracle)?;
+    Ok(())
+  }
+
+  // add_pending_rewards adds incentive rewards that the account can claim,
+  // they are minted on claim.
+  pub fn add_pending_rewards(
+    &self,
+    storage: &mut dyn Storage,
+    address: &Addr,
+    rewards: Vec<Coin>,
+  ) -> AnyResult<()> {
+    let mut pending = PENDING_REWARDS
+      .may_load(storage, address)?
+      .unwrap_or_default();
+    for reward in rewards {
+      match pending.iter_mut().find(|coin| coin.denom == reward.denom) {
+        Some(coin) => coin.amount += reward.amount,
+        None => pending.push(reward),
+      }
+    }
+    PENDING_REWARDS.save(storage, address, &pending)?;
+    Ok(())
+  }
+
+  // set_last_reward_time sets the unix time of the last incentive rewards.
+  pub fn set_last_reward_time(&self, storage: &mut dyn Storage, time: i64) -> AnyResult<()> {
+    LAST_REWARD_TIME.save(storage, &time)?;
+    Ok(())
+  }
+
+  // set_current_rates sets the rewards per year of the reference bond of a
+  // uToken, by the denom of the reference bond.
+  pub fn set_current_rates(
+    &self,
+    storage: &mut dyn Storage,
+    rates: CurrentRatesResponse,
+  ) -> AnyResult<()> {
+    CURRENT_RATES.save(storage, &rates.reference_bond.denom, &rates)?;
+    Ok(())
+  }
+
+  // register_metoken_index adds or replaces a meToken index.
+  pub fn register_metoken_index(&self, storage: &mut dyn Storage, index: Index) -> AnyResult<()> {
+    METOKEN_INDEXES.save(storage, &index.denom.clone(), &index)?;
+    Ok(())
+  }
+
+  // set_leverage_parameters replaces the parameters of the leverage module.
+  pub fn set_leverage_parameters(
+    &self,
+    storage: &mut dyn Storage,
+    params: LeverageParameters,
+  ) -> AnyResult<()> {
+    PARAMS.save(storage, &params)?;
+    Ok(())
+  }
+}
+
+impl Module for UmeeLeverageModule {
+  type ExecT = StructUmeeMsg;
+  type QueryT = StructUmeeQuery;
+  type SudoT = Empty;
+
+  fn execute<ExecC, QueryC>(
+    &self,
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    sender: Addr,
+    msg: StructUmeeMsg,
+  ) -> AnyResult<AppResponse>
+  where
+    ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+  {
+    let mut keeper = Keeper {
+      api,
+      storage,
+      router,
+      block,
+    };
+    let (module, attributes) = match msg.umee_msg() {
+      Some(UmeeMsg::Leverage(leverage_msg)) => (
+        "umee_leverage",
+        keeper.execute_leverage(&sender, leverage_msg)?,
+      ),
+      Some(UmeeMsg::Incentive(UmeeMsgIncentive::Bond(p))) => {
+        ("umee_incentive", keeper.bond(&sender, p.u_token)?)
+      }
+      Some(UmeeMsg::Incentive(UmeeMsgIncentive::Claim(_))) => {
+        ("umee_incentive", keeper.claim(&sender)?)
+      }
+      Some(UmeeMsg::Metoken(UmeeMsgMeToken::Swap(p))) => (
+        "umee_metoken",
+        keeper.metoken_swap(&sender, p.asset, &p.metoken_denom)?,
+      ),
+      Some(UmeeMsg::Metoken(UmeeMsgMeToken::Redeem(p))) => (
+        "umee_metoken",
+        keeper.metoken_redeem(&sender, p.metoken, &p.asset_denom)?,
+      ),
+      _ => bail!("unsupported umee msg: {}", msg.assigned_str()),
+    };
+
+    let event = Event::new(module)
+      .add_attribute("action", msg.assigned_str())
+      .add_attribute("sender", sender)
+      .add_attributes(attributes);
+    Ok(AppResponse {
+      events: vec![event],
+      data: None,
+    })
+  }
+
+  fn sudo<ExecC, QueryC>(
+    &self,
+    _api: &dyn Api,
+    _storage: &mut dyn Storage,
+    _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    _block: &BlockInfo,
+    _msg: Empty,
+  ) -> AnyResult<AppResponse>
+  where
+    ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+  {
+    bail!("sudo is not supported by the umee leverage module")
+  }
+
+  fn query(
+    &self,
+    _api: &dyn Api,
+    storage: &dyn Storage,
+    querier: &dyn Querier,
+    block: &BlockInfo,
+    request: StructUmeeQuery,
+  ) -> AnyResult<Binary> {
+    let view = View {
+      storage,
+      querier,
+      oracle: self.oracle(storage)?.at_height(block.height),
+    };
+    let res = match request.umee_query() {
+      Some(UmeeQuery::Oracle(oracle_query)) => view.oracle.query(&oracle_query)?,
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::LeverageParameters(_))) => {
+        to_json_binary(&LeverageParametersResponse {
+          params: view.params()?,
+        })?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::RegisteredTokens(_))) => {
+        to_json_binary(&RegisteredTokensResponse {
+          registry: REGISTRY
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, token)| token))
+            .collect::<Result<_, _>>()?,
+        })?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::MarketSummary(p))) => {
+        to_json_binary(&view.market_summary(&p.denom)?)?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::AccountBalances(p))) => {
+        to_json_binary(&view.account_balances(&p.address)?)?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::AccountSummary(p))) => {
+        to_json_binary(&view.account_summary(&p.address)?)?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::LiquidationTargets(_))) => {
+        to_json_binary(&LiquidationTargetsResponse {
+          targets: view.liquidation_targets()?,
+        })?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::MaxWithdraw(p))) => {
+        let (u_tokens, tokens) = view.max_withdraw(&p.address, &p.denom)?;
+        to_json_binary(&MaxWithdrawResponse { u_tokens, tokens })?
+      }
+      Some(UmeeQuery::Leverage(UmeeQueryLeverage::MaxBorrow(p))) => {
+        to_json_binary(&MaxBorrowResponse {
+          tokens: vec![view.max_borrow(&p.address, &p.denom)?],
+        })?
+      }
+      Some(UmeeQuery::Incentive(UmeeQueryIncentive::AccountBonds(p))) => {
+        to_json_binary(&view.account_bonds(&Addr::unchecked(p.address))?)?
+      }
+      Some(UmeeQuery::Incentive(UmeeQueryIncentive::PendingRewards(p))) => {
+        to_json_binary(&PendingRewardsResponse {
+          rewards: PENDING_REWARDS
+            .may_load(storage, &Addr::unchecked(p.address))?
+            .unwrap_or_default(),
+        })?
+      }
+      Some(UmeeQuery::Incentive(UmeeQueryIncentive::LastRewardTime(_))) => {
+        to_json_binary(&LastRewardTimeResponse {
+          time: LAST_REWARD_TIME.may_load(storage)?.unwrap_or_default(),
+        })?
+      }
+      Some(UmeeQuery::Incentive(UmeeQueryIncentive::CurrentRates(p))) => {
+        to_json_binary(&view.current_rates(&p.u_token)?)?
+      }
+      Some(UmeeQuery::Metoken(UmeeQueryMeToken::MetokenIndexes(p))) => {
+        to_json_binary(&MetokenIndexesResponse {
+          registry: METOKEN_INDEXES
+            .range(storage, None, None, Order::Ascending)
+            .filter(|item| match item {
+              Ok((denom, _)) => p.metoken_denom.is_empty() || *denom == p.metoken_denom,
+              Err(_) => true,
+            })
+            .map(|item| item.map(|(_, index)| index))
+            .collect::<Result<_, _>>()?,
+        })?
+      }
+      _ => bail!("unsupported umee query: {:?}", request),
+    };
+    Ok(res)
+  }
+}
+
+fn utoken_denom(base_denom: &str) -> String {
+  format!("{}{}", UTOKEN_PREFIX, base_denom)
+}
+
+fn base_denom(denom: &str) -> AnyResult<&str> {
+  match denom.strip_prefix(UTOKEN_PREFIX) {
+    Some(base) => Ok(base),
+    None => bail!("denom should be a uToken: {}", denom),
+  }
+}
+
+fn dec(amount: Uint128) -> Decimal256 {
+  Decimal256::from_ratio(amount, 1u128)
+}
+
+fn floor(value: Decimal256) -> AnyResult<Uint128> {
+  Ok(Uint128::try_from(value.to_uint_floor())?)
+}
+
+fn sub_or_zero(a: Decimal256, b: Decimal256) -> Decimal256 {
+  a.checked_sub(b).unwrap_or_default()
+}
+
+// Market is the state of a registered token in the leverage module.
+struct Market {
+  token: Token,
+  price: Decimal256,
+  // historic_price is the historic median of the tokens with
+  // historic_medians, the spot price otherwise or without median.
+  historic_price: Decimal256,
+  exchange_rate: Decimal256,
+  utoken_supply: Uint128,
+  collateral: Uint128,
+  borrowed: Uint128,
+  liquidity: Uint128,
+}
+
+impl Market {
+  // value returns the USD value of an amount of base tokens.
+  fn value(&self, amount: Uint128) -> AnyResult<Decimal256> {
+    if self.price.is_zero() && !amount.is_zero() {
+      bail!("invalid oracle price for {}", self.token_denom());
+    }
+    Ok(Decimal256::from_atomics(amount, self.token.exponent)? * self.price)
+  }
+
+  // historic_value returns the USD value of the base tokens at the
+  // historic price.
+  fn historic_value(&self, amount: Uint128) -> AnyResult<Decimal256> {
+    Ok(Decimal256::from_atomics(amount, self.token.exponent)? * self.historic_price)
+  }
+
+  // amount returns the base tokens worth a USD value, rounded down.
+  fn amount(&self, value: Decimal256) -> AnyResult<Uint128> {
+    if self.price.is_zero() {
+      bail!("invalid oracle price for {}", self.token_denom());
+    }
+    let units = Decimal256::from_ratio(10u128.pow(self.token.exponent), 1u128);
+    floor(value / self.price * units)
+  }
+
+  fn token_denom(&self) -> String {
+    self.token.base_denom.clone().unwrap_or_default()
+  }
+
+  fn to_tokens(&self, u_tokens: Uint128) -> AnyResult<Uint128> {
+    floor(dec(u_tokens) * self.exchange_rate)
+  }
+
+  fn to_u_tokens(&self, tokens: Uint128) -> AnyResult<Uint128> {
+    floor(dec(tokens) / self.exchange_rate)
+  }
+
+  fn supplied(&self) -> AnyResult<Uint128> {
+    self.to_tokens(self.utoken_supply)
+  }
+
+  fn minimum_liquidity(&self) -> AnyResult<Decimal256> {
+    Ok(
+      dec(self.to_tokens(self.collateral)?) * Decimal256::from(self.token.min_collateral_liquidity),
+    )
+  }
+
+  fn maximum_borrow(&self) -> AnyResult<Decimal256> {
+    Ok(dec(self.supplied()?) * Decimal256::from(self.token.max_supply_utilization))
+  }
+
+  fn maximum_collateral(&self) -> Decimal256 {
+    dec(self.utoken_supply) * Decimal256::from(self.token.max_collateral_share)
+  }
+
+  // available_borrow is the amount of tokens that can be borrowed without
+  // breaking the min collateral liquidity and max supply utilization.
+  fn available_borrow(&self) -> AnyResult<Uint128> {
+    let by_liquidity = sub_or_zero(dec(self.liquidity), self.minimum_liquidity()?);
+    let by_utilization = sub_or_zero(self.maximum_borrow()?, dec(self.borrowed));
+    floor(by_liquidity.min(by_utilization))
+  }
+
+  // available_withdraw is the amount of uTokens that can be withdrawn
+  // without breaking the min collateral liquidity.
+  fn available_withdraw(&self) -> AnyResult<Uint128> {
+    let tokens = floor(sub_or_zero(dec(self.liquidity), self.minimum_liquidity()?))?;
+    self.to_u_tokens(tokens)
+  }
+}
+
+// Position is the value of the collateral and borrows of an account.
+#[derive(Default)]
+struct Position {
+  collateral_value: Decimal256,
+  borrowed_value: Decimal256,
+  borrow_limit: Decimal256,
+  liquidation_threshold: Decimal256,
+  // the borrow limit and the borrowed value at the lower and the higher of
+  // the spot and historic prices, and the historic liquidation threshold.
+  historic_borrow_limit: Decimal256,
+  historic_borrowed_value: Decimal256,
+  historic_liquidation_threshold: Decimal256,
+}
+
+// View reads the leverage state, the querier is used for the bank balances
+// and the oracle is at the block height.
+struct View<'a> {
+  storage: &'a dyn Storage,
+  querier: &'a dyn Querier,
+  oracle: MockOracle,
+}
+
+impl<'a> View<'a> {
+  fn params(&self) -> AnyResult<LeverageParameters> {
+    Ok(
+      PARAMS
+        .may_load(self.storage)?
+        .unwrap_or_else(mock_leverage_parameters),
+    )
+  }
+
+  // current_rates returns the rates set by the tests, or no rewards for the
+  // reference bond of one token of the uToken.
+  fn current_rates(&self, u_token: &str) -> AnyResult<CurrentRatesResponse> {
+    if let Some(rates) = CURRENT_RATES.may_load(self.storage, u_token)? {
+      return Ok(rates);
+    }
+    let market = self.market(base_denom(u_token)?)?;
+    Ok(CurrentRatesResponse {
+      reference_bond: Coin::new(10u128.pow(market.token.exponent), u_token),
+      rewards: vec![],
+    })
+  }
+
+  fn market(&self, denom: &str) -> AnyResult<Market> {
+    let token = match REGISTRY.may_load(self.storage, denom)? {
+      Some(token) => token,
+      None => bail!("not a registered Token: {}", denom),
+    };
+    let utoken_supply = UTOKEN_SUPPLY
+      .may_load(self.storage, denom)?
+      .unwrap_or_default();
+    let borrowed = TOTAL_BORROWED
+      .may_load(self.storage, denom)?
+      .unwrap_or_default();
+    let liquidity = LIQUIDITY.may_load(self.storage, denom)?.unwrap_or_default();
+    let exchange_rate = if utoken_supply.is_zero() {
+      Decimal256::one()
+    } else {
+      Decimal256::from_ratio(liquidity + borrowed, utoken_supply)
+    };
+    let symbol_denom = token.symbol_denom.as_deref().unwrap_or_default();
+    let price = self.oracle.exchange_rate(symbol_denom).unwrap_or_default();
+    let historic_price = match token.historic_medians {
+      0 => price,
+      count => self
+        .oracle
+        .historic_median(symbol_denom, count)
+        .unwrap_or(price),
+    };
+    Ok(Market {
+      price,
+      historic_price,
+      collateral: TOTAL_COLLATERAL
+        .may_load(self.storage, denom)?
+        .unwrap_or_default(),
+      token,
+      exchange_rate,
+      utoken_supply,
+      borrowed,
+      liquidity,
+    })
+  }
+
+  fn balance(&self, address: &Addr, denom: &str) -> AnyResult<Uint128> {
+    let querier: QuerierWrapper<Empty> = QuerierWrapper::new(self.querier);
+    Ok(querier.query_balance(address, denom)?.amount)
+  }
+
+  fn collateral(&self, address: &Addr, base_denom: &str) -> AnyResult<Uint128> {
+    Ok(
+      COLLATERAL
+        .may_load(self.storage, (address, &utoken_denom(base_denom)))?
+        .unwrap_or_default(),
+    )
+  }
+
+  // unbonded_collateral returns the collateral uTokens of the account that
+  // are not bonded to the incentive module, only they can be withdrawn or
+  // decollateralized.
+  fn unbonded_collateral(&self, address: &Addr, base_denom: &str) -> AnyResult<Uint128> {
+    let bonded = BONDED
+      .may_load(self.storage, (address, &utoken_denom(base_denom)))?
+      .unwrap_or_default();
+    Ok(self.collateral(address, base_denom)?.saturating_sub(bonded))
+  }
+
+  fn borrowed(&self, address: &Addr, base_denom: &str) -> AnyResult<Uint128> {
+    Ok(
+      BORROWED
+        .may_load(self.storage, (address, base_denom))?
+        .unwrap_or_default(),
+    )
+  }
+
+  fn position(&self, address: &Addr) -> AnyResult<Position> {
+    let mut position = Position::default();
+    for item in COLLATERAL
+      .prefix(address)
+      .range(self.storage, None, None, Order::Ascending)
+    {
+      let (u_denom, u_tokens) = item?;
+      let market = self.market(base_denom(&u_denom)?)?;
+      let tokens = market.to_tokens(u_tokens)?;
+      let value = market.value(tokens)?;
+      let historic_value = market.historic_value(tokens)?;
+      let weight = Decimal256::from(market.token.collateral_weight);
+      let threshold = Decimal256::from(market.token.liquidation_threshold);
+      position.collateral_value += value;
+      position.borrow_limit += value * weight;
+      position.liquidation_threshold += value * threshold;
+      position.historic_borrow_limit += value.min(historic_value) * weight;
+      position.historic_liquidation_threshold += historic_value * threshold;
+    }
+    for item in BORROWED
+      .prefix(address)
+      .range(self.storage, None, None, Order::Ascending)
+    {
+      let (denom, amount) = item?;
+      let market = self.market(&denom)?;
+      let value = market.value(amount)?;
+      position.borrowed_value += value;
+      position.historic_borrowed_value += value.max(market.historic_value(amount)?);
+    }
+    Ok(position)
+  }
+
+  // max_withdraw returns the uTokens, and their value in base tokens, that
+  // the account can withdraw without exceeding its borrow limit.
+  fn max_withdraw(&self, address: &Addr, denom: &str) -> AnyResult<(Coin, Coin)> {
+    let market = self.market(denom)?;
+    let wallet = self.balance(address, &utoken_denom(denom))?;
+    let collateral = self.unbonded_collateral(address, denom)?;
+    let position = self.position(address)?;
+    let weight = Decimal256::from(market.token.collateral_weight);
+
+    let free_collateral = if position.borrowed_value.is_zero() || weight.is_zero() {
+      collateral
+    } else {
+      let free_value = sub_or_zero(position.borrow_limit, position.borrowed_value) / weight;
+      market
+        .to_u_tokens(market.amount(free_value)?)?
+        .min(collateral)
+    };
+    let u_tokens = (wallet + free_collateral).min(market.available_withdraw()?);
+    Ok((
+      Coin::new(u_tokens.u128(), utoken_denom(denom)),
+      Coin::new(market.to_tokens(u_tokens)?.u128(), denom),
+    ))
+  }
+
+  // max_borrow returns the tokens that the account can borrow without
+  // exceeding its borrow limit.
+  fn max_borrow(&self, address: &Addr, denom: &str) -> AnyResult<Coin> {
+    let market = self.market(denom)?;
+    let position = self.position(address)?;
+    let free_value = sub_or_zero(position.borrow_limit, position.borrowed_value);
+    let amount = market.amount(free_value)?.min(market.available_borrow()?);
+    Ok(Coin::new(amount.u128(), denom))
+  }
+
+  fn market_summary(&self, denom: &str) -> AnyResult<MarketSummaryResponse> {
+    let market = self.market(denom)?;
+    let minimum_liquidity = market.minimum_liquidity()?;
+    let maximum_collateral = market.maximum_collateral();
+    let liquidity = MarketLiquidity::new(dec(market.borrowed), dec(market.liquidity));
+    let rates = market.token.interest_rates(&liquidity, &self.params()?)?;
+    Ok(MarketSummaryResponse {
+      symbol_denom: market.token.symbol_denom.clone().unwrap_or_default(),
+      exponent: market.token.exponent,
+      oracle_price: market.price,
+      utoken_exchange_rate: market.exchange_rate,
+      supply_apy: rates.supply_apy,
+      borrow_apy: rates.borrow_apy,
+      supplied: dec(market.supplied()?),
+      reserved: Decimal256::zero(),
+      collateral: dec(market.collateral),
+      borrowed: dec(market.borrowed),
+      liquidity: dec(market.liquidity),
+      maximum_borrow: market.maximum_borrow()?,
+      maximum_collateral,
+      minimum_liquidity,
+      utoken_supply: dec(market.utoken_supply),
+      available_borrow: dec(market.available_borrow()?),
+      available_withdraw: dec(market.available_withdraw()?),
+      available_collateralize: sub_or_zero(maximum_collateral, dec(market.collateral)),
+    })
+  }
+
+  fn account_balances(&self, address: &Addr) -> AnyResult<AccountBalancesResponse> {
+    let mut res = AccountBalancesResponse {
+      supplied: vec![],
+      collateral: vec![],
+      borrowed: vec![],
+    };
+    for denom in REGISTRY.keys(self.storage, None, None, Order::Ascending) {
+      let denom = denom?;
+      let market = self.market(&denom)?;
+      let collateral = self.collateral(address, &denom)?;
+      let u_tokens = self.balance(address, &utoken_denom(&denom))? + collateral;
+      if !u_tokens.is_zero() {
+        res
+          .supplied
+          .push(Coin::new(market.to_tokens(u_tokens)?.u128(), denom.clone()));
+      }
+      if !collateral.is_zero() {
+        res
+          .collateral
+          .push(Coin::new(collateral.u128(), utoken_denom(&denom)));
+      }
+      let borrowed = self.borrowed(address, &denom)?;
+      if !borrowed.is_zero() {
+        res.borrowed.push(Coin::new(borrowed.u128(), denom));
+      }
+    }
+    Ok(res)
+  }
+
+  fn account_summary(&self, address: &Addr) -> AnyResult<AccountSummaryResponse> {
+    let balances = self.account_balances(address)?;
+    let mut supplied_value = Decimal256::zero();
+    for coin in balances.supplied {
+      supplied_value += self.market(&coin.denom)?.value(coin.amount)?;
+    }
+    let position = self.position(address)?;
+    Ok(AccountSummaryResponse {
+      supplied_value,
+      collateral_value: position.collateral_value,
+      borrowed_value: position.borrowed_value,
+      borrow_limit: position.borrow_limit,
+      liquidation_threshold: position.liquidation_threshold,
+      spendable_borrow_limit: Some(sub_or_zero(
+        position.historic_borrow_limit,
+        position.historic_borrowed_value,
+      )),
+      historic_liquidation_threshold: Some(position.historic_liquidation_threshold),
+    })
+  }
+
+  fn account_bonds(&self, address: &Addr) -> AnyResult<AccountBondsResponse> {
+    let bonded = BONDED
+      .prefix(address)
+      .range(self.storage, None, None, Order::Ascending)
+      .map(|item| item.map(|(denom, amount)| Coin::new(amount.u128(), denom)))
+      .collect::<Result<_, _>>()?;
+    Ok(AccountBondsResponse {
+      bonded,
+      unbonding: vec![],
+      unbondings: vec![],
+    })
+  }
+
+  // account_position returns the AccountPosition of the account, valued at
+  // the oracle prices and the uToken exchange rates of the markets.
+  fn account_position(&self, address: &Addr) -> AnyResult<AccountPosition> {
+    let registry: Vec<Token> = REGISTRY
+      .range(self.storage, None, None, Order::Ascending)
+      .map(|item| item.map(|(_, token)| token))
+      .collect::<Result<_, _>>()?;
+    let prices: ExchangeRatesResponse = from_json(self.oracle.query(
+      &UmeeQueryOracle::ExchangeRates(ExchangeRatesParams {
+        denom: String::new(),
+      }),
+    )?)?;
+    let mut position = AccountPosition::new(
+      &self.account_balances(address)?,
+      &RegisteredTokensResponse {
+        registry: registry.clone(),
+      },
+      &prices,
+    )?;
+    for token in registry {
+      let rate = self.market(token.base_denom())?.exchange_rate;
+      position = position.with_utoken_exchange_rate(token.base_denom(), rate)?;
+    }
+    Ok(position)
+  }
+
+  // liquidation_targets returns the borrowers over their liquidation
+  // threshold.
+  fn liquidation_targets(&self) -> AnyResult<Vec<String>> {
+    let mut targets = vec![];
+    let mut borrowers: Vec<Addr> = vec![];
+    for key in BORROWED.keys(self.storage, None, None, Order::Ascending) {
+      let (address, _) = key?;
+      if borrowers.last() != Some(&address) {
+        borrowers.push(address);
+      }
+    }
+    for address in borrowers {
+      let position = self.position(&address)?;
+      if position.borrowed_value > position.liquidation_threshold {
+        targets.push(address.to_string());
+      }
+    }
+    Ok(targets)
+  }
+}
+
+// RouterQuerier answers the bank queries of the module while executing a
+// msg, it sends the query through the router like the app querier does.
+struct RouterQuerier<'a, ExecC, QueryC> {
+  api: &'a dyn Api,
+  storage: &'a dyn Storage,
+  router: &'a dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+  block: &'a BlockInfo,
+}
+
+impl<'a, ExecC, QueryC> Querier for RouterQuerier<'a, ExecC, QueryC>
+where
+  QueryC: CustomQuery + DeserializeOwned + 'static,
+{
+  fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+    let request: QueryRequest<QueryC> = match from_json(bin_request) {
+      Ok(request) => request,
+      Err(err) => {
+        return SystemResult::Err(SystemError::InvalidRequest {
+          error: format!("Parsing query request: {}", err),
+          request: bin_request.into(),
+        })
+      }
+    };
+    let res: ContractResult<Binary> = self
+      .router
+      .query(self.api, self.storage, self.block, request)
+      .into();
+    SystemResult::Ok(res)
+  }
+}
+
+// Keeper changes the leverage state while executing the msgs of an account,
+// every method returns the attributes of the emitted event.
+struct Keeper<'a, ExecC, QueryC> {
+  api: &'a dyn Api,
+  storage: &'a mut dyn Storage,
+  router: &'a dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+  block: &'a BlockInfo,
+}
+
+type Attributes = Vec<(String, String)>;
+
+impl<'a, ExecC, QueryC> Keeper<'a, ExecC, QueryC>
+where
+  ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+  QueryC: CustomQuery + DeserializeOwned + 'static,
+{
+  fn view<T>(&self, f: impl FnOnce(&View) -> AnyResult<T>) -> AnyResult<T> {
+    let querier = RouterQuerier {
+      api: self.api,
+      storage: &*self.storage,
+      router: self.router,
+      block: self.block,
+    };
+    f(&View {
+      storage: &*self.storage,
+      querier: &querier,
+      oracle: ORACLE
+        .may_load(&*self.storage)?
+        .unwrap_or_default()
+        .at_height(self.block.height),
+    })
+  }
+
+  fn send(&mut self, from: &Addr, to: &Addr, coin: Coin) -> AnyResult<()> {
+    if coin.amount.is_zero() {
+      return Ok(());
+    }
+    let msg = BankMsg::Send {
+      to_address: to.to_string(),
+      amount: vec![coin],
+    };
+    self
+      .router
+      .execute(self.api, self.storage, self.block, from.clone(), msg.into())?;
+    Ok(())
+  }
+
+  fn mint(&mut self, to: &Addr, coin: Coin) -> AnyResult<()> {
+    if coin.amount.is_zero() {
+      return Ok(());
+    }
+    let msg = BankSudo::Mint {
+      to_address: to.to_string(),
+      amount: vec![coin],
+    };
+    self
+      .router
+      .sudo(self.api, self.storage, self.block, SudoMsg::Bank(msg))?;
+    Ok(())
+  }
+
+  fn burn(&mut self, from: &Addr, coin: Coin) -> AnyResult<()> {
+    if coin.amount.is_zero() {
+      return Ok(());
+    }
+    let msg = BankMsg::Burn { amount: vec![coin] };
+    self
+      .router
+      .execute(self.api, self.storage, self.block, from.clone(), msg.into())?;
+    Ok(())
+  }
+
+  fn update(
+    &mut self,
+    map: Map<&str, Uint128>,
+    denom: &str,
+    f: impl FnOnce(Uint128) -> AnyResult<Uint128>,
+  ) -> AnyResult<()> {
+    let value = f(map.may_load(self.storage, denom)?.unwrap_or_default())?;
+    map.save(self.storage, denom, &value)?;
+    Ok(())
+  }
+
+  fn update_account(
+    &mut self,
+    map: Map<(&Addr, &str), Uint128>,
+    address: &Addr,
+    denom: &str,
+    f: impl FnOnce(Uint128) -> AnyResult<Uint128>,
+  ) -> AnyResult<()> {
+    let value = f(map
+      .may_load(self.storage, (address, denom))?
+      .unwrap_or_default())?;
+    if value.is_zero() {
+      map.remove(self.storage, (address, denom));
+    } else {
+      map.save(self.storage, (address, denom), &value)?;
+    }
+    Ok(())
+  }
+
+  // ensure_healthy fails if the borrowed value of the account is over its
+  // borrow limit, at the higher and the lower of the spot and historic
+  // prices as the chain checks.
+  fn ensure_healthy(&self, address: &Addr) -> AnyResult<()> {
+    let position = self.view(|view| view.position(address))?;
+    if position.historic_borrowed_value > position.historic_borrow_limit {
+      bail!(
+        "borrow positions are undercollaterized: borrowed value {} exceeds borrow limit {}",
+        position.historic_borrowed_value,
+        position.historic_borrow_limit
+      );
+    }
+    Ok(())
+  }
+
+  // execute_leverage executes a msg of the leverage module.
+  fn execute_leverage(&mut self, sender: &Addr, msg: UmeeMsgLeverage) -> AnyResult<Attributes> {
+    let attributes = match msg {
+      UmeeMsgLeverage::Supply(p) => self.supply(sender, p.asset)?.1,
+      UmeeMsgLeverage::Withdraw(p) => self.withdraw(sender, p.asset)?,
+      UmeeMsgLeverage::MaxWithdraw(p) => {
+        let (u_tokens, _) = self.max_withdraw(sender, &p.denom)?;
+        self.withdraw(sender, u_tokens)?
+      }
+      UmeeMsgLeverage::Collateralize(p) => self.collateralize(sender, p.asset)?,
+      UmeeMsgLeverage::Decollateralize(p) => self.decollateralize(sender, p.asset)?,
+      UmeeMsgLeverage::Borrow(p) => self.borrow(sender, p.asset)?,
+      UmeeMsgLeverage::MaxBorrow(p) => {
+        let tokens = self.max_borrow(sender, &p.denom.denom)?;
+        self.borrow(sender, tokens)?
+      }
+      UmeeMsgLeverage::Repay(p) => self.repay(sender, p.asset)?,
+      UmeeMsgLeverage::SupplyCollateral(p) => {
+        let (u_tokens, mut attributes) = self.supply(sender, p.asset)?;
+        attributes.extend(self.collateralize(sender, u_tokens)?);
+        attributes
+      }
+      UmeeMsgLeverage::Liquidate(p) => {
+        self.liquidate(sender, &p.borrower, p.repayment, &p.reward.denom)?
+      }
+    };
+    Ok(attributes)
+  }
+
+  // supply returns the minted uTokens with the event attributes.
+  fn supply(&mut self, sender: &Addr, asset: Coin) -> AnyResult<(Coin, Attributes)> {
+    let market = self.view(|view| view.market(&asset.denom))?;
+    if market.token.blacklist == Some(true) || market.token.enable_msg_supply != Some(true) {
+      bail!("supplying of Token disabled: {}", asset.denom);
+    }
+    let max_supply = Decimal256::from(market.token.max_supply);
+    if !max_supply.is_zero() && dec(market.supplied()? + asset.amount) > max_supply {
+      bail!(
+        "market total supply would exceed MaxSupply: {}",
+        asset.denom
+      );
+    }
+    let u_tokens = Coin::new(
+      market.to_u_tokens(asset.amount)?.u128(),
+      utoken_denom(&asset.denom),
+    );
+
+    self.send(
+      sender,
+      &Addr::unchecked(LEVERAGE_MODULE_ADDR),
+      asset.clone(),
+    )?;
+    self.mint(sender, u_tokens.clone())?;
+    self.update(UTOKEN_SUPPLY, &asset.denom, |v| Ok(v + u_tokens.amount))?;
+    self.update(LIQUIDITY, &asset.denom, |v| Ok(v + asset.amount))?;
+
+    let attributes = vec![
+      ("supplied".to_string(), asset.to_string()),
+      ("received".to_string(), u_tokens.to_string()),
+    ];
+    Ok((u_tokens, attributes))
+  }
+
+  fn withdraw(&mut self, sender: &Addr, u_tokens: Coin) -> AnyResult<Attributes> {
+    let denom = base_denom(&u_tokens.denom)?.to_string();
+    let (market, wallet, collateral) = self.view(|view| {
+      Ok((
+        view.market(&denom)?,
+        view.balance(sender, &u_tokens.denom)?,
+        view.unbonded_collateral(sender, &denom)?,
+      ))
+    })?;
+    if u_tokens.amount > wallet + collateral {
+      bail!("insufficient balance: {}", u_tokens);
+    }
+    let tokens = Coin::new(market.to_tokens(u_tokens.amount)?.u128(), denom.clone());
+    if tokens.amount > market.liquidity {
+      bail!("lending pool insufficient: {}", tokens);
+    }
+    let from_wallet = u_tokens.amount.min(wallet);
+    let from_collateral = u_tokens.amount - from_wallet;
+    let module = Addr::unchecked(LEVERAGE_MODULE_ADDR);
+
+    if !from_collateral.is_zero() {
+      self.update_account(COLLATERAL, sender, &u_tokens.denom, |v| {
+        Ok(v.checked_sub(from_collateral)?)
+      })?;
+      self.update(TOTAL_COLLATERAL, &denom, |v| {
+        Ok(v.checked_sub(from_collateral)?)
+      })?;
+      self.ensure_healthy(sender)?;
+    }
+    self.burn(sender, Coin::new(from_wallet.u128(), &u_tokens.denom))?;
+    self.burn(&module, Coin::new(from_collateral.u128(), &u_tokens.denom))?;
+    self.send(&module, sender, tokens.clone())?;
+    self.update(UTOKEN_SUPPLY, &denom, |v| {
+      Ok(v.checked_sub(u_tokens.amount)?)
+    })?;
+    self.update(LIQUIDITY, &denom, |v| Ok(v.checked_sub(tokens.amount)?))?;
+
+    Ok(vec![
+      ("withdrawn".to_string(), u_tokens.to_string()),
+      ("received".to_string(), tokens.to_string()),
+    ])
+  }
+
+  fn max_withdraw(&self, sender: &Addr, denom: &str) -> AnyResult<(Coin, Coin)> {
+    self.view(|view| view.max_withdraw(sender, denom))
+  }
+
+  fn collateralize(&mut self, sender: &Addr, u_tokens: Coin) -> AnyResult<Attributes> {
+    let denom = base_denom(&u_tokens.denom)?.to_string();
+    let market = self.view(|view| view.market(&denom))?;
+    if market.token.collateral_weight.is_zero() {
+      bail!("collateral weight of Token is zero: {}", denom);
+    }
+    if dec(market.collateral + u_tokens.amount) > market.maximum_collateral() {
+      bail!(
+        "market total collateral would exceed MaxCollateralShare: {}",
+        denom
+      );
+    }
+
+    self.send(
+      sender,
+      &Addr::unchecked(LEVERAGE_MODULE_ADDR),
+      u_tokens.clone(),
+    )?;
+    self.update_account(COLLATERAL, sender, &u_tokens.denom, |v| {
+      Ok(v + u_tokens.amount)
+    })?;
+    self.update(TOTAL_COLLATERAL, &denom, |v| Ok(v + u_tokens.amount))?;
+
+    Ok(vec![("collateralized".to_string(), u_tokens.to_string())])
+  }
+
+  fn decollateralize(&mut self, sender: &Addr, u_tokens: Coin) -> AnyResult<Attributes> {
+    let denom = base_denom(&u_tokens.denom)?.to_string();
+    let collateral = self.view(|view| view.unbonded_collateral(sender, &denom))?;
+    if u_tokens.amount > collateral {
+      bail!("insufficient collateral: {}", u_tokens);
+    }
+
+    self.update_account(COLLATERAL, sender, &u_tokens.denom, |v| {
+      Ok(v.checked_sub(u_tokens.amount)?)
+    })?;
+    self.update(TOTAL_COLLATERAL, &denom, |v| {
+      Ok(v.checked_sub(u_tokens.amount)?)
+    })?;
+    self.ensure_healthy(sender)?;
+    self.send(
+      &Addr::unchecked(LEVERAGE_MODULE_ADDR),
+      sender,
+      u_tokens.clone(),
+    )?;
+
+    Ok(vec![("decollateralized".to_string(), u_tokens.to_string())])
+  }
+
+  fn max_borrow(&self, sender: &Addr, denom: &str) -> AnyResult<Coin> {
+    self.view(|view| view.max_borrow(sender, denom))
+  }
+
+  fn borrow(&mut self, sender: &Addr, asset: Coin) -> AnyResult<Attributes> {
+    let market = self.view(|view| view.market(&asset.denom))?;
+    if market.token.blacklist == Some(true) || market.token.enable_msg_borrow != Some(true) {
+      bail!("borrowing of Token disabled: {}", asset.denom);
+    }
+    if asset.amount > market.liquidity {
+      bail!("lending pool insufficient: {}", asset);
+    }
+    if asset.amount > market.available_borrow()? {
+      bail!(
+        "market would fall below minimum collateral liquidity or exceed max supply utilization: {}",
+        asset
+      );
+    }
+
+    self.update_account(BORROWED, sender, &asset.denom, |v| Ok(v + asset.amount))?;
+    self.update(TOTAL_BORROWED, &asset.denom, |v| Ok(v + asset.amount))?;
+    self.update(LIQUIDITY, &asset.denom, |v| {
+      Ok(v.checked_sub(asset.amount)?)
+    })?;
+    self.ensure_healthy(sender)?;
+    self.send(
+      &Addr::unchecked(LEVERAGE_MODULE_ADDR),
+      sender,
+      asset.clone(),
+    )?;
+
+    Ok(vec![("borrowed".to_string(), asset.to_string())])
+  }
+
+  fn repay(&mut self, sender: &Addr, asset: Coin) -> AnyResult<Attributes> {
+    let borrowed = self.view(|view| view.borrowed(sender, &asset.denom))?;
+    if borrowed.is_zero() {
+      bail!("no active borrow of {} to repay", asset.denom);
+    }
+    let repaid = Coin::new(asset.amount.min(borrowed).u128(), asset.denom.clone());
+
+    self.send(
+      sender,
+      &Addr::unchecked(LEVERAGE_MODULE_ADDR),
+      repaid.clone(),
+    )?;
+    self.update_account(BORROWED, sender, &asset.denom, |v| {
+      Ok(v.checked_sub(repaid.amount)?)
+    })?;
+    self.update(TOTAL_BORROWED, &asset.denom, |v| {
+      Ok(v.checked_sub(repaid.amount)?)
+    })?;
+    self.update(LIQUIDITY, &asset.denom, |v| Ok(v + repaid.amount))?;
+
+    Ok(vec![("repaid".to_string(), repaid.to_string())])
+  }
+
+  // liquidate repays the borrow of the borrower for its collateral, the
+  // repayment and the reward follow the AccountPosition liquidation, the
+  // reward is paid in uTokens for a uToken reward_denom or in base tokens.
+  fn liquidate(
+    &mut self,
+    liquidator: &Addr,
+    borrower: &Addr,
+    repayment: Coin,
+    reward_denom: &str,
+  ) -> AnyResult<Attributes> {
+    let (params, position) =
+      self.view(|view| Ok((view.params()?, view.account_position(borrower)?)))?;
+    if position.close_factor(&params)?.is_zero() {
+      bail!("borrower not eligible for liquidation: {}", borrower);
+    }
+    let plan = position.liquidation(
+      &params,
+      &repayment.denom,
+      reward_denom,
+      Some(repayment.amount),
+    )?;
+    let denom = base_denom(&plan.collateral_burn.denom)?.to_string();
+    let module = Addr::unchecked(LEVERAGE_MODULE_ADDR);
+
+    self.send(liquidator, &module, plan.repayment.clone())?;
+    self.update_account(BORROWED, borrower, &repayment.denom, |v| {
+      Ok(v.checked_sub(plan.repayment.amount)?)
+    })?;
+    self.update(TOTAL_BORROWED, &repayment.denom, |v| {
+      Ok(v.checked_sub(plan.repayment.amount)?)
+    })?;
+    self.update(LIQUIDITY, &repayment.denom, |v| {
+      Ok(v + plan.repayment.amount)
+    })?;
+    self.update_account(COLLATERAL, borrower, &plan.collateral_burn.denom, |v| {
+      Ok(v.checked_sub(plan.collateral_burn.amount)?)
+    })?;
+    self.update(TOTAL_COLLATERAL, &denom, |v| {
+      Ok(v.checked_sub(plan.collateral_burn.amount)?)
+    })?;
+    if plan.reward.denom == plan.collateral_burn.denom {
+      self.send(&module, liquidator, plan.reward.clone())?;
+    } else {
+      let liquidity = self.view(|view| Ok(view.market(&denom)?.liquidity))?;
+      if plan.reward.amount > liquidity {
+        bail!("lending pool insufficient: {}", plan.reward);
+      }
+      self.burn(&module, plan.collateral_burn.clone())?;
+      self.update(UTOKEN_SUPPLY, &denom, |v| {
+        Ok(v.checked_sub(plan.collateral_burn.amount)?)
+      })?;
+      self.update(LIQUIDITY, &denom, |v| {
+        Ok(v.checked_sub(plan.reward.amount)?)
+      })?;
+      self.send(&module, liquidator, plan.reward.clone())?;
+    }
+
+    Ok(vec![
+      ("borrower".to_string(), borrower.to_string()),
+      ("repaid".to_string(), plan.repayment.to_string()),
+      ("liquidated".to_string(), plan.collateral_burn.to_string()),
+      ("reward".to_string(), plan.reward.to_string()),
+    ])
+  }
+
+  // bond bonds collateral uTokens of the account, the bonded uTokens stay
+  // in the collateral of the account.
+  fn bond(&mut self, sender: &Addr, u_token: Coin) -> AnyResult<Attributes> {
+    let denom = base_denom(&u_token.denom)?.to_string();
+    let collateral = self.view(|view| view.collateral(sender, &denom))?;
+    let bonded = BONDED
+      .may_load(self.storage, (sender, &u_token.denom))?
+      .unwrap_or_default();
+    if bonded + u_token.amount > collateral {
+      bail!("insufficient collateral to bond: {}", u_token);
+    }
+    self.update_account(BONDED, sender, &u_token.denom, |v| Ok(v + u_token.amount))?;
+
+    Ok(vec![("bonded".to_string(), u_token.to_string())])
+  }
+
+  // claim mints the pending rewards of the account.
+  fn claim(&mut self, sender: &Addr) -> AnyResult<Attributes> {
+    let rewards = PENDING_REWARDS
+      .may_load(self.storage, sender)?
+      .unwrap_or_default();
+    PENDING_REWARDS.remove(self.storage, sender);
+    let mut attributes = vec![];
+    for reward in rewards {
+      attributes.push(("claimed".to_string(), reward.to_string()));
+      self.mint(sender, reward)?;
+    }
+    Ok(attributes)
+  }
+
+  // metoken_index returns the index of the meToken, it must accept the asset.
+  fn metoken_index(&self, metoken_denom: &str, asset_denom: &str) -> AnyResult<Index> {
+    let index = match METOKEN_INDEXES.may_load(self.storage, metoken_denom)? {
+      Some(index) => index,
+      None => bail!("index {} not found", metoken_denom),
+    };
+    if !index
+      .accepted_assets
+      .iter()
+      .any(|asset| asset.denom == asset_denom)
+    {
+      bail!(
+        "asset {} is not accepted by the index {}",
+        asset_denom,
+        metoken_denom
+      );
+    }
+    Ok(index)
+  }
+
+  // metoken_swap keeps the asset in the reserves and mints the meTokens.
+  fn metoken_swap(
+    &mut self,
+    sender: &Addr,
+    asset: Coin,
+    metoken_denom: &str,
+  ) -> AnyResult<Attributes> {
+    let index = self.metoken_index(metoken_denom, &asset.denom)?;
+    let fee = asset.amount * index.fee.min_fee;
+    let metokens = Coin::new((asset.amount - fee).u128(), metoken_denom);
+    self.send(sender, &Addr::unchecked(METOKEN_MODULE_ADDR), asset)?;
+    self.mint(sender, metokens.clone())?;
+
+    Ok(vec![
+      ("metoken".to_string(), metokens.to_string()),
+      ("fee".to_string(), fee.to_string()),
+    ])
+  }
+
+  // metoken_redeem burns the meTokens and pays the asset from the reserves.
+  fn metoken_redeem(
+    &mut self,
+    sender: &Addr,
+    metoken: Coin,
+    asset_denom: &str,
+  ) -> AnyResult<Attributes> {
+    let index = self.metoken_index(&metoken.denom, asset_denom)?;
+    let fee = metoken.amount * index.fee.min_fee;
+    let asset = Coin::new((metoken.amount - fee).u128(), asset_denom);
+    if self.view(|view| view.balance(&Addr::unchecked(METOKEN_MODULE_ADDR), asset_denom))?
+      < asset.amount
+    {
+      bail!("not enough {} reserves to redeem {}", asset_denom, metoken);
+    }
+    self.burn(sender, metoken)?;
+    self.send(&Addr::unchecked(METOKEN_MODULE_ADDR), sender, asset.clone())?;
+
+    Ok(vec![
+      ("asset".to_string(), asset.to_string()),
+      ("fee".to_string(), fee.to_string()),
+    ])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::msg_incentive::BondParams;
+  use crate::msg_leverage::{DecollateralizeParams, SupplyCollateralParams, WithdrawParams};
+  use crate::querier::UmeeQuerier;
+  use crate::query_leverage::MaxWithdrawParams;
+  use cosmwasm_std::{coin, coins, CosmosMsg};
+  use cw_multi_test::{BankSudo, Executor, SudoMsg};
+
+  fn execute(app: &mut UmeeApp, sender: &Addr, msg: UmeeMsg) -> AnyResult<AppResponse> {
+    app.execute(sender.clone(), CosmosMsg::Custom(StructUmeeMsg::from(msg)))
+  }
+
+  #[test]
+  fn bonded_collateral() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::one())
+        .unwrap();
+    });
+    let supplier = Addr::unchecked("supplier");
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: supplier.to_string(),
+        amount: coins(1_000, "uumee"),
+      }))
+      .unwrap();
+    execute(
+      &mut app,
+      &supplier,
+      UmeeMsg::Leverage(UmeeMsgLeverage::SupplyCollateral(SupplyCollateralParams {
+        asset: coin(1_000, "uumee"),
+      })),
+    )
+    .unwrap();
+    execute(
+      &mut app,
+      &supplier,
+      UmeeMsg::Incentive(UmeeMsgIncentive::Bond(BondParams {
+        u_token: coin(600, "u/uumee"),
+      })),
+    )
+    .unwrap();
+
+    // only the unbonded collateral is withdrawable
+    let wrapper = app.wrap();
+    let querier = UmeeQuerier::custom(&wrapper);
+    let max = querier
+      .max_withdraw(MaxWithdrawParams {
+        address: supplier.clone(),
+        denom: String::from("uumee"),
+      })
+      .unwrap();
+    assert_eq!(max.u_tokens, coin(400, "u/uumee"));
+
+    // the bonded uTokens are neither decollateralized nor withdrawn
+    let decollateralize = |amount| {
+      UmeeMsg::Leverage(UmeeMsgLeverage::Decollateralize(DecollateralizeParams {
+        asset: coin(amount, "u/uumee"),
+      }))
+    };
+    let withdraw = |amount| {
+      UmeeMsg::Leverage(UmeeMsgLeverage::Withdraw(WithdrawParams {
+        asset: coin(amount, "u/uumee"),
+      }))
+    };
+    execute(&mut app, &supplier, decollateralize(401)).unwrap_err();
+    execute(&mut app, &supplier, withdraw(401)).unwrap_err();
+    execute(&mut app, &supplier, decollateralize(300)).unwrap();
+    execute(&mut app, &supplier, withdraw(400)).unwrap();
+    execute(&mut app, &supplier, decollateralize(1)).unwrap_err();
+  }
+}
@@ -3,26 +3,165 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // Params defines the parameters for the oracle module.
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
 pub struct OracleParameters {
-  vote_period: u64,
-  vote_threshold: Decimal256,
-  reward_band: Decimal256,
-  reward_distribution_window: u64,
-  accept_list: Vec<Denom>,
-  slash_fraction: Decimal256,
-  slash_window: u64,
-  min_valid_per_window: Decimal256,
-  stamp_period: u64,
-  prune_period: u64,
-  median_period: u64,
-  historic_accept_list: Vec<Denom>,
+  pub(crate) vote_period: u64,
+  pub(crate) vote_threshold: Decimal256,
+  pub(crate) reward_band: Decimal256,
+  pub(crate) reward_distribution_window: u64,
+  pub(crate) accept_list: Vec<Denom>,
+  pub(crate) slash_fraction: Decimal256,
+  pub(crate) slash_window: u64,
+  pub(crate) min_valid_per_window: Decimal256,
+  pub(crate) stamp_period: u64,
+  pub(crate) prune_period: u64,
+  pub(crate) median_period: u64,
+  pub(crate) historic_accept_list: Vec<Denom>,
+}
+
+impl OracleParameters {
+  pub fn vote_period(&self) -> u64 {
+    self.vote_period
+  }
+
+  pub fn vote_threshold(&self) -> Decimal256 {
+    self.vote_threshold
+  }
+
+  pub fn reward_band(&self) -> Decimal256 {
+    self.reward_band
+  }
+
+  pub fn reward_distribution_window(&self) -> u64 {
+    self.reward_distribution_window
+  }
+
+  pub fn accept_list(&self) -> &[Denom] {
+    &self.accept_list
+  }
+
+  pub fn slash_fraction(&self) -> Decimal256 {
+    self.slash_fraction
+  }
+
+  pub fn slash_window(&self) -> u64 {
+    self.slash_window
+  }
+
+  pub fn min_valid_per_window(&self) -> Decimal256 {
+    self.min_valid_per_window
+  }
+
+  pub fn stamp_period(&self) -> u64 {
+    self.stamp_period
+  }
+
+  pub fn prune_period(&self) -> u64 {
+    self.prune_period
+  }
+
+  pub fn median_period(&self) -> u64 {
+    self.median_period
+  }
+
+  pub fn historic_accept_list(&self) -> &[Denom] {
+    &self.historic_accept_list
+  }
+
+  // the with_ builders set a field over the Default zero values, to build
+  // the parameters of the tests.
+  pub fn with_vote_period(mut self, vote_period: u64) -> Self {
+    self.vote_period = vote_period;
+    self
+  }
+
+  pub fn with_vote_threshold(mut self, vote_threshold: Decimal256) -> Self {
+    self.vote_threshold = vote_threshold;
+    self
+  }
+
+  pub fn with_reward_band(mut self, reward_band: Decimal256) -> Self {
+    self.reward_band = reward_band;
+    self
+  }
+
+  pub fn with_reward_distribution_window(mut self, reward_distribution_window: u64) -> Self {
+    self.reward_distribution_window = reward_distribution_window;
+    self
+  }
+
+  pub fn with_accept_list(mut self, accept_list: Vec<Denom>) -> Self {
+    self.accept_list = accept_list;
+    self
+  }
+
+  pub fn with_slash_fraction(mut self, slash_fraction: Decimal256) -> Self {
+    self.slash_fraction = slash_fraction;
+    self
+  }
+
+  pub fn with_slash_window(mut self, slash_window: u64) -> Self {
+    self.slash_window = slash_window;
+    self
+  }
+
+  pub fn with_min_valid_per_window(mut self, min_valid_per_window: Decimal256) -> Self {
+    self.min_valid_per_window = min_valid_per_window;
+    self
+  }
+
+  pub fn with_stamp_period(mut self, stamp_period: u64) -> Self {
+    self.stamp_period = stamp_period;
+    self
+  }
+
+  pub fn with_prune_period(mut self, prune_period: u64) -> Self {
+    self.prune_period = prune_period;
+    self
+  }
+
+  pub fn with_median_period(mut self, median_period: u64) -> Self {
+    self.median_period = median_period;
+    self
+  }
+
+  pub fn with_historic_accept_list(mut self, historic_accept_list: Vec<Denom>) -> Self {
+    self.historic_accept_list = historic_accept_list;
+    self
+  }
 }
 
 // Denom object to hold configurations of each denom.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Denom {
-  base_denom: String,
-  symbol_denom: String,
-  exponent: u32,
+  pub(crate) base_denom: String,
+  pub(crate) symbol_denom: String,
+  pub(crate) exponent: u32,
+}
+
+impl Denom {
+  // new returns the config of the base denom and its symbol denom.
+  pub fn new(
+    base_denom: impl Into<String>,
+    symbol_denom: impl Into<String>,
+    exponent: u32,
+  ) -> Self {
+    Denom {
+      base_denom: base_denom.into(),
+      symbol_denom: symbol_denom.into(),
+      exponent,
+    }
+  }
+
+  pub fn base_denom(&self) -> &str {
+    &self.base_denom
+  }
+
+  pub fn symbol_denom(&self) -> &str {
+    &self.symbol_denom
+  }
+
+  pub fn exponent(&self) -> u32 {
+    self.exponent
+  }
 }
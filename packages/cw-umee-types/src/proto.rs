@@ -0,0 +1,1021 @@
+// Protobuf definitions of the umee native module messages and queries, they
+// are used to send the messages as CosmosMsg::Stargate and the queries as
+// QueryRequest::Stargate instead of the custom "umee" wasm handlers. The field tags must match the .proto files from
+// the umee public repository https://github.com/umee-network/umee/tree/main/proto
+use cosmwasm_std::Coin;
+
+// Coin defines cosmos.base.v1beta1.Coin.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoCoin {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub amount: String,
+}
+
+impl From<Coin> for ProtoCoin {
+  fn from(coin: Coin) -> Self {
+    ProtoCoin {
+      denom: coin.denom,
+      amount: coin.amount.to_string(),
+    }
+  }
+}
+
+// MsgSupply defines umee.leverage.v1.MsgSupply.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSupply {
+  #[prost(string, tag = "1")]
+  pub supplier: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgWithdraw defines umee.leverage.v1.MsgWithdraw.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgWithdraw {
+  #[prost(string, tag = "1")]
+  pub supplier: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgMaxWithdraw defines umee.leverage.v1.MsgMaxWithdraw.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgMaxWithdraw {
+  #[prost(string, tag = "1")]
+  pub supplier: String,
+  #[prost(string, tag = "2")]
+  pub denom: String,
+}
+
+// MsgCollateralize defines umee.leverage.v1.MsgCollateralize.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgCollateralize {
+  #[prost(string, tag = "1")]
+  pub borrower: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgDecollateralize defines umee.leverage.v1.MsgDecollateralize.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgDecollateralize {
+  #[prost(string, tag = "1")]
+  pub borrower: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgBorrow defines umee.leverage.v1.MsgBorrow.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgBorrow {
+  #[prost(string, tag = "1")]
+  pub borrower: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgMaxBorrow defines umee.leverage.v1.MsgMaxBorrow.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgMaxBorrow {
+  #[prost(string, tag = "1")]
+  pub borrower: String,
+  #[prost(string, tag = "2")]
+  pub denom: String,
+}
+
+// MsgRepay defines umee.leverage.v1.MsgRepay.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgRepay {
+  #[prost(string, tag = "1")]
+  pub borrower: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgLiquidate defines umee.leverage.v1.MsgLiquidate.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgLiquidate {
+  #[prost(string, tag = "1")]
+  pub liquidator: String,
+  #[prost(string, tag = "2")]
+  pub borrower: String,
+  #[prost(message, optional, tag = "3")]
+  pub repayment: Option<ProtoCoin>,
+  #[prost(string, tag = "4")]
+  pub reward_denom: String,
+}
+
+// MsgSupplyCollateral defines umee.leverage.v1.MsgSupplyCollateral.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSupplyCollateral {
+  #[prost(string, tag = "1")]
+  pub supplier: String,
+  #[prost(message, optional, tag = "2")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// MsgSupplyResponse defines umee.leverage.v1.MsgSupplyResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSupplyResponse {
+  #[prost(message, optional, tag = "1")]
+  pub received: Option<ProtoCoin>,
+}
+
+// MsgWithdrawResponse defines umee.leverage.v1.MsgWithdrawResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgWithdrawResponse {
+  #[prost(message, optional, tag = "1")]
+  pub received: Option<ProtoCoin>,
+}
+
+// MsgMaxWithdrawResponse defines umee.leverage.v1.MsgMaxWithdrawResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgMaxWithdrawResponse {
+  #[prost(message, optional, tag = "1")]
+  pub withdrawn: Option<ProtoCoin>,
+  #[prost(message, optional, tag = "2")]
+  pub received: Option<ProtoCoin>,
+}
+
+// MsgMaxBorrowResponse defines umee.leverage.v1.MsgMaxBorrowResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgMaxBorrowResponse {
+  #[prost(message, optional, tag = "1")]
+  pub borrowed: Option<ProtoCoin>,
+}
+
+// MsgRepayResponse defines umee.leverage.v1.MsgRepayResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgRepayResponse {
+  #[prost(message, optional, tag = "1")]
+  pub repaid: Option<ProtoCoin>,
+}
+
+// MsgLiquidateResponse defines umee.leverage.v1.MsgLiquidateResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgLiquidateResponse {
+  #[prost(message, optional, tag = "1")]
+  pub repaid: Option<ProtoCoin>,
+  #[prost(message, optional, tag = "2")]
+  pub collateral: Option<ProtoCoin>,
+  #[prost(message, optional, tag = "3")]
+  pub reward: Option<ProtoCoin>,
+}
+
+// MsgSupplyCollateralResponse defines umee.leverage.v1.MsgSupplyCollateralResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSupplyCollateralResponse {
+  #[prost(message, optional, tag = "1")]
+  pub collateralized: Option<ProtoCoin>,
+}
+
+// umee.leverage.v1 queries
+
+// QueryLeverageParams defines umee.leverage.v1.QueryParams.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryLeverageParams {}
+
+// QueryLeverageParamsResponse defines umee.leverage.v1.QueryParamsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryLeverageParamsResponse {
+  #[prost(message, optional, tag = "1")]
+  pub params: Option<LeverageParams>,
+}
+
+// LeverageParams defines umee.leverage.v1.Params.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LeverageParams {
+  #[prost(string, tag = "1")]
+  pub complete_liquidation_threshold: String,
+  #[prost(string, tag = "2")]
+  pub minimum_close_factor: String,
+  #[prost(string, tag = "3")]
+  pub oracle_reward_factor: String,
+  #[prost(string, tag = "4")]
+  pub small_liquidation_size: String,
+  #[prost(string, tag = "5")]
+  pub direct_liquidation_fee: String,
+}
+
+// QueryRegisteredTokens defines umee.leverage.v1.QueryRegisteredTokens.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRegisteredTokens {
+  #[prost(string, tag = "1")]
+  pub base_denom: String,
+}
+
+// QueryRegisteredTokensResponse defines umee.leverage.v1.QueryRegisteredTokensResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRegisteredTokensResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub registry: Vec<ProtoToken>,
+}
+
+// ProtoToken defines umee.leverage.v1.Token.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoToken {
+  #[prost(string, tag = "1")]
+  pub base_denom: String,
+  #[prost(string, tag = "2")]
+  pub reserve_factor: String,
+  #[prost(string, tag = "3")]
+  pub collateral_weight: String,
+  #[prost(string, tag = "4")]
+  pub liquidation_threshold: String,
+  #[prost(string, tag = "5")]
+  pub base_borrow_rate: String,
+  #[prost(string, tag = "6")]
+  pub kink_borrow_rate: String,
+  #[prost(string, tag = "7")]
+  pub max_borrow_rate: String,
+  #[prost(string, tag = "8")]
+  pub kink_utilization: String,
+  #[prost(string, tag = "9")]
+  pub liquidation_incentive: String,
+  #[prost(string, tag = "10")]
+  pub symbol_denom: String,
+  #[prost(uint32, tag = "11")]
+  pub exponent: u32,
+  #[prost(bool, tag = "12")]
+  pub enable_msg_supply: bool,
+  #[prost(bool, tag = "13")]
+  pub enable_msg_borrow: bool,
+  #[prost(bool, tag = "14")]
+  pub blacklist: bool,
+  #[prost(string, tag = "15")]
+  pub max_collateral_share: String,
+  #[prost(string, tag = "16")]
+  pub max_supply_utilization: String,
+  #[prost(string, tag = "17")]
+  pub min_collateral_liquidity: String,
+  #[prost(string, tag = "18")]
+  pub max_supply: String,
+  #[prost(uint32, tag = "19")]
+  pub historic_medians: u32,
+}
+
+// QueryMarketSummary defines umee.leverage.v1.QueryMarketSummary.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMarketSummary {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+}
+
+// QueryMarketSummaryResponse defines umee.leverage.v1.QueryMarketSummaryResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMarketSummaryResponse {
+  #[prost(string, tag = "1")]
+  pub symbol_denom: String,
+  #[prost(uint32, tag = "2")]
+  pub exponent: u32,
+  #[prost(string, tag = "3")]
+  pub oracle_price: String,
+  #[prost(string, tag = "4")]
+  pub utoken_exchange_rate: String,
+  #[prost(string, tag = "5")]
+  pub supply_apy: String,
+  #[prost(string, tag = "6")]
+  pub borrow_apy: String,
+  #[prost(string, tag = "7")]
+  pub supplied: String,
+  #[prost(string, tag = "8")]
+  pub reserved: String,
+  #[prost(string, tag = "9")]
+  pub collateral: String,
+  #[prost(string, tag = "10")]
+  pub borrowed: String,
+  #[prost(string, tag = "11")]
+  pub liquidity: String,
+  #[prost(string, tag = "12")]
+  pub maximum_borrow: String,
+  #[prost(string, tag = "13")]
+  pub maximum_collateral: String,
+  #[prost(string, tag = "14")]
+  pub minimum_liquidity: String,
+  #[prost(string, tag = "15")]
+  pub utoken_supply: String,
+  #[prost(string, tag = "16")]
+  pub available_borrow: String,
+  #[prost(string, tag = "17")]
+  pub available_withdraw: String,
+  #[prost(string, tag = "18")]
+  pub available_collateralize: String,
+}
+
+// QueryAccountBalances defines umee.leverage.v1.QueryAccountBalances.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAccountBalances {
+  #[prost(string, tag = "1")]
+  pub address: String,
+}
+
+// QueryAccountBalancesResponse defines umee.leverage.v1.QueryAccountBalancesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAccountBalancesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub supplied: Vec<ProtoCoin>,
+  #[prost(message, repeated, tag = "2")]
+  pub collateral: Vec<ProtoCoin>,
+  #[prost(message, repeated, tag = "3")]
+  pub borrowed: Vec<ProtoCoin>,
+}
+
+// QueryAccountSummary defines umee.leverage.v1.QueryAccountSummary.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAccountSummary {
+  #[prost(string, tag = "1")]
+  pub address: String,
+}
+
+// QueryAccountSummaryResponse defines umee.leverage.v1.QueryAccountSummaryResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAccountSummaryResponse {
+  #[prost(string, tag = "1")]
+  pub supplied_value: String,
+  #[prost(string, tag = "2")]
+  pub collateral_value: String,
+  #[prost(string, tag = "3")]
+  pub borrowed_value: String,
+  #[prost(string, tag = "4")]
+  pub borrow_limit: String,
+  #[prost(string, tag = "5")]
+  pub liquidation_threshold: String,
+  #[prost(string, tag = "6")]
+  pub spendable_borrow_limit: String,
+  #[prost(string, tag = "7")]
+  pub historic_liquidation_threshold: String,
+}
+
+// QueryLiquidationTargets defines umee.leverage.v1.QueryLiquidationTargets.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryLiquidationTargets {}
+
+// QueryLiquidationTargetsResponse defines umee.leverage.v1.QueryLiquidationTargetsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryLiquidationTargetsResponse {
+  #[prost(string, repeated, tag = "1")]
+  pub targets: Vec<String>,
+}
+
+// QueryBadDebts defines umee.leverage.v1.QueryBadDebts.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryBadDebts {}
+
+// QueryBadDebtsResponse defines umee.leverage.v1.QueryBadDebtsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryBadDebtsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub targets: Vec<ProtoBadDebt>,
+}
+
+// ProtoBadDebt defines umee.leverage.v1.BadDebt.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoBadDebt {
+  #[prost(string, tag = "1")]
+  pub address: String,
+  #[prost(string, tag = "2")]
+  pub denom: String,
+}
+
+// QueryMaxWithdraw defines umee.leverage.v1.QueryMaxWithdraw.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMaxWithdraw {
+  #[prost(string, tag = "1")]
+  pub address: String,
+  #[prost(string, tag = "2")]
+  pub denom: String,
+}
+
+// QueryMaxWithdrawResponse defines umee.leverage.v1.QueryMaxWithdrawResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMaxWithdrawResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub u_tokens: Vec<ProtoCoin>,
+  #[prost(message, repeated, tag = "2")]
+  pub tokens: Vec<ProtoCoin>,
+}
+
+// QueryMaxBorrow defines umee.leverage.v1.QueryMaxBorrow.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMaxBorrow {
+  #[prost(string, tag = "1")]
+  pub address: String,
+  #[prost(string, tag = "2")]
+  pub denom: String,
+}
+
+// QueryMaxBorrowResponse defines umee.leverage.v1.QueryMaxBorrowResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMaxBorrowResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub tokens: Vec<ProtoCoin>,
+}
+
+// umee.oracle.v1 queries
+
+// ProtoDecCoin defines cosmos.base.v1beta1.DecCoin.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoDecCoin {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub amount: String,
+}
+
+// QueryExchangeRates defines umee.oracle.v1.QueryExchangeRates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryExchangeRates {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+}
+
+// QueryExchangeRatesResponse defines umee.oracle.v1.QueryExchangeRatesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryExchangeRatesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub exchange_rates: Vec<ProtoDecCoin>,
+}
+
+// QueryActiveExchangeRates defines umee.oracle.v1.QueryActiveExchangeRates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryActiveExchangeRates {}
+
+// QueryActiveExchangeRatesResponse defines umee.oracle.v1.QueryActiveExchangeRatesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryActiveExchangeRatesResponse {
+  #[prost(string, repeated, tag = "1")]
+  pub active_rates: Vec<String>,
+}
+
+// QueryFeederDelegation defines umee.oracle.v1.QueryFeederDelegation.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryFeederDelegation {
+  #[prost(string, tag = "1")]
+  pub validator_addr: String,
+}
+
+// QueryFeederDelegationResponse defines umee.oracle.v1.QueryFeederDelegationResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryFeederDelegationResponse {
+  #[prost(string, tag = "1")]
+  pub feeder_addr: String,
+}
+
+// QueryMissCounter defines umee.oracle.v1.QueryMissCounter.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMissCounter {
+  #[prost(string, tag = "1")]
+  pub validator_addr: String,
+}
+
+// QueryMissCounterResponse defines umee.oracle.v1.QueryMissCounterResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMissCounterResponse {
+  #[prost(uint64, tag = "1")]
+  pub miss_counter: u64,
+}
+
+// QuerySlashWindow defines umee.oracle.v1.QuerySlashWindow.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QuerySlashWindow {}
+
+// QuerySlashWindowResponse defines umee.oracle.v1.QuerySlashWindowResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QuerySlashWindowResponse {
+  #[prost(uint64, tag = "1")]
+  pub window_progress: u64,
+}
+
+// QueryAggregatePrevote defines umee.oracle.v1.QueryAggregatePrevote.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregatePrevote {
+  #[prost(string, tag = "1")]
+  pub validator_addr: String,
+}
+
+// QueryAggregatePrevoteResponse defines umee.oracle.v1.QueryAggregatePrevoteResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregatePrevoteResponse {
+  #[prost(message, optional, tag = "1")]
+  pub aggregate_prevote: Option<ProtoAggregateExchangeRatePrevote>,
+}
+
+// QueryAggregatePrevotes defines umee.oracle.v1.QueryAggregatePrevotes.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregatePrevotes {}
+
+// QueryAggregatePrevotesResponse defines umee.oracle.v1.QueryAggregatePrevotesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregatePrevotesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub aggregate_prevotes: Vec<ProtoAggregateExchangeRatePrevote>,
+}
+
+// ProtoAggregateExchangeRatePrevote defines umee.oracle.v1.AggregateExchangeRatePrevote.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoAggregateExchangeRatePrevote {
+  #[prost(string, tag = "1")]
+  pub hash: String,
+  #[prost(string, tag = "2")]
+  pub voter: String,
+  #[prost(uint64, tag = "3")]
+  pub submit_block: u64,
+}
+
+// QueryAggregateVote defines umee.oracle.v1.QueryAggregateVote.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregateVote {
+  #[prost(string, tag = "1")]
+  pub validator_addr: String,
+}
+
+// QueryAggregateVoteResponse defines umee.oracle.v1.QueryAggregateVoteResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregateVoteResponse {
+  #[prost(message, optional, tag = "1")]
+  pub aggregate_vote: Option<ProtoAggregateExchangeRateVote>,
+}
+
+// QueryAggregateVotes defines umee.oracle.v1.QueryAggregateVotes.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregateVotes {}
+
+// QueryAggregateVotesResponse defines umee.oracle.v1.QueryAggregateVotesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAggregateVotesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub aggregate_votes: Vec<ProtoAggregateExchangeRateVote>,
+}
+
+// ProtoAggregateExchangeRateVote defines umee.oracle.v1.AggregateExchangeRateVote.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoAggregateExchangeRateVote {
+  #[prost(message, repeated, tag = "1")]
+  pub exchange_rate_tuples: Vec<ProtoExchangeRateTuple>,
+  #[prost(string, tag = "2")]
+  pub voter: String,
+}
+
+// ProtoExchangeRateTuple defines umee.oracle.v1.ExchangeRateTuple.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoExchangeRateTuple {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub exchange_rate: String,
+}
+
+// QueryOracleParams defines umee.oracle.v1.QueryParams.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryOracleParams {}
+
+// QueryOracleParamsResponse defines umee.oracle.v1.QueryParamsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryOracleParamsResponse {
+  #[prost(message, optional, tag = "1")]
+  pub params: Option<OracleParams>,
+}
+
+// OracleParams defines umee.oracle.v1.Params.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OracleParams {
+  #[prost(uint64, tag = "1")]
+  pub vote_period: u64,
+  #[prost(string, tag = "2")]
+  pub vote_threshold: String,
+  #[prost(string, tag = "3")]
+  pub reward_band: String,
+  #[prost(uint64, tag = "4")]
+  pub reward_distribution_window: u64,
+  #[prost(message, repeated, tag = "5")]
+  pub accept_list: Vec<ProtoDenom>,
+  #[prost(string, tag = "6")]
+  pub slash_fraction: String,
+  #[prost(uint64, tag = "7")]
+  pub slash_window: u64,
+  #[prost(string, tag = "8")]
+  pub min_valid_per_window: String,
+  #[prost(uint64, tag = "9")]
+  pub stamp_period: u64,
+  #[prost(uint64, tag = "10")]
+  pub prune_period: u64,
+  #[prost(uint64, tag = "11")]
+  pub median_period: u64,
+  #[prost(message, repeated, tag = "12")]
+  pub historic_accept_list: Vec<ProtoDenom>,
+}
+
+// ProtoDenom defines umee.oracle.v1.Denom.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoDenom {
+  #[prost(string, tag = "1")]
+  pub base_denom: String,
+  #[prost(string, tag = "2")]
+  pub symbol_denom: String,
+  #[prost(uint32, tag = "3")]
+  pub exponent: u32,
+}
+
+// QueryMedians defines umee.oracle.v1.QueryMedians.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMedians {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(uint32, tag = "2")]
+  pub num_stamps: u32,
+}
+
+// QueryMediansResponse defines umee.oracle.v1.QueryMediansResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMediansResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub medians: Vec<ProtoDecCoin>,
+}
+
+// QueryMedianDeviations defines umee.oracle.v1.QueryMedianDeviations.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMedianDeviations {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+}
+
+// QueryMedianDeviationsResponse defines umee.oracle.v1.QueryMedianDeviationsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMedianDeviationsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub median_deviations: Vec<ProtoDecCoin>,
+}
+
+// umee.incentive.v1 queries
+
+// QueryIncentiveParams defines umee.incentive.v1.QueryParams.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIncentiveParams {}
+
+// QueryIncentiveParamsResponse defines umee.incentive.v1.QueryParamsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIncentiveParamsResponse {
+  #[prost(message, optional, tag = "1")]
+  pub params: Option<IncentiveParams>,
+}
+
+// IncentiveParams defines umee.incentive.v1.Params.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct IncentiveParams {
+  #[prost(uint32, tag = "1")]
+  pub max_unbondings: u32,
+  #[prost(int64, tag = "2")]
+  pub unbonding_duration: i64,
+  #[prost(string, tag = "3")]
+  pub emergency_unbond_fee: String,
+}
+
+// QueryTotalBonded defines umee.incentive.v1.QueryTotalBonded.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryTotalBonded {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+}
+
+// QueryTotalBondedResponse defines umee.incentive.v1.QueryTotalBondedResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryTotalBondedResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub bonded: Vec<ProtoCoin>,
+}
+
+// QueryTotalUnbonding defines umee.incentive.v1.QueryTotalUnbonding.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryTotalUnbonding {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+}
+
+// QueryTotalUnbondingResponse defines umee.incentive.v1.QueryTotalUnbondingResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryTotalUnbondingResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub unbonding: Vec<ProtoCoin>,
+}
+
+// QueryAccountBonds defines umee.incentive.v1.QueryAccountBonds.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAccountBonds {
+  #[prost(string, tag = "1")]
+  pub address: String,
+}
+
+// QueryAccountBondsResponse defines umee.incentive.v1.QueryAccountBondsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAccountBondsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub bonded: Vec<ProtoCoin>,
+  #[prost(message, repeated, tag = "2")]
+  pub unbonding: Vec<ProtoCoin>,
+  #[prost(message, repeated, tag = "3")]
+  pub unbondings: Vec<ProtoUnbonding>,
+}
+
+// ProtoUnbonding defines umee.incentive.v1.Unbonding.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoUnbonding {
+  #[prost(int64, tag = "1")]
+  pub start: i64,
+  #[prost(int64, tag = "2")]
+  pub end: i64,
+  #[prost(message, optional, tag = "3")]
+  pub u_token: Option<ProtoCoin>,
+}
+
+// QueryPendingRewards defines umee.incentive.v1.QueryPendingRewards.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryPendingRewards {
+  #[prost(string, tag = "1")]
+  pub address: String,
+}
+
+// QueryPendingRewardsResponse defines umee.incentive.v1.QueryPendingRewardsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryPendingRewardsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub rewards: Vec<ProtoCoin>,
+}
+
+// QueryCompletedIncentivePrograms defines umee.incentive.v1.QueryCompletedIncentivePrograms.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryCompletedIncentivePrograms {}
+
+// QueryOngoingIncentivePrograms defines umee.incentive.v1.QueryOngoingIncentivePrograms.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryOngoingIncentivePrograms {}
+
+// QueryUpcomingIncentivePrograms defines umee.incentive.v1.QueryUpcomingIncentivePrograms.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryUpcomingIncentivePrograms {}
+
+// QueryIncentiveProgramsResponse defines the umee.incentive.v1 Completed,
+// Ongoing and Upcoming IncentiveProgramsResponse, they share the same fields.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIncentiveProgramsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub programs: Vec<ProtoIncentiveProgram>,
+}
+
+// QueryIncentiveProgram defines umee.incentive.v1.QueryIncentiveProgram.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIncentiveProgram {
+  #[prost(uint32, tag = "1")]
+  pub id: u32,
+}
+
+// QueryIncentiveProgramResponse defines umee.incentive.v1.QueryIncentiveProgramResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIncentiveProgramResponse {
+  #[prost(message, optional, tag = "1")]
+  pub program: Option<ProtoIncentiveProgram>,
+}
+
+// ProtoIncentiveProgram defines umee.incentive.v1.IncentiveProgram.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoIncentiveProgram {
+  #[prost(uint32, tag = "1")]
+  pub id: u32,
+  #[prost(int64, tag = "2")]
+  pub start_time: i64,
+  #[prost(int64, tag = "3")]
+  pub duration: i64,
+  #[prost(string, tag = "4")]
+  pub u_token: String,
+  #[prost(bool, tag = "5")]
+  pub funded: bool,
+  #[prost(message, optional, tag = "6")]
+  pub total_rewards: Option<ProtoCoin>,
+  #[prost(message, optional, tag = "7")]
+  pub remaining_rewards: Option<ProtoCoin>,
+}
+
+// QueryCurrentRates defines umee.incentive.v1.QueryCurrentRates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryCurrentRates {
+  #[prost(string, tag = "1")]
+  pub u_token: String,
+}
+
+// QueryCurrentRatesResponse defines umee.incentive.v1.QueryCurrentRatesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryCurrentRatesResponse {
+  #[prost(message, optional, tag = "1")]
+  pub reference_bond: Option<ProtoCoin>,
+  #[prost(message, repeated, tag = "2")]
+  pub rewards: Vec<ProtoCoin>,
+}
+
+// QueryActualRates defines umee.incentive.v1.QueryActualRates.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryActualRates {
+  #[prost(string, tag = "1")]
+  pub u_token: String,
+}
+
+// QueryActualRatesResponse defines umee.incentive.v1.QueryActualRatesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryActualRatesResponse {
+  #[prost(string, tag = "1")]
+  pub apy: String,
+}
+
+// QueryLastRewardTime defines umee.incentive.v1.QueryLastRewardTime.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryLastRewardTime {}
+
+// QueryLastRewardTimeResponse defines umee.incentive.v1.QueryLastRewardTimeResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryLastRewardTimeResponse {
+  #[prost(int64, tag = "1")]
+  pub time: i64,
+}
+
+// umee.metoken.v1 queries
+
+// QueryMetokenParams defines umee.metoken.v1.QueryParams.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMetokenParams {}
+
+// QueryMetokenParamsResponse defines umee.metoken.v1.QueryParamsResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMetokenParamsResponse {
+  #[prost(message, optional, tag = "1")]
+  pub params: Option<MetokenParams>,
+}
+
+// MetokenParams defines umee.metoken.v1.Params.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MetokenParams {
+  #[prost(int64, tag = "1")]
+  pub rebalancing_frequency: i64,
+  #[prost(int64, tag = "2")]
+  pub claiming_frequency: i64,
+}
+
+// QueryIndexes defines umee.metoken.v1.QueryIndexes.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIndexes {
+  #[prost(string, tag = "1")]
+  pub metoken_denom: String,
+}
+
+// QueryIndexesResponse defines umee.metoken.v1.QueryIndexesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIndexesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub registry: Vec<ProtoIndex>,
+}
+
+// ProtoIndex defines umee.metoken.v1.Index.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoIndex {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub max_supply: String,
+  #[prost(uint32, tag = "3")]
+  pub exponent: u32,
+  #[prost(message, optional, tag = "4")]
+  pub fee: Option<ProtoFee>,
+  #[prost(message, repeated, tag = "5")]
+  pub accepted_assets: Vec<ProtoAcceptedAsset>,
+}
+
+// ProtoFee defines umee.metoken.v1.Fee.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoFee {
+  #[prost(string, tag = "1")]
+  pub min_fee: String,
+  #[prost(string, tag = "2")]
+  pub balanced_fee: String,
+  #[prost(string, tag = "3")]
+  pub max_fee: String,
+}
+
+// ProtoAcceptedAsset defines umee.metoken.v1.AcceptedAsset.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoAcceptedAsset {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub reserve_portion: String,
+  #[prost(string, tag = "3")]
+  pub target_allocation: String,
+}
+
+// QuerySwapFee defines umee.metoken.v1.QuerySwapFee.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QuerySwapFee {
+  #[prost(message, optional, tag = "1")]
+  pub asset: Option<ProtoCoin>,
+  #[prost(string, tag = "2")]
+  pub metoken_denom: String,
+}
+
+// QueryRedeemFee defines umee.metoken.v1.QueryRedeemFee.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRedeemFee {
+  #[prost(message, optional, tag = "1")]
+  pub metoken: Option<ProtoCoin>,
+  #[prost(string, tag = "2")]
+  pub asset_denom: String,
+}
+
+// QueryFeeResponse defines the umee.metoken.v1 SwapFeeResponse and
+// RedeemFeeResponse, they share the same fields.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryFeeResponse {
+  #[prost(message, optional, tag = "1")]
+  pub asset: Option<ProtoCoin>,
+}
+
+// QueryIndexBalances defines umee.metoken.v1.QueryIndexBalances.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIndexBalances {
+  #[prost(string, tag = "1")]
+  pub metoken_denom: String,
+}
+
+// QueryIndexBalancesResponse defines umee.metoken.v1.QueryIndexBalancesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIndexBalancesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub index_balances: Vec<ProtoIndexBalances>,
+  #[prost(message, repeated, tag = "2")]
+  pub prices: Vec<ProtoIndexPrices>,
+}
+
+// ProtoIndexBalances defines umee.metoken.v1.IndexBalances.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoIndexBalances {
+  #[prost(message, optional, tag = "1")]
+  pub metoken_supply: Option<ProtoCoin>,
+  #[prost(message, repeated, tag = "2")]
+  pub asset_balances: Vec<ProtoAssetBalance>,
+}
+
+// ProtoAssetBalance defines umee.metoken.v1.AssetBalance.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoAssetBalance {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub leveraged: String,
+  #[prost(string, tag = "3")]
+  pub reserved: String,
+  #[prost(string, tag = "4")]
+  pub fees: String,
+  #[prost(string, tag = "5")]
+  pub interest: String,
+}
+
+// QueryIndexPrices defines umee.metoken.v1.QueryIndexPrices.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIndexPrices {
+  #[prost(string, tag = "1")]
+  pub metoken_denom: String,
+}
+
+// QueryIndexPricesResponse defines umee.metoken.v1.QueryIndexPricesResponse.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIndexPricesResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub prices: Vec<ProtoIndexPrices>,
+}
+
+// ProtoIndexPrices defines umee.metoken.v1.IndexPrices.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoIndexPrices {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(string, tag = "2")]
+  pub price: String,
+  #[prost(uint32, tag = "3")]
+  pub exponent: u32,
+  #[prost(message, repeated, tag = "4")]
+  pub assets: Vec<ProtoAssetPrice>,
+}
+
+// ProtoAssetPrice defines umee.metoken.v1.AssetPrice.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoAssetPrice {
+  #[prost(string, tag = "1")]
+  pub base_denom: String,
+  #[prost(string, tag = "2")]
+  pub symbol_denom: String,
+  #[prost(string, tag = "3")]
+  pub price: String,
+  #[prost(uint32, tag = "4")]
+  pub exponent: u32,
+  #[prost(string, tag = "5")]
+  pub swap_rate: String,
+  #[prost(string, tag = "6")]
+  pub redeem_rate: String,
+}
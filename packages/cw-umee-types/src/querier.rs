@@ -0,0 +1,646 @@
+use crate::error::ContractError;
+use crate::query::{StructUmeeQuery, UmeeQuery};
+use crate::query_incentive::{
+  AccountBondsParams, AccountBondsResponse, ActualRatesParams, ActualRatesResponse,
+  CompletedIncentiveProgramsParams, CompletedIncentiveProgramsResponse, CurrentRatesParams,
+  CurrentRatesResponse, IncentiveParametersParams, IncentiveParametersResponse,
+  IncentiveProgramParams, IncentiveProgramResponse, LastRewardTimeParams, LastRewardTimeResponse,
+  OngoingIncentiveProgramsParams, OngoingIncentiveProgramsResponse, PendingRewardsParams,
+  PendingRewardsResponse, TotalBondedParams, TotalBondedResponse, TotalUnbondingParams,
+  TotalUnbondingResponse, UmeeQueryIncentive, UpcomingIncentiveProgramsParams,
+  UpcomingIncentiveProgramsResponse,
+};
+use crate::query_leverage::{
+  AccountBalancesParams, AccountBalancesResponse, AccountSummaryParams, AccountSummaryResponse,
+  BadDebtsParams, BadDebtsResponse, LeverageParametersParams, LeverageParametersResponse,
+  LiquidationTargetsParams, LiquidationTargetsResponse, MarketSummaryParams, MarketSummaryResponse,
+  MaxBorrowParams, MaxBorrowResponse, MaxWithdrawParams, MaxWithdrawResponse,
+  RegisteredTokensParams, RegisteredTokensResponse, UmeeQueryLeverage,
+};
+use crate::query_metoken::{
+  MetokenIndexPricesParams, MetokenIndexPricesResponse, MetokenIndexbalancesParams,
+  MetokenIndexbalancesResponse, MetokenIndexesParams, MetokenIndexesResponse,
+  MetokenParametersParams, MetokenParametersResponse, MetokenRedeemfeeParams,
+  MetokenRedeemfeeResponse, MetokenSwapfeeParams, MetokenSwapfeeResponse, UmeeQueryMeToken,
+};
+use crate::query_oracle::{
+  ActiveExchangeRatesParams, ActiveExchangeRatesResponse, AggregatePrevoteParams,
+  AggregatePrevoteResponse, AggregatePrevotesParams, AggregatePrevotesResponse,
+  AggregateVoteParams, AggregateVoteResponse, AggregateVotesParams, AggregateVotesResponse,
+  ExchangeRatesParams, ExchangeRatesResponse, FeederDelegationParams, FeederDelegationResponse,
+  MedianDeviationsParams, MedianDeviationsParamsResponse, MediansParams, MediansParamsResponse,
+  MissCounterParams, MissCounterResponse, OracleParametersParams, OracleParametersResponse,
+  SlashWindowParams, SlashWindowResponse, UmeeQueryOracle,
+};
+#[cfg(feature = "stargate")]
+use crate::query_stargate::StargateQuery;
+#[cfg(feature = "stargate")]
+use cosmwasm_std::Empty;
+use cosmwasm_std::{
+  from_json, to_json_vec, Binary, ContractResult, CustomQuery, QuerierWrapper, QueryRequest,
+  StdError, SystemResult,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// UmeeQuerier wraps a QuerierWrapper and exposes one typed method per
+// umee native module query, so contracts don't need to build the
+// StructUmeeQuery and decode the chain response by hand.
+//
+// It is generic over the custom query of the wrapped querier, because the
+// request is sent as raw bytes, so it works both with Deps<StructUmeeQuery>
+// and with contracts that keep the default Deps.
+//
+// The transport is chosen by the constructor: custom sends the queries as
+// QueryRequest::Custom to the "umee" binding and, with the stargate feature,
+// stargate sends them as QueryRequest::Stargate to the gRPC path of the umee
+// modules. The feature only makes stargate available, it doesn't change the
+// transport of the queriers built with custom.
+pub struct UmeeQuerier<'a, C: CustomQuery = StructUmeeQuery> {
+  querier: &'a QuerierWrapper<'a, C>,
+  #[cfg(feature = "stargate")]
+  transport: QueryTransport,
+}
+
+// QueryTransport defines how the UmeeQuerier sends the umee queries.
+#[cfg(feature = "stargate")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QueryTransport {
+  // Custom sends the queries as QueryRequest::Custom to the "umee" binding.
+  Custom,
+  // Stargate sends the protobuf encoded queries as QueryRequest::Stargate.
+  Stargate,
+}
+
+impl<'a, C: CustomQuery> UmeeQuerier<'a, C> {
+  // custom returns the querier that sends the queries to the "umee" custom
+  // binding.
+  pub fn custom(querier: &'a QuerierWrapper<'a, C>) -> Self {
+    UmeeQuerier {
+      querier,
+      #[cfg(feature = "stargate")]
+      transport: QueryTransport::Custom,
+    }
+  }
+
+  // stargate returns the querier that sends the queries to the gRPC path of
+  // the umee modules.
+  #[cfg(feature = "stargate")]
+  pub fn stargate(querier: &'a QuerierWrapper<'a, C>) -> Self {
+    UmeeQuerier {
+      querier,
+      transport: QueryTransport::Stargate,
+    }
+  }
+
+  #[cfg(not(feature = "stargate"))]
+  fn dispatch<P, T: DeserializeOwned>(
+    &self,
+    params: P,
+    custom: fn(P) -> StructUmeeQuery,
+  ) -> Result<T, ContractError> {
+    self.query(custom(params))
+  }
+
+  // dispatch sends the query params with the selected transport.
+  #[cfg(feature = "stargate")]
+  fn dispatch<P: StargateQuery<Response = T>, T: DeserializeOwned>(
+    &self,
+    params: P,
+    custom: fn(P) -> StructUmeeQuery,
+  ) -> Result<T, ContractError> {
+    match self.transport {
+      QueryTransport::Custom => self.query(custom(params)),
+      QueryTransport::Stargate => self.query_stargate(params),
+    }
+  }
+
+  // query sends the umee query to the chain and decodes the response, the
+  // querier errors are returned as QuerierSystem and QuerierContract.
+  fn query<T: DeserializeOwned>(&self, umee_query: StructUmeeQuery) -> Result<T, ContractError> {
+    let (module, query) = query_name(&umee_query);
+    let request: QueryRequest<StructUmeeQuery> = QueryRequest::Custom(umee_query);
+    let value = self.raw_query(&request, module, query)?;
+    Ok(from_json(&value)?)
+  }
+
+  // query_stargate sends the query to the gRPC path of the umee module and
+  // decodes the protobuf response.
+  #[cfg(feature = "stargate")]
+  fn query_stargate<P: StargateQuery>(&self, params: P) -> Result<P::Response, ContractError> {
+    let (module, query) = stargate_query_name(P::PATH);
+    let request: QueryRequest<Empty> = params.to_stargate_query();
+    let value = self.raw_query(&request, module, query)?;
+    Ok(params.decode_response(value.as_slice())?)
+  }
+
+  fn raw_query<Q: Serialize>(
+    &self,
+    request: &Q,
+    module: String,
+    query: String,
+  ) -> Result<Binary, ContractError> {
+    let raw = to_json_vec(request).map_err(|serialize_err| {
+      StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
+    })?;
+    match self.querier.raw_query(&raw) {
+      SystemResult::Err(system_err) => Err(ContractError::QuerierSystem {
+        message: system_err.to_string(),
+      }),
+      SystemResult::Ok(ContractResult::Err(message)) => Err(ContractError::QuerierContract {
+        module,
+        query,
+        message,
+      }),
+      SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
+    }
+  }
+
+  // leverage
+
+  pub fn leverage_parameters(&self) -> Result<LeverageParametersResponse, ContractError> {
+    self.dispatch(
+      LeverageParametersParams {},
+      StructUmeeQuery::leverage_parameters,
+    )
+  }
+
+  pub fn registered_tokens(&self) -> Result<RegisteredTokensResponse, ContractError> {
+    self.dispatch(
+      RegisteredTokensParams {},
+      StructUmeeQuery::registered_tokens,
+    )
+  }
+
+  pub fn market_summary(
+    &self,
+    params: MarketSummaryParams,
+  ) -> Result<MarketSummaryResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::market_summary)
+  }
+
+  pub fn account_balances(
+    &self,
+    params: AccountBalancesParams,
+  ) -> Result<AccountBalancesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::account_balances)
+  }
+
+  pub fn account_summary(
+    &self,
+    params: AccountSummaryParams,
+  ) -> Result<AccountSummaryResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::account_summary)
+  }
+
+  pub fn liquidation_targets(&self) -> Result<LiquidationTargetsResponse, ContractError> {
+    self.dispatch(
+      LiquidationTargetsParams {},
+      StructUmeeQuery::liquidation_targets,
+    )
+  }
+
+  pub fn bad_debts(&self) -> Result<BadDebtsResponse, ContractError> {
+    self.dispatch(BadDebtsParams {}, StructUmeeQuery::bad_debts_parameters)
+  }
+
+  pub fn max_withdraw(
+    &self,
+    params: MaxWithdrawParams,
+  ) -> Result<MaxWithdrawResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::max_withdraw_params)
+  }
+
+  pub fn max_borrow(&self, params: MaxBorrowParams) -> Result<MaxBorrowResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::max_borrow_params)
+  }
+
+  // oracle
+
+  pub fn exchange_rates(
+    &self,
+    params: ExchangeRatesParams,
+  ) -> Result<ExchangeRatesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::exchange_rates)
+  }
+
+  pub fn active_exchange_rates(&self) -> Result<ActiveExchangeRatesResponse, ContractError> {
+    self.dispatch(
+      ActiveExchangeRatesParams {},
+      StructUmeeQuery::active_exchange_rates,
+    )
+  }
+
+  pub fn feeder_delegation(
+    &self,
+    params: FeederDelegationParams,
+  ) -> Result<FeederDelegationResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::feeder_delegation)
+  }
+
+  pub fn miss_counter(
+    &self,
+    params: MissCounterParams,
+  ) -> Result<MissCounterResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::miss_counter)
+  }
+
+  pub fn slash_window(&self) -> Result<SlashWindowResponse, ContractError> {
+    self.dispatch(SlashWindowParams {}, StructUmeeQuery::slash_window)
+  }
+
+  pub fn aggregate_prevote(
+    &self,
+    params: AggregatePrevoteParams,
+  ) -> Result<AggregatePrevoteResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::aggregate_prevote)
+  }
+
+  pub fn aggregate_prevotes(&self) -> Result<AggregatePrevotesResponse, ContractError> {
+    self.dispatch(
+      AggregatePrevotesParams {},
+      StructUmeeQuery::aggregate_prevotes,
+    )
+  }
+
+  pub fn aggregate_vote(
+    &self,
+    params: AggregateVoteParams,
+  ) -> Result<AggregateVoteResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::aggregate_vote)
+  }
+
+  pub fn aggregate_votes(&self) -> Result<AggregateVotesResponse, ContractError> {
+    self.dispatch(AggregateVotesParams {}, StructUmeeQuery::aggregate_votes)
+  }
+
+  pub fn oracle_parameters(&self) -> Result<OracleParametersResponse, ContractError> {
+    self.dispatch(
+      OracleParametersParams {},
+      StructUmeeQuery::oracle_parameters,
+    )
+  }
+
+  pub fn medians(&self, params: MediansParams) -> Result<MediansParamsResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::medians_params)
+  }
+
+  pub fn median_deviations(
+    &self,
+    params: MedianDeviationsParams,
+  ) -> Result<MedianDeviationsParamsResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::median_deviations_params)
+  }
+
+  // incentive
+
+  pub fn incentive_parameters(&self) -> Result<IncentiveParametersResponse, ContractError> {
+    self.dispatch(
+      IncentiveParametersParams {},
+      StructUmeeQuery::incentive_params,
+    )
+  }
+
+  pub fn total_bonded(
+    &self,
+    params: TotalBondedParams,
+  ) -> Result<TotalBondedResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::total_bonded)
+  }
+
+  pub fn total_unbonding(
+    &self,
+    params: TotalUnbondingParams,
+  ) -> Result<TotalUnbondingResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::total_unbonding)
+  }
+
+  pub fn account_bonds(
+    &self,
+    params: AccountBondsParams,
+  ) -> Result<AccountBondsResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::account_bonds)
+  }
+
+  pub fn pending_rewards(
+    &self,
+    params: PendingRewardsParams,
+  ) -> Result<PendingRewardsResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::pending_rewards)
+  }
+
+  pub fn completed_incentive_programs(
+    &self,
+  ) -> Result<CompletedIncentiveProgramsResponse, ContractError> {
+    self.dispatch(
+      CompletedIncentiveProgramsParams {},
+      StructUmeeQuery::completed_incentive_programs,
+    )
+  }
+
+  pub fn ongoing_incentive_programs(
+    &self,
+  ) -> Result<OngoingIncentiveProgramsResponse, ContractError> {
+    self.dispatch(
+      OngoingIncentiveProgramsParams {},
+      StructUmeeQuery::ongoing_incentive_programs,
+    )
+  }
+
+  pub fn upcoming_incentive_programs(
+    &self,
+  ) -> Result<UpcomingIncentiveProgramsResponse, ContractError> {
+    self.dispatch(
+      UpcomingIncentiveProgramsParams {},
+      StructUmeeQuery::upcoming_incentive_programs,
+    )
+  }
+
+  pub fn incentive_program(
+    &self,
+    params: IncentiveProgramParams,
+  ) -> Result<IncentiveProgramResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::incentive_program)
+  }
+
+  pub fn current_rates(
+    &self,
+    params: CurrentRatesParams,
+  ) -> Result<CurrentRatesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::current_rates)
+  }
+
+  pub fn actual_rates(
+    &self,
+    params: ActualRatesParams,
+  ) -> Result<ActualRatesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::actual_rates)
+  }
+
+  pub fn last_reward_time(&self) -> Result<LastRewardTimeResponse, ContractError> {
+    self.dispatch(LastRewardTimeParams {}, StructUmeeQuery::last_reward_time)
+  }
+
+  // metoken
+
+  pub fn metoken_parameters(&self) -> Result<MetokenParametersResponse, ContractError> {
+    self.dispatch(
+      MetokenParametersParams {},
+      StructUmeeQuery::metoken_parameters,
+    )
+  }
+
+  pub fn metoken_indexes(
+    &self,
+    params: MetokenIndexesParams,
+  ) -> Result<MetokenIndexesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::metoken_indexes)
+  }
+
+  pub fn metoken_swapfee(
+    &self,
+    params: MetokenSwapfeeParams,
+  ) -> Result<MetokenSwapfeeResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::metoken_swapfee)
+  }
+
+  pub fn metoken_redeemfee(
+    &self,
+    params: MetokenRedeemfeeParams,
+  ) -> Result<MetokenRedeemfeeResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::metoken_redeemfee)
+  }
+
+  pub fn metoken_indexbalances(
+    &self,
+    params: MetokenIndexbalancesParams,
+  ) -> Result<MetokenIndexbalancesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::metoken_indexbalances)
+  }
+
+  pub fn metoken_index_prices(
+    &self,
+    params: MetokenIndexPricesParams,
+  ) -> Result<MetokenIndexPricesResponse, ContractError> {
+    self.dispatch(params, StructUmeeQuery::metoken_indexprice)
+  }
+}
+
+// query_name returns the module and the name of the query, as in the json
+// of the UmeeQuery enum, e.g. leverage and market_summary.
+pub fn query_name(umee_query: &StructUmeeQuery) -> (String, String) {
+  let (module, query) = match umee_query.umee_query() {
+    Some(UmeeQuery::Leverage(query)) => ("leverage", leverage_query_name(&query)),
+    Some(UmeeQuery::Oracle(query)) => ("oracle", oracle_query_name(&query)),
+    Some(UmeeQuery::Incentive(query)) => ("incentive", incentive_query_name(&query)),
+    Some(UmeeQuery::Metoken(query)) => ("metoken", metoken_query_name(&query)),
+    None => ("umee", "unknown"),
+  };
+  (module.to_string(), query.to_string())
+}
+
+fn leverage_query_name(query: &UmeeQueryLeverage) -> &'static str {
+  match query {
+    UmeeQueryLeverage::LeverageParameters(_) => "leverage_parameters",
+    UmeeQueryLeverage::RegisteredTokens(_) => "registered_tokens",
+    UmeeQueryLeverage::MarketSummary(_) => "market_summary",
+    UmeeQueryLeverage::AccountBalances(_) => "account_balances",
+    UmeeQueryLeverage::AccountSummary(_) => "account_summary",
+    UmeeQueryLeverage::LiquidationTargets(_) => "liquidation_targets",
+    UmeeQueryLeverage::BadDebts(_) => "bad_debts",
+    UmeeQueryLeverage::MaxWithdraw(_) => "max_withdraw",
+    UmeeQueryLeverage::MaxBorrow(_) => "max_borrow",
+  }
+}
+
+fn oracle_query_name(query: &UmeeQueryOracle) -> &'static str {
+  match query {
+    UmeeQueryOracle::ExchangeRates(_) => "exchange_rates",
+    UmeeQueryOracle::ActiveExchangeRates(_) => "active_exchange_rates",
+    UmeeQueryOracle::FeederDelegation(_) => "feeder_delegation",
+    UmeeQueryOracle::MissCounter(_) => "miss_counter",
+    UmeeQueryOracle::SlashWindow(_) => "slash_window",
+    UmeeQueryOracle::AggregatePrevote(_) => "aggregate_prevote",
+    UmeeQueryOracle::AggregatePrevotes(_) => "aggregate_prevotes",
+    UmeeQueryOracle::AggregateVote(_) => "aggregate_vote",
+    UmeeQueryOracle::AggregateVotes(_) => "aggregate_votes",
+    UmeeQueryOracle::OracleParameters(_) => "oracle_parameters",
+    UmeeQueryOracle::Medians(_) => "medians",
+    UmeeQueryOracle::MedianDeviations(_) => "median_deviations",
+  }
+}
+
+fn incentive_query_name(query: &UmeeQueryIncentive) -> &'static str {
+  match query {
+    UmeeQueryIncentive::IncentiveParameters(_) => "incentive_parameters",
+    UmeeQueryIncentive::TotalBonded(_) => "total_bonded",
+    UmeeQueryIncentive::TotalUnbonding(_) => "total_unbonding",
+    UmeeQueryIncentive::AccountBonds(_) => "account_bonds",
+    UmeeQueryIncentive::PendingRewards(_) => "pending_rewards",
+    UmeeQueryIncentive::CompletedIncentivePrograms(_) => "completed_incentive_programs",
+    UmeeQueryIncentive::OngoingIncentivePrograms(_) => "ongoing_incentive_programs",
+    UmeeQueryIncentive::UpcomingIncentivePrograms(_) => "upcoming_incentive_programs",
+    UmeeQueryIncentive::IncentiveProgram(_) => "incentive_program",
+    UmeeQueryIncentive::CurrentRates(_) => "current_rates",
+    UmeeQueryIncentive::ActualRates(_) => "actual_rates",
+    UmeeQueryIncentive::LastRewardTime(_) => "last_reward_time",
+  }
+}
+
+fn metoken_query_name(query: &UmeeQueryMeToken) -> &'static str {
+  match query {
+    UmeeQueryMeToken::MetokenParameters(_) => "metoken_parameters",
+    UmeeQueryMeToken::MetokenIndexes(_) => "metoken_indexes",
+    UmeeQueryMeToken::MetokenSwapfee(_) => "metoken_swapfee",
+    UmeeQueryMeToken::MetokenRedeemfee(_) => "metoken_redeemfee",
+    UmeeQueryMeToken::MetokenIndexbalances(_) => "metoken_indexbalances",
+    UmeeQueryMeToken::MetokenIndexPrices(_) => "metoken_index_prices",
+  }
+}
+
+// stargate_query_name returns the module and the name of the query of the
+// gRPC path, e.g. leverage and MarketSummary of
+// /umee.leverage.v1.Query/MarketSummary.
+#[cfg(feature = "stargate")]
+fn stargate_query_name(path: &str) -> (String, String) {
+  let mut parts = path.trim_start_matches('/').split('/');
+  let service = parts.next().unwrap_or_default();
+  let module = service.split('.').nth(1).unwrap_or(service);
+  (
+    module.to_string(),
+    parts.next().unwrap_or_default().to_string(),
+  )
+}
+
+#[cfg(all(test, feature = "multitest"))]
+mod tests {
+  use super::*;
+  use crate::multitest::umee_app;
+  use crate::{
+    AggregateExchangeRatePrevote, AggregateExchangeRateVote, BadDebt, Denom, ExchangeRateTuple,
+    LeverageParameters, OracleParameters, Token,
+  };
+  use cosmwasm_std::{from_json, to_json_vec, Decimal, Decimal256};
+
+  #[test]
+  fn response_accessors() {
+    let token = Token::new("uumee", "UMEE", 6)
+      .with_collateral_weight(Decimal::percent(60))
+      .with_liquidation_threshold(Decimal::percent(75))
+      .with_max_collateral_share(Decimal::one())
+      .with_max_supply_utilization(Decimal::percent(90))
+      .with_enable_msg_supply(true)
+      .with_enable_msg_borrow(true);
+    let app = umee_app(|router, _, storage| {
+      router.custom.register_token(storage, token).unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::percent(50))
+        .unwrap();
+    });
+    let wrapper = app.wrap();
+    let querier = UmeeQuerier::custom(&wrapper);
+
+    // the chain responses are read through the accessors
+    let registry = querier.registered_tokens().unwrap().registry;
+    assert_eq!(registry[0].base_denom(), "uumee");
+    assert_eq!(registry[0].collateral_weight(), Decimal::percent(60));
+    assert_eq!(registry[0].liquidation_threshold(), Decimal::percent(75));
+    assert!(registry[0].enable_msg_borrow());
+    assert!(!registry[0].blacklist());
+    let summary = querier
+      .market_summary(MarketSummaryParams {
+        denom: String::from("uumee"),
+      })
+      .unwrap();
+    assert_eq!(summary.symbol_denom(), "UMEE");
+    assert_eq!(summary.exponent(), 6);
+    assert_eq!(summary.oracle_price(), Decimal256::percent(50));
+    assert!(summary.supplied().is_zero());
+    let params = querier.leverage_parameters().unwrap().params;
+    assert_eq!(params.minimum_close_factor(), Decimal256::percent(5));
+    let params = querier.oracle_parameters().unwrap().params;
+    assert_eq!(params.vote_period(), 5);
+
+    // the builders keep the wire format of the chain
+    let summary = MarketSummaryResponse::default()
+      .with_symbol_denom("UMEE")
+      .with_exponent(6)
+      .with_oracle_price(Decimal256::percent(50));
+    let json = String::from_utf8(to_json_vec(&summary).unwrap()).unwrap();
+    assert!(json.contains(r#""symbol_denom":"UMEE""#));
+    assert!(json.contains(r#""oracle_price":"0.5""#));
+    assert_eq!(
+      from_json::<MarketSummaryResponse>(to_json_vec(&summary).unwrap()).unwrap(),
+      summary
+    );
+    assert_eq!(
+      to_json_vec(&BadDebt::new("borrower", "uumee")).unwrap(),
+      br#"{"address":"borrower","denom":"uumee"}"#.to_vec()
+    );
+    let vote = AggregateExchangeRateVote::new(
+      vec![ExchangeRateTuple::new("UMEE", Decimal256::percent(50))],
+      "voter",
+    );
+    assert_eq!(
+      to_json_vec(&vote).unwrap(),
+      br#"{"exchange_rate_tuples":[{"denom":"UMEE","exchange_rate":"0.5"}],"voter":"voter"}"#
+        .to_vec()
+    );
+    assert_eq!(vote.exchange_rate_tuples()[0].denom(), "UMEE");
+    let prevote = AggregateExchangeRatePrevote::new("hash", "voter", 10);
+    assert_eq!(prevote.submit_block(), 10);
+    let denom = Denom::new("uumee", "UMEE", 6);
+    assert_eq!(
+      OracleParameters::default()
+        .with_accept_list(vec![denom.clone()])
+        .accept_list(),
+      &[denom]
+    );
+    assert_eq!(
+      LeverageParameters::default()
+        .with_direct_liquidation_fee(Decimal256::percent(5))
+        .direct_liquidation_fee(),
+      Decimal256::percent(5)
+    );
+  }
+
+  #[test]
+  fn query_names() {
+    let names = [
+      (
+        StructUmeeQuery::market_summary(MarketSummaryParams {
+          denom: "uumee".to_string(),
+        }),
+        ("leverage", "market_summary"),
+      ),
+      (
+        StructUmeeQuery::medians_params(MediansParams {
+          denom: "UMEE".to_string(),
+        }),
+        ("oracle", "medians"),
+      ),
+      (
+        StructUmeeQuery::current_rates(CurrentRatesParams {
+          u_token: "u/uumee".to_string(),
+        }),
+        ("incentive", "current_rates"),
+      ),
+      (
+        StructUmeeQuery::metoken_indexprice(MetokenIndexPricesParams {
+          metoken_denom: "me/USD".to_string(),
+        }),
+        ("metoken", "metoken_index_prices"),
+      ),
+    ];
+    for (umee_query, (module, query)) in names {
+      assert_eq!(
+        query_name(&umee_query),
+        (module.to_string(), query.to_string())
+      );
+      // the name is the key of the query in the json of the UmeeQuery enum
+      let json = cosmwasm_std::to_json_string(&umee_query.umee_query()).unwrap();
+      assert!(json.contains(&format!("{{\"{}\":{{\"{}\"", module, query)));
+    }
+  }
+}
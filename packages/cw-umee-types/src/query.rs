@@ -137,6 +137,227 @@ fn default_struct_umee_query() -> StructUmeeQuery {
 // the fields inside the struct are private, to avoid missmatching
 // the query property with the assigned_query field
 impl StructUmeeQuery {
+  // umee_query returns the query as the UmeeQuery enum, it is None when
+  // no query param is set. It allows matching the query by its module.
+  pub fn umee_query(&self) -> Option<UmeeQuery> {
+    let leverage = self
+      .leverage_parameters
+      .clone()
+      .map(UmeeQueryLeverage::LeverageParameters)
+      .or_else(|| {
+        self
+          .market_summary
+          .clone()
+          .map(UmeeQueryLeverage::MarketSummary)
+      })
+      .or_else(|| {
+        self
+          .account_balances
+          .clone()
+          .map(UmeeQueryLeverage::AccountBalances)
+      })
+      .or_else(|| {
+        self
+          .account_summary
+          .clone()
+          .map(UmeeQueryLeverage::AccountSummary)
+      })
+      .or_else(|| {
+        self
+          .registered_tokens
+          .clone()
+          .map(UmeeQueryLeverage::RegisteredTokens)
+      })
+      .or_else(|| {
+        self
+          .liquidation_targets
+          .clone()
+          .map(UmeeQueryLeverage::LiquidationTargets)
+      })
+      .or_else(|| {
+        self
+          .bad_debts_params
+          .clone()
+          .map(UmeeQueryLeverage::BadDebts)
+      })
+      .or_else(|| {
+        self
+          .max_withdraw_params
+          .clone()
+          .map(UmeeQueryLeverage::MaxWithdraw)
+      })
+      .or_else(|| {
+        self
+          .max_borrow_params
+          .clone()
+          .map(UmeeQueryLeverage::MaxBorrow)
+      })
+      .map(UmeeQuery::Leverage);
+    let oracle = self
+      .exchange_rates
+      .clone()
+      .map(UmeeQueryOracle::ExchangeRates)
+      .or_else(|| {
+        self
+          .active_exchange_rates
+          .clone()
+          .map(UmeeQueryOracle::ActiveExchangeRates)
+      })
+      .or_else(|| {
+        self
+          .feeder_delegation
+          .clone()
+          .map(UmeeQueryOracle::FeederDelegation)
+      })
+      .or_else(|| self.miss_counter.clone().map(UmeeQueryOracle::MissCounter))
+      .or_else(|| self.slash_window.clone().map(UmeeQueryOracle::SlashWindow))
+      .or_else(|| {
+        self
+          .aggregate_prevote
+          .clone()
+          .map(UmeeQueryOracle::AggregatePrevote)
+      })
+      .or_else(|| {
+        self
+          .aggregate_prevotes
+          .clone()
+          .map(UmeeQueryOracle::AggregatePrevotes)
+      })
+      .or_else(|| {
+        self
+          .aggregate_vote
+          .clone()
+          .map(UmeeQueryOracle::AggregateVote)
+      })
+      .or_else(|| {
+        self
+          .aggregate_votes
+          .clone()
+          .map(UmeeQueryOracle::AggregateVotes)
+      })
+      .or_else(|| {
+        self
+          .oracle_params
+          .clone()
+          .map(UmeeQueryOracle::OracleParameters)
+      })
+      .or_else(|| self.medians_params.clone().map(UmeeQueryOracle::Medians))
+      .or_else(|| {
+        self
+          .median_deviations_params
+          .clone()
+          .map(UmeeQueryOracle::MedianDeviations)
+      })
+      .map(UmeeQuery::Oracle);
+    let incentive = self
+      .incentive_parameters
+      .clone()
+      .map(UmeeQueryIncentive::IncentiveParameters)
+      .or_else(|| {
+        self
+          .total_bonded
+          .clone()
+          .map(UmeeQueryIncentive::TotalBonded)
+      })
+      .or_else(|| {
+        self
+          .total_unbonding
+          .clone()
+          .map(UmeeQueryIncentive::TotalUnbonding)
+      })
+      .or_else(|| {
+        self
+          .account_bonds
+          .clone()
+          .map(UmeeQueryIncentive::AccountBonds)
+      })
+      .or_else(|| {
+        self
+          .pending_rewards
+          .clone()
+          .map(UmeeQueryIncentive::PendingRewards)
+      })
+      .or_else(|| {
+        self
+          .completed_incentive_programs
+          .clone()
+          .map(UmeeQueryIncentive::CompletedIncentivePrograms)
+      })
+      .or_else(|| {
+        self
+          .ongoing_incentive_programs
+          .clone()
+          .map(UmeeQueryIncentive::OngoingIncentivePrograms)
+      })
+      .or_else(|| {
+        self
+          .upcoming_incentive_programs
+          .clone()
+          .map(UmeeQueryIncentive::UpcomingIncentivePrograms)
+      })
+      .or_else(|| {
+        self
+          .incentive_program
+          .clone()
+          .map(UmeeQueryIncentive::IncentiveProgram)
+      })
+      .or_else(|| {
+        self
+          .current_rates
+          .clone()
+          .map(UmeeQueryIncentive::CurrentRates)
+      })
+      .or_else(|| {
+        self
+          .actual_rates
+          .clone()
+          .map(UmeeQueryIncentive::ActualRates)
+      })
+      .or_else(|| {
+        self
+          .last_reward_time
+          .clone()
+          .map(UmeeQueryIncentive::LastRewardTime)
+      })
+      .map(UmeeQuery::Incentive);
+    let metoken = self
+      .metoken_parameters
+      .clone()
+      .map(UmeeQueryMeToken::MetokenParameters)
+      .or_else(|| {
+        self
+          .metoken_indexes
+          .clone()
+          .map(UmeeQueryMeToken::MetokenIndexes)
+      })
+      .or_else(|| {
+        self
+          .metoken_swapfee
+          .clone()
+          .map(UmeeQueryMeToken::MetokenSwapfee)
+      })
+      .or_else(|| {
+        self
+          .metoken_redeemfee
+          .clone()
+          .map(UmeeQueryMeToken::MetokenRedeemfee)
+      })
+      .or_else(|| {
+        self
+          .metoken_indexbalances
+          .clone()
+          .map(UmeeQueryMeToken::MetokenIndexbalances)
+      })
+      .or_else(|| {
+        self
+          .metoken_indexprice
+          .clone()
+          .map(UmeeQueryMeToken::MetokenIndexPrices)
+      })
+      .map(UmeeQuery::Metoken);
+    leverage.or(oracle).or(incentive).or(metoken)
+  }
+
   pub fn incentive_params(
     incentive_parameter_params: IncentiveParametersParams,
   ) -> StructUmeeQuery {
@@ -64,26 +64,193 @@ pub struct MarketSummaryParams {
 }
 
 // MarketSummary base asset's current borrowing and supplying conditions.
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
 pub struct MarketSummaryResponse {
-  symbol_denom: String,
-  exponent: u32,
-  oracle_price: Decimal256,
-  utoken_exchange_rate: Decimal256,
-  supply_apy: Decimal256,
-  borrow_apy: Decimal256,
-  supplied: Decimal256,
-  reserved: Decimal256,
-  collateral: Decimal256,
-  borrowed: Decimal256,
-  liquidity: Decimal256,
-  maximum_borrow: Decimal256,
-  maximum_collateral: Decimal256,
-  minimum_liquidity: Decimal256,
-  utoken_supply: Decimal256,
-  available_borrow: Decimal256,
-  available_withdraw: Decimal256,
-  available_collateralize: Decimal256,
+  pub(crate) symbol_denom: String,
+  pub(crate) exponent: u32,
+  pub(crate) oracle_price: Decimal256,
+  pub(crate) utoken_exchange_rate: Decimal256,
+  pub(crate) supply_apy: Decimal256,
+  pub(crate) borrow_apy: Decimal256,
+  pub(crate) supplied: Decimal256,
+  pub(crate) reserved: Decimal256,
+  pub(crate) collateral: Decimal256,
+  pub(crate) borrowed: Decimal256,
+  pub(crate) liquidity: Decimal256,
+  pub(crate) maximum_borrow: Decimal256,
+  pub(crate) maximum_collateral: Decimal256,
+  pub(crate) minimum_liquidity: Decimal256,
+  pub(crate) utoken_supply: Decimal256,
+  pub(crate) available_borrow: Decimal256,
+  pub(crate) available_withdraw: Decimal256,
+  pub(crate) available_collateralize: Decimal256,
+}
+
+impl MarketSummaryResponse {
+  pub fn symbol_denom(&self) -> &str {
+    &self.symbol_denom
+  }
+
+  pub fn exponent(&self) -> u32 {
+    self.exponent
+  }
+
+  pub fn oracle_price(&self) -> Decimal256 {
+    self.oracle_price
+  }
+
+  // utoken_exchange_rate returns the tokens per uToken of the market.
+  pub fn utoken_exchange_rate(&self) -> Decimal256 {
+    self.utoken_exchange_rate
+  }
+
+  pub fn supply_apy(&self) -> Decimal256 {
+    self.supply_apy
+  }
+
+  pub fn borrow_apy(&self) -> Decimal256 {
+    self.borrow_apy
+  }
+
+  pub fn supplied(&self) -> Decimal256 {
+    self.supplied
+  }
+
+  pub fn reserved(&self) -> Decimal256 {
+    self.reserved
+  }
+
+  pub fn collateral(&self) -> Decimal256 {
+    self.collateral
+  }
+
+  pub fn borrowed(&self) -> Decimal256 {
+    self.borrowed
+  }
+
+  pub fn liquidity(&self) -> Decimal256 {
+    self.liquidity
+  }
+
+  pub fn maximum_borrow(&self) -> Decimal256 {
+    self.maximum_borrow
+  }
+
+  pub fn maximum_collateral(&self) -> Decimal256 {
+    self.maximum_collateral
+  }
+
+  pub fn minimum_liquidity(&self) -> Decimal256 {
+    self.minimum_liquidity
+  }
+
+  pub fn utoken_supply(&self) -> Decimal256 {
+    self.utoken_supply
+  }
+
+  pub fn available_borrow(&self) -> Decimal256 {
+    self.available_borrow
+  }
+
+  pub fn available_withdraw(&self) -> Decimal256 {
+    self.available_withdraw
+  }
+
+  pub fn available_collateralize(&self) -> Decimal256 {
+    self.available_collateralize
+  }
+
+  // the with_ builders set a field over the Default zero values, to build
+  // the market summaries of the tests.
+  pub fn with_symbol_denom(mut self, symbol_denom: impl Into<String>) -> Self {
+    self.symbol_denom = symbol_denom.into();
+    self
+  }
+
+  pub fn with_exponent(mut self, exponent: u32) -> Self {
+    self.exponent = exponent;
+    self
+  }
+
+  pub fn with_oracle_price(mut self, oracle_price: Decimal256) -> Self {
+    self.oracle_price = oracle_price;
+    self
+  }
+
+  pub fn with_utoken_exchange_rate(mut self, utoken_exchange_rate: Decimal256) -> Self {
+    self.utoken_exchange_rate = utoken_exchange_rate;
+    self
+  }
+
+  pub fn with_supply_apy(mut self, supply_apy: Decimal256) -> Self {
+    self.supply_apy = supply_apy;
+    self
+  }
+
+  pub fn with_borrow_apy(mut self, borrow_apy: Decimal256) -> Self {
+    self.borrow_apy = borrow_apy;
+    self
+  }
+
+  pub fn with_supplied(mut self, supplied: Decimal256) -> Self {
+    self.supplied = supplied;
+    self
+  }
+
+  pub fn with_reserved(mut self, reserved: Decimal256) -> Self {
+    self.reserved = reserved;
+    self
+  }
+
+  pub fn with_collateral(mut self, collateral: Decimal256) -> Self {
+    self.collateral = collateral;
+    self
+  }
+
+  pub fn with_borrowed(mut self, borrowed: Decimal256) -> Self {
+    self.borrowed = borrowed;
+    self
+  }
+
+  pub fn with_liquidity(mut self, liquidity: Decimal256) -> Self {
+    self.liquidity = liquidity;
+    self
+  }
+
+  pub fn with_maximum_borrow(mut self, maximum_borrow: Decimal256) -> Self {
+    self.maximum_borrow = maximum_borrow;
+    self
+  }
+
+  pub fn with_maximum_collateral(mut self, maximum_collateral: Decimal256) -> Self {
+    self.maximum_collateral = maximum_collateral;
+    self
+  }
+
+  pub fn with_minimum_liquidity(mut self, minimum_liquidity: Decimal256) -> Self {
+    self.minimum_liquidity = minimum_liquidity;
+    self
+  }
+
+  pub fn with_utoken_supply(mut self, utoken_supply: Decimal256) -> Self {
+    self.utoken_supply = utoken_supply;
+    self
+  }
+
+  pub fn with_available_borrow(mut self, available_borrow: Decimal256) -> Self {
+    self.available_borrow = available_borrow;
+    self
+  }
+
+  pub fn with_available_withdraw(mut self, available_withdraw: Decimal256) -> Self {
+    self.available_withdraw = available_withdraw;
+    self
+  }
+
+  pub fn with_available_collateralize(mut self, available_collateralize: Decimal256) -> Self {
+    self.available_collateralize = available_collateralize;
+    self
+  }
 }
 
 // AccountBalancesParams params to query AccountBalances.
@@ -114,6 +281,16 @@ pub struct AccountSummaryResponse {
   pub borrowed_value: Decimal256,
   pub borrow_limit: Decimal256,
   pub liquidation_threshold: Decimal256,
+  // spendable_borrow_limit is the value the account can still borrow, the
+  // borrow limit with the collateral at the lower of the spot and historic
+  // prices over the borrowed value at the higher of them. It is None on the
+  // chains before the historic medians, that omit it.
+  pub spendable_borrow_limit: Option<Decimal256>,
+  // historic_liquidation_threshold is the liquidation threshold with the
+  // collateral at the historic median prices, of the tokens that have
+  // historic_medians, and at the spot prices otherwise. It is None on the
+  // chains before the historic medians.
+  pub historic_liquidation_threshold: Option<Decimal256>,
 }
 
 // LiquidationTargetsParams params to query LiquidationTargets.
@@ -83,7 +83,7 @@ pub struct ActiveExchangeRatesResponse {
 // FeederDelegationParams params to query FeederDelegation.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct FeederDelegationParams {
-  validator_addr: Addr,
+  pub(crate) validator_addr: Addr,
 }
 
 // FeederDelegationResponse response struct of FeederDelegation.
@@ -95,7 +95,7 @@ pub struct FeederDelegationResponse {
 // MissCounterParams params to query MissCounter.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct MissCounterParams {
-  validator_addr: Addr,
+  pub(crate) validator_addr: Addr,
 }
 
 // MissCounterResponse response struct of MissCounter.
@@ -117,7 +117,7 @@ pub struct SlashWindowResponse {
 // AggregatePrevoteParams params to query AggregatePrevote.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct AggregatePrevoteParams {
-  validator_addr: Addr,
+  pub(crate) validator_addr: Addr,
 }
 
 // AggregatePrevoteResponse response struct of AggregatePrevote.
@@ -139,7 +139,7 @@ pub struct AggregatePrevotesResponse {
 // AggregateVoteParams params to query AggregateVote.
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct AggregateVoteParams {
-  validator_addr: Addr,
+  pub(crate) validator_addr: Addr,
 }
 
 // AggregateVoteResponse response struct of AggregateVote.
@@ -0,0 +1,1382 @@
+use crate::aggregate_exchange_rate_prevote::AggregateExchangeRatePrevote;
+use crate::aggregate_exchange_rate_vote::{AggregateExchangeRateVote, ExchangeRateTuple};
+use crate::bad_debt::BadDebt;
+use crate::leverage_parameters::LeverageParameters;
+use crate::oracle_parameters::{Denom, OracleParameters};
+use crate::proto;
+use crate::query_incentive::{
+  AccountBondsParams, AccountBondsResponse, ActualRatesParams, ActualRatesResponse,
+  CompletedIncentiveProgramsParams, CompletedIncentiveProgramsResponse, CurrentRatesParams,
+  CurrentRatesResponse, IncentiveParameters, IncentiveParametersParams,
+  IncentiveParametersResponse, IncentiveProgram, IncentiveProgramParams, IncentiveProgramResponse,
+  LastRewardTimeParams, LastRewardTimeResponse, OngoingIncentiveProgramsParams,
+  OngoingIncentiveProgramsResponse, PendingRewardsParams, PendingRewardsResponse,
+  TotalBondedParams, TotalBondedResponse, TotalUnbondingParams, TotalUnbondingResponse, Unbonding,
+  UpcomingIncentiveProgramsParams, UpcomingIncentiveProgramsResponse,
+};
+use crate::query_leverage::{
+  AccountBalancesParams, AccountBalancesResponse, AccountSummaryParams, AccountSummaryResponse,
+  BadDebtsParams, BadDebtsResponse, LeverageParametersParams, LeverageParametersResponse,
+  LiquidationTargetsParams, LiquidationTargetsResponse, MarketSummaryParams, MarketSummaryResponse,
+  MaxBorrowParams, MaxBorrowResponse, MaxWithdrawParams, MaxWithdrawResponse,
+  RegisteredTokensParams, RegisteredTokensResponse,
+};
+use crate::query_metoken::{
+  AcceptedAsset, AssetBalance, AssetPrice, Fee, Index, IndexBalances, IndexPrices,
+  MetokenIndexPricesParams, MetokenIndexPricesResponse, MetokenIndexbalancesParams,
+  MetokenIndexbalancesResponse, MetokenIndexesParams, MetokenIndexesResponse, MetokenParameters,
+  MetokenParametersParams, MetokenParametersResponse, MetokenRedeemfeeParams,
+  MetokenRedeemfeeResponse, MetokenSwapfeeParams, MetokenSwapfeeResponse,
+};
+use crate::query_oracle::{
+  ActiveExchangeRatesParams, ActiveExchangeRatesResponse, AggregatePrevoteParams,
+  AggregatePrevoteResponse, AggregatePrevotesParams, AggregatePrevotesResponse,
+  AggregateVoteParams, AggregateVoteResponse, AggregateVotesParams, AggregateVotesResponse,
+  DecCoin, ExchangeRatesParams, ExchangeRatesResponse, FeederDelegationParams,
+  FeederDelegationResponse, MedianDeviationsParams, MedianDeviationsParamsResponse, MediansParams,
+  MediansParamsResponse, MissCounterParams, MissCounterResponse, OracleParametersParams,
+  OracleParametersResponse, SlashWindowParams, SlashWindowResponse,
+};
+use crate::token::Token;
+use cosmwasm_std::{
+  Binary, Coin, Decimal, Decimal256, QueryRequest, StdError, StdResult, Uint128, Uint256,
+};
+use prost::Message;
+use std::str::FromStr;
+
+// sdk.Dec values are sent over gRPC as the string of its atomics,
+// an integer with 18 decimal places.
+const SDK_DEC_PLACES: u32 = 18;
+
+// StargateQuery defines the QueryRequest::Stargate path of an umee query,
+// it is implemented by the params of every umee query and decodes the
+// protobuf response of the chain into the same response struct returned
+// by the custom "umee" query binding.
+pub trait StargateQuery {
+  type Response;
+
+  // PATH is the gRPC method of the query in the umee chain.
+  const PATH: &'static str;
+
+  // encode_request returns the protobuf encoded query request.
+  fn encode_request(&self) -> Vec<u8>;
+
+  // decode_response decodes the protobuf query response of the chain.
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response>;
+
+  // to_stargate_query creates the QueryRequest::Stargate of the query.
+  fn to_stargate_query<T>(&self) -> QueryRequest<T> {
+    QueryRequest::Stargate {
+      path: Self::PATH.to_string(),
+      data: Binary::from(self.encode_request()),
+    }
+  }
+}
+
+fn decode<M: Message + Default>(data: &[u8]) -> StdResult<M> {
+  M::decode(data).map_err(|err| StdError::parse_err(std::any::type_name::<M>(), err))
+}
+
+fn required<T>(value: Option<T>, field: &str) -> StdResult<T> {
+  value.ok_or_else(|| StdError::generic_err(format!("missing {} in stargate response", field)))
+}
+
+// dec256 parses an sdk.Dec, it also accepts the decimal representation
+// used by the amino JSON encoding, an empty string is a zero Dec.
+fn dec256(value: &str) -> StdResult<Decimal256> {
+  if value.is_empty() {
+    return Ok(Decimal256::zero());
+  }
+  if value.contains('.') {
+    return Decimal256::from_str(value);
+  }
+  Decimal256::from_atomics(Uint256::from_str(value)?, SDK_DEC_PLACES)
+    .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+// opt_dec256 parses the sdk decimal of a field the older chains omit, the
+// empty field is None.
+fn opt_dec256(value: &str) -> StdResult<Option<Decimal256>> {
+  if value.is_empty() {
+    return Ok(None);
+  }
+  dec256(value).map(Some)
+}
+
+fn dec(value: &str) -> StdResult<Decimal> {
+  if value.is_empty() {
+    return Ok(Decimal::zero());
+  }
+  if value.contains('.') {
+    return Decimal::from_str(value);
+  }
+  Decimal::from_atomics(Uint128::from_str(value)?, SDK_DEC_PLACES)
+    .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+// int256 parses an sdk.Int as a Decimal256 without decimal places.
+fn int256(value: &str) -> StdResult<Decimal256> {
+  if value.is_empty() {
+    return Ok(Decimal256::zero());
+  }
+  Decimal256::from_atomics(Uint256::from_str(value)?, 0)
+    .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+// int parses an sdk.Int as a Decimal without decimal places.
+fn int(value: &str) -> StdResult<Decimal> {
+  if value.is_empty() {
+    return Ok(Decimal::zero());
+  }
+  Decimal::from_atomics(Uint128::from_str(value)?, 0)
+    .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+fn coin(value: proto::ProtoCoin) -> StdResult<Coin> {
+  let amount = if value.amount.is_empty() {
+    Uint128::zero()
+  } else {
+    Uint128::from_str(&value.amount)?
+  };
+  Ok(Coin {
+    denom: value.denom,
+    amount,
+  })
+}
+
+fn coins(values: Vec<proto::ProtoCoin>) -> StdResult<Vec<Coin>> {
+  values.into_iter().map(coin).collect()
+}
+
+fn dec_coins(values: Vec<proto::ProtoDecCoin>) -> StdResult<Vec<DecCoin>> {
+  values
+    .into_iter()
+    .map(|value| {
+      Ok(DecCoin {
+        amount: dec256(&value.amount)?,
+        denom: value.denom,
+      })
+    })
+    .collect()
+}
+
+// parse_coin parses a coin written as a string, e.g. "1000uumee", it is
+// the format used by the metoken fee queries params.
+fn parse_coin(value: &str) -> StdResult<proto::ProtoCoin> {
+  let split = value
+    .find(|c: char| !c.is_ascii_digit())
+    .filter(|split| *split > 0)
+    .ok_or_else(|| StdError::generic_err(format!("invalid coin: {}", value)))?;
+  let (amount, denom) = value.split_at(split);
+  Ok(proto::ProtoCoin {
+    denom: denom.to_string(),
+    amount: amount.to_string(),
+  })
+}
+
+// leverage
+
+impl StargateQuery for LeverageParametersParams {
+  type Response = LeverageParametersResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/Params";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryLeverageParams {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryLeverageParamsResponse = decode(data)?;
+    let params = required(res.params, "params")?;
+    Ok(LeverageParametersResponse {
+      params: LeverageParameters {
+        complete_liquidation_threshold: dec256(&params.complete_liquidation_threshold)?,
+        minimum_close_factor: dec256(&params.minimum_close_factor)?,
+        oracle_reward_factor: dec256(&params.oracle_reward_factor)?,
+        small_liquidation_size: dec256(&params.small_liquidation_size)?,
+        direct_liquidation_fee: dec256(&params.direct_liquidation_fee)?,
+      },
+    })
+  }
+}
+
+fn token(value: proto::ProtoToken) -> StdResult<Token> {
+  Ok(Token {
+    base_denom: Some(value.base_denom),
+    reserve_factor: dec(&value.reserve_factor)?,
+    collateral_weight: dec(&value.collateral_weight)?,
+    liquidation_threshold: dec(&value.liquidation_threshold)?,
+    base_borrow_rate: dec(&value.base_borrow_rate)?,
+    kink_borrow_rate: dec(&value.kink_borrow_rate)?,
+    max_borrow_rate: dec(&value.max_borrow_rate)?,
+    kink_utilization: dec(&value.kink_utilization)?,
+    liquidation_incentive: dec(&value.liquidation_incentive)?,
+    symbol_denom: Some(value.symbol_denom),
+    exponent: value.exponent,
+    enable_msg_supply: Some(value.enable_msg_supply),
+    enable_msg_borrow: Some(value.enable_msg_borrow),
+    blacklist: Some(value.blacklist),
+    max_collateral_share: dec(&value.max_collateral_share)?,
+    max_supply_utilization: dec(&value.max_supply_utilization)?,
+    min_collateral_liquidity: dec(&value.min_collateral_liquidity)?,
+    max_supply: int(&value.max_supply)?,
+    historic_medians: value.historic_medians,
+  })
+}
+
+impl StargateQuery for RegisteredTokensParams {
+  type Response = RegisteredTokensResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/RegisteredTokens";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryRegisteredTokens {
+      base_denom: String::new(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryRegisteredTokensResponse = decode(data)?;
+    Ok(RegisteredTokensResponse {
+      registry: res
+        .registry
+        .into_iter()
+        .map(token)
+        .collect::<StdResult<_>>()?,
+    })
+  }
+}
+
+impl StargateQuery for MarketSummaryParams {
+  type Response = MarketSummaryResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/MarketSummary";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMarketSummary {
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMarketSummaryResponse = decode(data)?;
+    Ok(MarketSummaryResponse {
+      symbol_denom: res.symbol_denom,
+      exponent: res.exponent,
+      oracle_price: dec256(&res.oracle_price)?,
+      utoken_exchange_rate: dec256(&res.utoken_exchange_rate)?,
+      supply_apy: dec256(&res.supply_apy)?,
+      borrow_apy: dec256(&res.borrow_apy)?,
+      supplied: int256(&res.supplied)?,
+      reserved: int256(&res.reserved)?,
+      collateral: int256(&res.collateral)?,
+      borrowed: int256(&res.borrowed)?,
+      liquidity: int256(&res.liquidity)?,
+      maximum_borrow: int256(&res.maximum_borrow)?,
+      maximum_collateral: int256(&res.maximum_collateral)?,
+      minimum_liquidity: int256(&res.minimum_liquidity)?,
+      utoken_supply: int256(&res.utoken_supply)?,
+      available_borrow: int256(&res.available_borrow)?,
+      available_withdraw: int256(&res.available_withdraw)?,
+      available_collateralize: int256(&res.available_collateralize)?,
+    })
+  }
+}
+
+impl StargateQuery for AccountBalancesParams {
+  type Response = AccountBalancesResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/AccountBalances";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAccountBalances {
+      address: self.address.to_string(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAccountBalancesResponse = decode(data)?;
+    Ok(AccountBalancesResponse {
+      supplied: coins(res.supplied)?,
+      collateral: coins(res.collateral)?,
+      borrowed: coins(res.borrowed)?,
+    })
+  }
+}
+
+impl StargateQuery for AccountSummaryParams {
+  type Response = AccountSummaryResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/AccountSummary";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAccountSummary {
+      address: self.address.to_string(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAccountSummaryResponse = decode(data)?;
+    Ok(AccountSummaryResponse {
+      supplied_value: dec256(&res.supplied_value)?,
+      collateral_value: dec256(&res.collateral_value)?,
+      borrowed_value: dec256(&res.borrowed_value)?,
+      borrow_limit: dec256(&res.borrow_limit)?,
+      liquidation_threshold: dec256(&res.liquidation_threshold)?,
+      spendable_borrow_limit: opt_dec256(&res.spendable_borrow_limit)?,
+      historic_liquidation_threshold: opt_dec256(&res.historic_liquidation_threshold)?,
+    })
+  }
+}
+
+impl StargateQuery for LiquidationTargetsParams {
+  type Response = LiquidationTargetsResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/LiquidationTargets";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryLiquidationTargets {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryLiquidationTargetsResponse = decode(data)?;
+    Ok(LiquidationTargetsResponse {
+      targets: res.targets,
+    })
+  }
+}
+
+impl StargateQuery for BadDebtsParams {
+  type Response = BadDebtsResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/BadDebts";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryBadDebts {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryBadDebtsResponse = decode(data)?;
+    Ok(BadDebtsResponse {
+      targets: res
+        .targets
+        .into_iter()
+        .map(|target| BadDebt {
+          address: target.address,
+          denom: target.denom,
+        })
+        .collect(),
+    })
+  }
+}
+
+impl StargateQuery for MaxWithdrawParams {
+  type Response = MaxWithdrawResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/MaxWithdraw";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMaxWithdraw {
+      address: self.address.to_string(),
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  // the chain answers with one coin per denom, the custom binding
+  // returns only the coin of the queried denom.
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMaxWithdrawResponse = decode(data)?;
+    let u_denom = format!("u/{}", self.denom);
+    let u_tokens = coins(res.u_tokens)?
+      .into_iter()
+      .find(|c| c.denom == u_denom)
+      .unwrap_or_else(|| Coin::new(0, u_denom));
+    let tokens = coins(res.tokens)?
+      .into_iter()
+      .find(|c| c.denom == self.denom)
+      .unwrap_or_else(|| Coin::new(0, self.denom.clone()));
+    Ok(MaxWithdrawResponse { u_tokens, tokens })
+  }
+}
+
+impl StargateQuery for MaxBorrowParams {
+  type Response = MaxBorrowResponse;
+  const PATH: &'static str = "/umee.leverage.v1.Query/MaxBorrow";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMaxBorrow {
+      address: self.address.to_string(),
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMaxBorrowResponse = decode(data)?;
+    Ok(MaxBorrowResponse {
+      tokens: coins(res.tokens)?,
+    })
+  }
+}
+
+// oracle
+
+impl StargateQuery for ExchangeRatesParams {
+  type Response = ExchangeRatesResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/ExchangeRates";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryExchangeRates {
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryExchangeRatesResponse = decode(data)?;
+    Ok(ExchangeRatesResponse {
+      exchange_rates: dec_coins(res.exchange_rates)?,
+    })
+  }
+}
+
+impl StargateQuery for ActiveExchangeRatesParams {
+  type Response = ActiveExchangeRatesResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/ActiveExchangeRates";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryActiveExchangeRates {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryActiveExchangeRatesResponse = decode(data)?;
+    Ok(ActiveExchangeRatesResponse {
+      active_rates: res.active_rates,
+    })
+  }
+}
+
+impl StargateQuery for FeederDelegationParams {
+  type Response = FeederDelegationResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/FeederDelegation";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryFeederDelegation {
+      validator_addr: self.validator_addr.to_string(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryFeederDelegationResponse = decode(data)?;
+    Ok(FeederDelegationResponse {
+      feeder_addr: res.feeder_addr,
+    })
+  }
+}
+
+impl StargateQuery for MissCounterParams {
+  type Response = MissCounterResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/MissCounter";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMissCounter {
+      validator_addr: self.validator_addr.to_string(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMissCounterResponse = decode(data)?;
+    Ok(MissCounterResponse {
+      miss_counter: res.miss_counter,
+    })
+  }
+}
+
+impl StargateQuery for SlashWindowParams {
+  type Response = SlashWindowResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/SlashWindow";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QuerySlashWindow {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QuerySlashWindowResponse = decode(data)?;
+    Ok(SlashWindowResponse {
+      window_progress: res.window_progress,
+    })
+  }
+}
+
+fn prevote(value: proto::ProtoAggregateExchangeRatePrevote) -> AggregateExchangeRatePrevote {
+  AggregateExchangeRatePrevote {
+    hash: value.hash,
+    voter: value.voter,
+    submit_block: value.submit_block,
+  }
+}
+
+fn vote(value: proto::ProtoAggregateExchangeRateVote) -> StdResult<AggregateExchangeRateVote> {
+  Ok(AggregateExchangeRateVote {
+    exchange_rate_tuples: value
+      .exchange_rate_tuples
+      .into_iter()
+      .map(|tuple| {
+        Ok(ExchangeRateTuple {
+          exchange_rate: dec256(&tuple.exchange_rate)?,
+          denom: tuple.denom,
+        })
+      })
+      .collect::<StdResult<_>>()?,
+    voter: value.voter,
+  })
+}
+
+impl StargateQuery for AggregatePrevoteParams {
+  type Response = AggregatePrevoteResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/AggregatePrevote";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAggregatePrevote {
+      validator_addr: self.validator_addr.to_string(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAggregatePrevoteResponse = decode(data)?;
+    Ok(AggregatePrevoteResponse {
+      aggregate_prevote: prevote(required(res.aggregate_prevote, "aggregate_prevote")?),
+    })
+  }
+}
+
+impl StargateQuery for AggregatePrevotesParams {
+  type Response = AggregatePrevotesResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/AggregatePrevotes";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAggregatePrevotes {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAggregatePrevotesResponse = decode(data)?;
+    Ok(AggregatePrevotesResponse {
+      aggregate_prevotes: res.aggregate_prevotes.into_iter().map(prevote).collect(),
+    })
+  }
+}
+
+impl StargateQuery for AggregateVoteParams {
+  type Response = AggregateVoteResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/AggregateVote";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAggregateVote {
+      validator_addr: self.validator_addr.to_string(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAggregateVoteResponse = decode(data)?;
+    Ok(AggregateVoteResponse {
+      aggregate_vote: vote(required(res.aggregate_vote, "aggregate_vote")?)?,
+    })
+  }
+}
+
+impl StargateQuery for AggregateVotesParams {
+  type Response = AggregateVotesResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/AggregateVotes";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAggregateVotes {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAggregateVotesResponse = decode(data)?;
+    Ok(AggregateVotesResponse {
+      aggregate_votes: res
+        .aggregate_votes
+        .into_iter()
+        .map(vote)
+        .collect::<StdResult<_>>()?,
+    })
+  }
+}
+
+fn denoms(values: Vec<proto::ProtoDenom>) -> Vec<Denom> {
+  values
+    .into_iter()
+    .map(|value| Denom {
+      base_denom: value.base_denom,
+      symbol_denom: value.symbol_denom,
+      exponent: value.exponent,
+    })
+    .collect()
+}
+
+impl StargateQuery for OracleParametersParams {
+  type Response = OracleParametersResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/Params";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryOracleParams {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryOracleParamsResponse = decode(data)?;
+    let params = required(res.params, "params")?;
+    Ok(OracleParametersResponse {
+      params: OracleParameters {
+        vote_period: params.vote_period,
+        vote_threshold: dec256(&params.vote_threshold)?,
+        reward_band: dec256(&params.reward_band)?,
+        reward_distribution_window: params.reward_distribution_window,
+        accept_list: denoms(params.accept_list),
+        slash_fraction: dec256(&params.slash_fraction)?,
+        slash_window: params.slash_window,
+        min_valid_per_window: dec256(&params.min_valid_per_window)?,
+        stamp_period: params.stamp_period,
+        prune_period: params.prune_period,
+        median_period: params.median_period,
+        historic_accept_list: denoms(params.historic_accept_list),
+      },
+    })
+  }
+}
+
+impl StargateQuery for MediansParams {
+  type Response = MediansParamsResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/Medians";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMedians {
+      denom: self.denom.clone(),
+      num_stamps: 0,
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMediansResponse = decode(data)?;
+    Ok(MediansParamsResponse {
+      medians: dec_coins(res.medians)?,
+    })
+  }
+}
+
+impl StargateQuery for MedianDeviationsParams {
+  type Response = MedianDeviationsParamsResponse;
+  const PATH: &'static str = "/umee.oracle.v1.Query/MedianDeviations";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMedianDeviations {
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMedianDeviationsResponse = decode(data)?;
+    Ok(MedianDeviationsParamsResponse {
+      median_deviations: dec_coins(res.median_deviations)?,
+    })
+  }
+}
+
+// incentive
+
+impl StargateQuery for IncentiveParametersParams {
+  type Response = IncentiveParametersResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/Params";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryIncentiveParams {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryIncentiveParamsResponse = decode(data)?;
+    let params = required(res.params, "params")?;
+    Ok(IncentiveParametersResponse {
+      params: IncentiveParameters {
+        max_unbondings: params.max_unbondings,
+        unbonding_duration: params.unbonding_duration,
+        emergency_unbond_fee: dec256(&params.emergency_unbond_fee)?,
+      },
+    })
+  }
+}
+
+impl StargateQuery for TotalBondedParams {
+  type Response = TotalBondedResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/TotalBonded";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryTotalBonded {
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryTotalBondedResponse = decode(data)?;
+    Ok(TotalBondedResponse {
+      bonded: coins(res.bonded)?,
+    })
+  }
+}
+
+impl StargateQuery for TotalUnbondingParams {
+  type Response = TotalUnbondingResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/TotalUnbonding";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryTotalUnbonding {
+      denom: self.denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryTotalUnbondingResponse = decode(data)?;
+    Ok(TotalUnbondingResponse {
+      unbonding: coins(res.unbonding)?,
+    })
+  }
+}
+
+impl StargateQuery for AccountBondsParams {
+  type Response = AccountBondsResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/AccountBonds";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryAccountBonds {
+      address: self.address.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryAccountBondsResponse = decode(data)?;
+    Ok(AccountBondsResponse {
+      bonded: coins(res.bonded)?,
+      unbonding: coins(res.unbonding)?,
+      unbondings: res
+        .unbondings
+        .into_iter()
+        .map(|unbonding| {
+          Ok(Unbonding {
+            start: unbonding.start,
+            end: unbonding.end,
+            u_token: coin(required(unbonding.u_token, "u_token")?)?,
+          })
+        })
+        .collect::<StdResult<_>>()?,
+    })
+  }
+}
+
+impl StargateQuery for PendingRewardsParams {
+  type Response = PendingRewardsResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/PendingRewards";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryPendingRewards {
+      address: self.address.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryPendingRewardsResponse = decode(data)?;
+    Ok(PendingRewardsResponse {
+      rewards: coins(res.rewards)?,
+    })
+  }
+}
+
+fn program(value: proto::ProtoIncentiveProgram) -> StdResult<IncentiveProgram> {
+  Ok(IncentiveProgram {
+    ID: value.id,
+    start_time: value.start_time,
+    duration: value.duration,
+    u_token: value.u_token,
+    funded: value.funded,
+    total_rewards: coin(required(value.total_rewards, "total_rewards")?)?,
+    remaining_rewards: coin(required(value.remaining_rewards, "remaining_rewards")?)?,
+  })
+}
+
+fn programs(data: &[u8]) -> StdResult<Vec<IncentiveProgram>> {
+  let res: proto::QueryIncentiveProgramsResponse = decode(data)?;
+  res.programs.into_iter().map(program).collect()
+}
+
+impl StargateQuery for CompletedIncentiveProgramsParams {
+  type Response = CompletedIncentiveProgramsResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/CompletedIncentivePrograms";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryCompletedIncentivePrograms {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    Ok(CompletedIncentiveProgramsResponse {
+      programs: programs(data)?,
+    })
+  }
+}
+
+impl StargateQuery for OngoingIncentiveProgramsParams {
+  type Response = OngoingIncentiveProgramsResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/OngoingIncentivePrograms";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryOngoingIncentivePrograms {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    Ok(OngoingIncentiveProgramsResponse {
+      programs: programs(data)?,
+    })
+  }
+}
+
+impl StargateQuery for UpcomingIncentiveProgramsParams {
+  type Response = UpcomingIncentiveProgramsResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/UpcomingIncentivePrograms";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryUpcomingIncentivePrograms {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    Ok(UpcomingIncentiveProgramsResponse {
+      programs: programs(data)?,
+    })
+  }
+}
+
+impl StargateQuery for IncentiveProgramParams {
+  type Response = IncentiveProgramResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/IncentiveProgram";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryIncentiveProgram { id: self.id }.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryIncentiveProgramResponse = decode(data)?;
+    Ok(IncentiveProgramResponse {
+      program: program(required(res.program, "program")?)?,
+    })
+  }
+}
+
+impl StargateQuery for CurrentRatesParams {
+  type Response = CurrentRatesResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/CurrentRates";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryCurrentRates {
+      u_token: self.u_token.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryCurrentRatesResponse = decode(data)?;
+    Ok(CurrentRatesResponse {
+      reference_bond: coin(required(res.reference_bond, "reference_bond")?)?,
+      rewards: coins(res.rewards)?,
+    })
+  }
+}
+
+impl StargateQuery for ActualRatesParams {
+  type Response = ActualRatesResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/ActualRates";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryActualRates {
+      u_token: self.u_token.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryActualRatesResponse = decode(data)?;
+    Ok(ActualRatesResponse {
+      APY: dec(&res.apy)?,
+    })
+  }
+}
+
+impl StargateQuery for LastRewardTimeParams {
+  type Response = LastRewardTimeResponse;
+  const PATH: &'static str = "/umee.incentive.v1.Query/LastRewardTime";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryLastRewardTime {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryLastRewardTimeResponse = decode(data)?;
+    Ok(LastRewardTimeResponse { time: res.time })
+  }
+}
+
+// metoken
+
+impl StargateQuery for MetokenParametersParams {
+  type Response = MetokenParametersResponse;
+  const PATH: &'static str = "/umee.metoken.v1.Query/Params";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryMetokenParams {}.encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryMetokenParamsResponse = decode(data)?;
+    let params = required(res.params, "params")?;
+    Ok(MetokenParametersResponse {
+      params: MetokenParameters {
+        rebalancing_frequency: params.rebalancing_frequency,
+        claiming_frequency: params.claiming_frequency,
+      },
+    })
+  }
+}
+
+fn index(value: proto::ProtoIndex) -> StdResult<Index> {
+  let fee = required(value.fee, "fee")?;
+  let max_supply = if value.max_supply.is_empty() {
+    0
+  } else {
+    i64::from_str(&value.max_supply)
+      .map_err(|err| StdError::parse_err("i64", format!("max_supply: {}", err)))?
+  };
+  Ok(Index {
+    denom: value.denom,
+    max_supply,
+    exponent: value.exponent,
+    fee: Fee {
+      min_fee: dec(&fee.min_fee)?,
+      balanced_fee: dec(&fee.balanced_fee)?,
+      max_fee: dec(&fee.max_fee)?,
+    },
+    accepted_assets: value
+      .accepted_assets
+      .into_iter()
+      .map(|asset| {
+        Ok(AcceptedAsset {
+          reserve_portion: dec(&asset.reserve_portion)?,
+          target_allocation: dec(&asset.target_allocation)?,
+          denom: asset.denom,
+        })
+      })
+      .collect::<StdResult<_>>()?,
+  })
+}
+
+impl StargateQuery for MetokenIndexesParams {
+  type Response = MetokenIndexesResponse;
+  const PATH: &'static str = "/umee.metoken.v1.Query/Indexes";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryIndexes {
+      metoken_denom: self.metoken_denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryIndexesResponse = decode(data)?;
+    Ok(MetokenIndexesResponse {
+      registry: res
+        .registry
+        .into_iter()
+        .map(index)
+        .collect::<StdResult<_>>()?,
+    })
+  }
+}
+
+impl StargateQuery for MetokenSwapfeeParams {
+  type Response = MetokenSwapfeeResponse;
+  const PATH: &'static str = "/umee.metoken.v1.Query/SwapFee";
+
+  // the asset is sent as a coin string, an invalid coin is encoded
+  // without it and the chain returns the validation error.
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QuerySwapFee {
+      asset: parse_coin(&self.asset).ok(),
+      metoken_denom: self.metoken_denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryFeeResponse = decode(data)?;
+    Ok(MetokenSwapfeeResponse {
+      asset: coin(required(res.asset, "asset")?)?,
+    })
+  }
+}
+
+impl StargateQuery for MetokenRedeemfeeParams {
+  type Response = MetokenRedeemfeeResponse;
+  const PATH: &'static str = "/umee.metoken.v1.Query/RedeemFee";
+
+  // the metoken is sent as a coin string, an invalid coin is encoded
+  // without it and the chain returns the validation error.
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryRedeemFee {
+      metoken: parse_coin(&self.metoken).ok(),
+      asset_denom: self.asset_denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryFeeResponse = decode(data)?;
+    Ok(MetokenRedeemfeeResponse {
+      asset: coin(required(res.asset, "asset")?)?,
+    })
+  }
+}
+
+// index_prices converts the prices of the chain, the sdk.Dec values are
+// returned with their decimal representation as the custom binding does.
+fn index_prices(values: Vec<proto::ProtoIndexPrices>) -> StdResult<Vec<IndexPrices>> {
+  values
+    .into_iter()
+    .map(|value| {
+      Ok(IndexPrices {
+        denom: value.denom,
+        price: dec256(&value.price)?.to_string(),
+        exponent: value.exponent,
+        assets: value
+          .assets
+          .into_iter()
+          .map(|asset| {
+            Ok(AssetPrice {
+              base_denom: asset.base_denom,
+              symbol_denom: asset.symbol_denom,
+              price: dec256(&asset.price)?.to_string(),
+              exponent: asset.exponent,
+              swap_rate: dec256(&asset.swap_rate)?.to_string(),
+              redeem_rate: dec256(&asset.redeem_rate)?.to_string(),
+            })
+          })
+          .collect::<StdResult<_>>()?,
+      })
+    })
+    .collect()
+}
+
+impl StargateQuery for MetokenIndexbalancesParams {
+  type Response = MetokenIndexbalancesResponse;
+  const PATH: &'static str = "/umee.metoken.v1.Query/IndexBalances";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryIndexBalances {
+      metoken_denom: self.metoken_denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryIndexBalancesResponse = decode(data)?;
+    Ok(MetokenIndexbalancesResponse {
+      index_balances: res
+        .index_balances
+        .into_iter()
+        .map(|balances| {
+          Ok(IndexBalances {
+            metoken_supply: coin(required(balances.metoken_supply, "metoken_supply")?)?,
+            asset_balances: balances
+              .asset_balances
+              .into_iter()
+              .map(|balance| {
+                Ok(AssetBalance {
+                  leveraged: int(&balance.leveraged)?,
+                  reserved: int(&balance.reserved)?,
+                  fees: int(&balance.fees)?,
+                  interest: int(&balance.interest)?,
+                  denom: balance.denom,
+                })
+              })
+              .collect::<StdResult<_>>()?,
+          })
+        })
+        .collect::<StdResult<_>>()?,
+      index_prices: index_prices(res.prices)?,
+    })
+  }
+}
+
+impl StargateQuery for MetokenIndexPricesParams {
+  type Response = MetokenIndexPricesResponse;
+  const PATH: &'static str = "/umee.metoken.v1.Query/IndexPrices";
+
+  fn encode_request(&self) -> Vec<u8> {
+    proto::QueryIndexPrices {
+      metoken_denom: self.metoken_denom.clone(),
+    }
+    .encode_to_vec()
+  }
+
+  fn decode_response(&self, data: &[u8]) -> StdResult<Self::Response> {
+    let res: proto::QueryIndexPricesResponse = decode(data)?;
+    Ok(MetokenIndexPricesResponse {
+      prices: index_prices(res.prices)?,
+    })
+  }
+}
+
+#[cfg(all(test, feature = "stargate"))]
+mod tests {
+  use super::*;
+  use cosmwasm_std::{Addr, Empty};
+
+  #[test]
+  fn sdk_decimals() {
+    // the gRPC responses send the atomics of the sdk.Dec, the amino JSON
+    // the decimal string
+    assert_eq!(
+      dec256("1500000000000000000").unwrap(),
+      Decimal256::percent(150)
+    );
+    assert_eq!(dec256("1.5").unwrap(), Decimal256::percent(150));
+    assert_eq!(
+      dec256("1").unwrap(),
+      Decimal256::from_atomics(1u128, 18).unwrap()
+    );
+    assert_eq!(dec256("").unwrap(), Decimal256::zero());
+    dec256("1.5uumee").unwrap_err();
+    dec256("-1").unwrap_err();
+    assert_eq!(dec("25000000000000000").unwrap(), Decimal::permille(25));
+    assert_eq!(dec("0.025").unwrap(), Decimal::permille(25));
+    assert_eq!(dec("").unwrap(), Decimal::zero());
+    dec("abc").unwrap_err();
+
+    // the sdk.Int has no decimal places
+    assert_eq!(
+      int256("1500").unwrap(),
+      Decimal256::from_ratio(1_500u128, 1u128)
+    );
+    assert_eq!(int("").unwrap(), Decimal::zero());
+    int("1.5").unwrap_err();
+  }
+
+  #[test]
+  fn coin_strings() {
+    let parsed = parse_coin("1000uumee").unwrap();
+    assert_eq!(parsed.amount, "1000");
+    assert_eq!(parsed.denom, "uumee");
+    let parsed = parse_coin("5u/uumee").unwrap();
+    assert_eq!(parsed.denom, "u/uumee");
+    parse_coin("uumee").unwrap_err();
+    parse_coin("1000").unwrap_err();
+    parse_coin("").unwrap_err();
+
+    // an empty amount is a zero coin, an invalid one fails
+    assert_eq!(
+      coin(proto::ProtoCoin {
+        denom: String::from("uumee"),
+        amount: String::new(),
+      })
+      .unwrap(),
+      Coin::new(0, "uumee")
+    );
+    coin(proto::ProtoCoin {
+      denom: String::from("uumee"),
+      amount: String::from("1.5"),
+    })
+    .unwrap_err();
+
+    // the fee queries send the coin string as a coin, an invalid one is left
+    // out for the chain to reject
+    let params = MetokenSwapfeeParams {
+      asset: String::from("100uusdt"),
+      metoken_denom: String::from("me/USD"),
+    };
+    let req: proto::QuerySwapFee = decode(&params.encode_request()).unwrap();
+    assert_eq!(req.asset.unwrap().amount, "100");
+    let params = MetokenSwapfeeParams {
+      asset: String::from("uusdt"),
+      metoken_denom: String::from("me/USD"),
+    };
+    let req: proto::QuerySwapFee = decode(&params.encode_request()).unwrap();
+    assert_eq!(req.asset, None);
+    assert_eq!(req.metoken_denom, "me/USD");
+  }
+
+  #[test]
+  fn max_withdraw_filtering() {
+    let params = MaxWithdrawParams {
+      address: Addr::unchecked("supplier"),
+      denom: String::from("uumee"),
+    };
+    let proto_coin = |amount: &str, denom: &str| proto::ProtoCoin {
+      denom: denom.to_string(),
+      amount: amount.to_string(),
+    };
+
+    // the chain answers every denom, only the queried one is returned
+    let data = proto::QueryMaxWithdrawResponse {
+      u_tokens: vec![proto_coin("10", "u/uatom"), proto_coin("20", "u/uumee")],
+      tokens: vec![proto_coin("11", "uatom"), proto_coin("22", "uumee")],
+    }
+    .encode_to_vec();
+    let res = params.decode_response(&data).unwrap();
+    assert_eq!(res.u_tokens, Coin::new(20, "u/uumee"));
+    assert_eq!(res.tokens, Coin::new(22, "uumee"));
+
+    // a denom missing from the response can't be withdrawn
+    let data = proto::QueryMaxWithdrawResponse {
+      u_tokens: vec![proto_coin("10", "u/uatom")],
+      tokens: vec![],
+    }
+    .encode_to_vec();
+    let res = params.decode_response(&data).unwrap();
+    assert_eq!(res.u_tokens, Coin::new(0, "u/uumee"));
+    assert_eq!(res.tokens, Coin::new(0, "uumee"));
+
+    let req: proto::QueryMaxWithdraw = decode(&params.encode_request()).unwrap();
+    assert_eq!(req.address, "supplier");
+    assert_eq!(req.denom, "uumee");
+  }
+
+  #[test]
+  fn grpc_paths() {
+    let request: QueryRequest<Empty> = MarketSummaryParams {
+      denom: String::from("uumee"),
+    }
+    .to_stargate_query();
+    match request {
+      QueryRequest::Stargate { path, data } => {
+        assert_eq!(path, "/umee.leverage.v1.Query/MarketSummary");
+        let req: proto::QueryMarketSummary = decode(data.as_slice()).unwrap();
+        assert_eq!(req.denom, "uumee");
+      }
+      _ => panic!("Must return a stargate query"),
+    }
+    assert_eq!(
+      <LeverageParametersParams as StargateQuery>::PATH,
+      "/umee.leverage.v1.Query/Params"
+    );
+    assert_eq!(
+      <AccountSummaryParams as StargateQuery>::PATH,
+      "/umee.leverage.v1.Query/AccountSummary"
+    );
+    assert_eq!(
+      <MediansParams as StargateQuery>::PATH,
+      "/umee.oracle.v1.Query/Medians"
+    );
+    assert_eq!(
+      <OracleParametersParams as StargateQuery>::PATH,
+      "/umee.oracle.v1.Query/Params"
+    );
+    assert_eq!(
+      <AccountBondsParams as StargateQuery>::PATH,
+      "/umee.incentive.v1.Query/AccountBonds"
+    );
+    assert_eq!(
+      <MetokenSwapfeeParams as StargateQuery>::PATH,
+      "/umee.metoken.v1.Query/SwapFee"
+    );
+  }
+
+  #[test]
+  fn response_round_trips() {
+    let data = proto::QueryMarketSummaryResponse {
+      symbol_denom: String::from("UMEE"),
+      exponent: 6,
+      oracle_price: String::from("500000000000000000"),
+      utoken_exchange_rate: String::from("1000000000000000000"),
+      supplied: String::from("1000"),
+      ..Default::default()
+    }
+    .encode_to_vec();
+    let res = MarketSummaryParams {
+      denom: String::from("uumee"),
+    }
+    .decode_response(&data)
+    .unwrap();
+    assert_eq!(res.symbol_denom, "UMEE");
+    assert_eq!(res.exponent, 6);
+    assert_eq!(res.oracle_price, Decimal256::percent(50));
+    assert_eq!(res.utoken_exchange_rate, Decimal256::one());
+    assert_eq!(res.supplied, Decimal256::from_ratio(1_000u128, 1u128));
+    assert_eq!(res.borrowed, Decimal256::zero());
+
+    let data = proto::QueryAccountBalancesResponse {
+      supplied: vec![proto::ProtoCoin {
+        denom: String::from("uumee"),
+        amount: String::from("100"),
+      }],
+      collateral: vec![],
+      borrowed: vec![],
+    }
+    .encode_to_vec();
+    let res = AccountBalancesParams {
+      address: Addr::unchecked("supplier"),
+    }
+    .decode_response(&data)
+    .unwrap();
+    assert_eq!(res.supplied, vec![Coin::new(100, "uumee")]);
+    assert!(res.collateral.is_empty());
+
+    // the chains before the historic medians omit the historic fields
+    let summary = |spendable_borrow_limit: &str| {
+      let data = proto::QueryAccountSummaryResponse {
+        borrow_limit: String::from("350000000000000000"),
+        spendable_borrow_limit: spendable_borrow_limit.to_string(),
+        ..Default::default()
+      }
+      .encode_to_vec();
+      AccountSummaryParams {
+        address: Addr::unchecked("borrower"),
+      }
+      .decode_response(&data)
+      .unwrap()
+    };
+    let res = summary("");
+    assert_eq!(res.borrow_limit, Decimal256::percent(35));
+    assert_eq!(res.spendable_borrow_limit, None);
+    assert_eq!(res.historic_liquidation_threshold, None);
+    let res = summary("180000000000000000");
+    assert_eq!(res.spendable_borrow_limit, Some(Decimal256::percent(18)));
+
+    let data = proto::QueryMediansResponse {
+      medians: vec![
+        proto::ProtoDecCoin {
+          denom: String::from("UMEE"),
+          amount: String::from("480000000000000000"),
+        },
+        proto::ProtoDecCoin {
+          denom: String::from("UMEE"),
+          amount: String::from("0.5"),
+        },
+      ],
+    }
+    .encode_to_vec();
+    let res = MediansParams {
+      denom: String::from("UMEE"),
+    }
+    .decode_response(&data)
+    .unwrap();
+    assert_eq!(res.medians[0].amount, Decimal256::percent(48));
+    assert_eq!(res.medians[1].amount, Decimal256::percent(50));
+
+    // a response that is not the protobuf of the query fails to decode
+    MarketSummaryParams {
+      denom: String::from("uumee"),
+    }
+    .decode_response(b"not protobuf")
+    .unwrap_err();
+  }
+}
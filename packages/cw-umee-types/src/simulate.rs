@@ -0,0 +1,289 @@
+use crate::error::ContractError;
+use crate::health::{AccountPosition, PositionSummary, PriceMode};
+use crate::msg_leverage::UmeeMsgLeverage;
+use crate::querier::UmeeQuerier;
+use crate::query_oracle::{MedianDeviationsParams, MediansParams, MediansParamsResponse};
+use cosmwasm_std::{CustomQuery, Decimal256, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// HealthConstraint is the health check of the x/leverage module that a
+// Borrow, Withdraw or Decollateralize fails, in the order they are
+// reported.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthConstraint {
+  // the borrowed value exceeds the borrow limit at the spot prices.
+  SpotPrices,
+  // the borrowed value exceeds the borrow limit at the historic median
+  // prices.
+  HistoricPrices,
+  // the borrowed value at the higher of the spot and historic prices
+  // exceeds the borrow limit at the lower of them, the check of the chain,
+  // while each price set alone is healthy.
+  MixedPrices,
+}
+
+// HistoricSimulation is the position after a leverage msg valued at the
+// spot and at the historic median prices. The borrowed_value and the
+// borrow_limit are the ones the chain compares, at the higher and the lower
+// of the prices of each token. The volatile denoms are the tokens of the
+// position with a spot price out of their historic median by more than the
+// median deviation, their historic price weighs on the check.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HistoricSimulation {
+  pub spot: PositionSummary,
+  pub historic: PositionSummary,
+  pub borrowed_value: Decimal256,
+  pub borrow_limit: Decimal256,
+  pub failed: Option<HealthConstraint>,
+  pub volatile: Vec<String>,
+}
+
+impl HistoricSimulation {
+  // is_healthy is true when the chain accepts the msg.
+  pub fn is_healthy(&self) -> bool {
+    self.failed.is_none()
+  }
+}
+
+// median_of_medians returns the median of the medians. The median of an
+// even count is the average of the middle two, as the oracle computes it.
+pub(crate) fn median_of_medians(medians: &[Decimal256]) -> Option<Decimal256> {
+  let mut values = medians.to_vec();
+  if values.is_empty() {
+    return None;
+  }
+  values.sort();
+  let middle = values.len() / 2;
+  if values.len() % 2 == 1 {
+    return Some(values[middle]);
+  }
+  Some((values[middle - 1] + values[middle]) / Decimal256::from_ratio(2u128, 1u128))
+}
+
+impl AccountPosition {
+  // with_historic_price sets the historic median price of the base denom,
+  // the position of a token with historic_medians is valued at it.
+  pub fn with_historic_price(mut self, denom: &str, price: Decimal256) -> StdResult<Self> {
+    self.market_mut(denom)?.historic_price = Some(price);
+    Ok(self)
+  }
+
+  // with_medians sets the historic price of the base denom to the median
+  // of the historic_medians newest medians of its symbol denom. The medians
+  // response lists the newest first, the order x/oracle iterates the
+  // stamps, the medians carry no block to check it. As the chain, it fails
+  // without that many medians.
+  pub fn with_medians(
+    self,
+    denom: &str,
+    medians: &MediansParamsResponse,
+  ) -> Result<Self, ContractError> {
+    let count = self.market(denom)?.token.historic_medians() as usize;
+    if medians.medians.len() < count {
+      return Err(ContractError::OracleStale {
+        denom: denom.to_string(),
+      });
+    }
+    let values: Vec<Decimal256> = medians
+      .medians
+      .iter()
+      .take(count)
+      .map(|median| median.amount)
+      .collect();
+    let price = median_of_medians(&values).ok_or_else(|| ContractError::OracleStale {
+      denom: denom.to_string(),
+    })?;
+    Ok(self.with_historic_price(denom, price)?)
+  }
+
+  // with_median_deviation sets the median deviation of the base denom.
+  pub fn with_median_deviation(mut self, denom: &str, deviation: Decimal256) -> StdResult<Self> {
+    self.market_mut(denom)?.median_deviation = Some(deviation);
+    Ok(self)
+  }
+
+  // with_historic_medians queries the medians and the median deviation of
+  // the tokens of the position that have historic_medians and sets their
+  // historic prices.
+  pub fn with_historic_medians<C: CustomQuery>(
+    mut self,
+    querier: &UmeeQuerier<C>,
+  ) -> Result<Self, ContractError> {
+    let denoms: Vec<String> = self
+      .collateral
+      .keys()
+      .chain(self.borrowed.keys())
+      .cloned()
+      .collect();
+    for denom in denoms {
+      let token = &self.market(&denom)?.token;
+      if token.historic_medians() == 0 || self.market(&denom)?.historic_price.is_some() {
+        continue;
+      }
+      let symbol_denom = token.symbol_denom().to_string();
+      let medians = querier.medians(MediansParams {
+        denom: symbol_denom.clone(),
+      })?;
+      self = self.with_medians(&denom, &medians)?;
+      let deviations = querier.median_deviations(MedianDeviationsParams {
+        denom: symbol_denom.clone(),
+      })?;
+      if let Some(deviation) = deviations
+        .median_deviations
+        .iter()
+        .find(|deviation| deviation.denom == symbol_denom)
+      {
+        self = self.with_median_deviation(&denom, deviation.amount)?;
+      }
+    }
+    Ok(self)
+  }
+
+  // volatile returns the denoms of the position with historic_medians whose
+  // spot price is out of the historic median by more than the median
+  // deviation.
+  fn volatile(&self) -> Vec<String> {
+    self
+      .markets
+      .iter()
+      .filter(|(denom, _)| {
+        self.collateral.contains_key(*denom) || self.borrowed.contains_key(*denom)
+      })
+      .filter(
+        |(_, market)| match (market.price, market.historic_price, market.median_deviation) {
+          (Some(spot), Some(historic), Some(deviation)) => spot.abs_diff(historic) > deviation,
+          _ => false,
+        },
+      )
+      .map(|(denom, _)| denom.clone())
+      .collect()
+  }
+
+  // simulate_historic returns the position after the Borrow, Withdraw or
+  // Decollateralize msg valued at the spot and the historic prices, and the
+  // first health constraint it fails. The historic prices of the tokens with
+  // historic_medians must be set.
+  pub fn simulate_historic(
+    &self,
+    msg: &UmeeMsgLeverage,
+  ) -> Result<HistoricSimulation, ContractError> {
+    let position = self.apply(msg)?;
+    let spot = position.summary_at(PriceMode::Spot)?;
+    let historic = position.summary_at(PriceMode::Historic)?;
+    let borrowed_value = position.borrowed_value_at(PriceMode::High)?;
+    let borrow_limit = position.borrow_limit_at(PriceMode::Low)?;
+    let failed = if !spot.is_healthy() {
+      Some(HealthConstraint::SpotPrices)
+    } else if !historic.is_healthy() {
+      Some(HealthConstraint::HistoricPrices)
+    } else if borrowed_value > borrow_limit {
+      Some(HealthConstraint::MixedPrices)
+    } else {
+      None
+    };
+    Ok(HistoricSimulation {
+      spot,
+      historic,
+      borrowed_value,
+      borrow_limit,
+      failed,
+      volatile: position.volatile(),
+    })
+  }
+}
+
+#[cfg(all(test, feature = "multitest"))]
+mod tests {
+  use super::*;
+  use crate::multitest::mock_token;
+  use crate::query_leverage::{AccountBalancesResponse, RegisteredTokensResponse};
+  use crate::query_oracle::{DecCoin, ExchangeRatesResponse};
+  use crate::BorrowParams;
+  use cosmwasm_std::{coin, coins};
+
+  fn umee(percent: u64) -> DecCoin {
+    DecCoin {
+      denom: String::from("UMEE"),
+      amount: Decimal256::percent(percent),
+    }
+  }
+
+  fn medians(percents: &[u64]) -> MediansParamsResponse {
+    MediansParamsResponse {
+      medians: percents.iter().map(|percent| umee(*percent)).collect(),
+    }
+  }
+
+  fn position() -> AccountPosition {
+    AccountPosition::new(
+      &AccountBalancesResponse {
+        supplied: coins(1_000_000, "uumee"),
+        collateral: coins(1_000_000, "u/uumee"),
+        borrowed: vec![],
+      },
+      &RegisteredTokensResponse {
+        registry: vec![
+          mock_token("uumee", "UMEE", 6).with_historic_medians(3),
+          mock_token("uatom", "ATOM", 6),
+        ],
+      },
+      &ExchangeRatesResponse {
+        exchange_rates: vec![
+          umee(50),
+          DecCoin {
+            denom: String::from("ATOM"),
+            amount: Decimal256::one(),
+          },
+        ],
+      },
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn historic_medians() {
+    assert_eq!(median_of_medians(&[]), None);
+    assert_eq!(
+      median_of_medians(&[Decimal256::percent(40), Decimal256::percent(30)]),
+      Some(Decimal256::percent(35))
+    );
+
+    // the chain fails without historic_medians medians
+    match position().with_medians("uumee", &medians(&[40, 30])) {
+      Err(ContractError::OracleStale { denom }) => assert_eq!(denom, "uumee"),
+      _ => panic!("Must return oracle stale error"),
+    }
+
+    // the median is of the newest medians, listed first
+    let position = position()
+      .with_medians("uumee", &medians(&[40, 30, 50, 90]))
+      .unwrap();
+    assert_eq!(
+      position.market("uumee").unwrap().historic_price,
+      Some(Decimal256::percent(40))
+    );
+    let borrow = UmeeMsgLeverage::Borrow(BorrowParams {
+      asset: coin(300_000, "uatom"),
+    });
+    let simulation = position.simulate_historic(&borrow).unwrap();
+    assert!(simulation.spot.is_healthy());
+    assert_eq!(simulation.historic.borrow_limit, Decimal256::percent(28));
+    assert_eq!(simulation.failed, Some(HealthConstraint::HistoricPrices));
+    assert!(simulation.volatile.is_empty());
+
+    // the spot price out of the median by more than the deviation is
+    // volatile
+    let position = position
+      .with_median_deviation("uumee", Decimal256::percent(5))
+      .unwrap();
+    let simulation = position.simulate_historic(&borrow).unwrap();
+    assert_eq!(simulation.volatile, vec![String::from("uumee")]);
+    let position = position
+      .with_median_deviation("uumee", Decimal256::percent(10))
+      .unwrap();
+    let simulation = position.simulate_historic(&borrow).unwrap();
+    assert!(simulation.volatile.is_empty());
+  }
+}
@@ -4,65 +4,65 @@ use serde::{Deserialize, Serialize};
 
 // Token defines a token, along with its capital metadata, in the Umee capital
 // facility that can be loaned and borrowed.
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
 pub struct Token {
   // The base_denom defines the denomination of the underlying base token.
-  base_denom: Option<String>,
+  pub(crate) base_denom: Option<String>,
 
   // The reserve factor defines what portion of accrued interest of the asset
   // type goes to reserves.
-  reserve_factor: Decimal,
+  pub(crate) reserve_factor: Decimal,
 
   // The collateral_weight defines what amount of the total value of the asset
   // can contribute to a users borrowing power. If the collateral_weight is
   // zero, using this asset as collateral against borrowing will be disabled.
-  collateral_weight: Decimal,
+  pub(crate) collateral_weight: Decimal,
 
   // The liquidation_threshold defines what amount of the total value of the
   // asset can contribute to a user's liquidation threshold (above which they
   // become eligible for liquidation).
-  liquidation_threshold: Decimal,
+  pub(crate) liquidation_threshold: Decimal,
 
   // The base_borrow_rate defines the base interest rate for borrowing this
   // asset.
-  base_borrow_rate: Decimal,
+  pub(crate) base_borrow_rate: Decimal,
 
   // The kink_borrow_rate defines the interest rate for borrowing this
   // asset when utilization equals to 'kink_utilization'.
-  kink_borrow_rate: Decimal,
+  pub(crate) kink_borrow_rate: Decimal,
 
   // The max_borrow_rate defines the interest rate for borrowing this
   // asset (seen when utilization is 100%).
-  max_borrow_rate: Decimal,
+  pub(crate) max_borrow_rate: Decimal,
 
   // The kink_utilization defines the value where the kink rate kicks off for
   // borrow rates.
-  kink_utilization: Decimal,
+  pub(crate) kink_utilization: Decimal,
 
   // The liquidation_incentive determines the portion of bonus collateral of
   // a token type liquidators receive as a liquidation reward.
-  liquidation_incentive: Decimal,
+  pub(crate) liquidation_incentive: Decimal,
 
   // The symbol_denom and exponent are solely used to update the oracle's accept
   // list of allowed tokens.
-  symbol_denom: Option<String>,
+  pub(crate) symbol_denom: Option<String>,
 
   // Exponent is the power of ten by which to multiply, in order to convert
   // an amount of the token denoted in its symbol denom to the actual amount
   // of its base denom.
-  exponent: u32,
+  pub(crate) exponent: u32,
 
   // Enable Msg Supply allows supplying for lending or collateral using this
   // token. `false` means that a token can no longer be supplied.
   // Note that withdrawing is always enabled. Disabling supply would
   // be one step in phasing out an asset type.
-  enable_msg_supply: Option<bool>,
+  pub(crate) enable_msg_supply: Option<bool>,
 
   // Allows borrowing of this token. Note that repaying is always enabled.
   // Disabling borrowing would be one step in phasing out an asset type, but
   // could also be used from the start for asset types meant to be collateral
   // only, like meTokens.
-  enable_msg_borrow: Option<bool>,
+  pub(crate) enable_msg_borrow: Option<bool>,
 
   // This should only be used to eliminate an asset completely. A blacklisted
   // asset is treated as though its oracle price is zero, and thus ignored by
@@ -70,19 +70,19 @@ pub struct Token {
   // or withdrawn, but not liquidated. A blacklisted token must have enable_lend
   // and enable_borrow set to false. Such tokens can be safely removed from the
   // oracle and price feeder as well.
-  blacklist: Option<bool>,
+  pub(crate) blacklist: Option<bool>,
   // Max Collateral Share specifies how much of the system's overall collateral
   // can be provided by a given token. 1.0 means that the token has no restriction.
   // 0.1 means maximum 10% of system's total collateral value can be provided by this token.
   // Valid values: 0-1.
-  max_collateral_share: Decimal,
+  pub(crate) max_collateral_share: Decimal,
 
   // Max Supply Utilization specifies the maximum supply utilization a token is
   // allowed to reach as a direct result of user borrowing. New borrows are not allowed when
   // the supply utilization is above `max_supply_utilization`.
   //    supply_utilization(token) = total_borrowed(token) / total_supply(token)
   // Valid values: 0-1.
-  max_supply_utilization: Decimal,
+  pub(crate) max_supply_utilization: Decimal,
 
   // Min Collateral Liquidity specifies min limit for the following function:
   //    collateral_liquidity(token) = available(token) / total_collateral(token)
@@ -90,18 +90,193 @@ pub struct Token {
   // result of such action invalidates min_collateral_liquidity.
   // Liquidity can only drop below this value due to interest or liquidations.
   // Valid values: 0 - 1
-  min_collateral_liquidity: Decimal,
+  pub(crate) min_collateral_liquidity: Decimal,
 
   // Max Supply is the maximum amount of tokens the protocol can hold.
   // Adding more supply of the given token to the protocol will return an error.
   // Must be a non negative value. 0 means that there is no limit.
   // To mark a token as not valid for supply, `msg_supply` must be set to false.
-  max_supply: Decimal,
+  pub(crate) max_supply: Decimal,
   // Historic Medians is the number of median historic prices to request from
   // the oracle module when evaluating new borrow positions containing this token.
   // All MsgBorrow, MsgWithdraw, and MsgDecollateralize must result in healthy
   // borrow positions under both current and historic prices. The default value of
   // zero for this field causes current price to be used in those calculations
   // for the affected Token.
-  historic_medians: u32,
+  pub(crate) historic_medians: u32,
+}
+
+impl Token {
+  // new returns the token of the base denom and its symbol denom, the
+  // other fields are the Default zero values, with supply and borrow
+  // disabled, and are set by the with_ builders.
+  pub fn new(
+    base_denom: impl Into<String>,
+    symbol_denom: impl Into<String>,
+    exponent: u32,
+  ) -> Self {
+    Token {
+      base_denom: Some(base_denom.into()),
+      symbol_denom: Some(symbol_denom.into()),
+      exponent,
+      ..Token::default()
+    }
+  }
+
+  pub fn base_denom(&self) -> &str {
+    self.base_denom.as_deref().unwrap_or_default()
+  }
+
+  pub fn reserve_factor(&self) -> Decimal {
+    self.reserve_factor
+  }
+
+  pub fn collateral_weight(&self) -> Decimal {
+    self.collateral_weight
+  }
+
+  pub fn liquidation_threshold(&self) -> Decimal {
+    self.liquidation_threshold
+  }
+
+  pub fn base_borrow_rate(&self) -> Decimal {
+    self.base_borrow_rate
+  }
+
+  pub fn kink_borrow_rate(&self) -> Decimal {
+    self.kink_borrow_rate
+  }
+
+  pub fn max_borrow_rate(&self) -> Decimal {
+    self.max_borrow_rate
+  }
+
+  pub fn kink_utilization(&self) -> Decimal {
+    self.kink_utilization
+  }
+
+  pub fn liquidation_incentive(&self) -> Decimal {
+    self.liquidation_incentive
+  }
+
+  pub fn symbol_denom(&self) -> &str {
+    self.symbol_denom.as_deref().unwrap_or_default()
+  }
+
+  pub fn exponent(&self) -> u32 {
+    self.exponent
+  }
+
+  // The omitted flags of the chain response are false.
+  pub fn enable_msg_supply(&self) -> bool {
+    self.enable_msg_supply.unwrap_or_default()
+  }
+
+  pub fn enable_msg_borrow(&self) -> bool {
+    self.enable_msg_borrow.unwrap_or_default()
+  }
+
+  pub fn blacklist(&self) -> bool {
+    self.blacklist.unwrap_or_default()
+  }
+
+  pub fn max_collateral_share(&self) -> Decimal {
+    self.max_collateral_share
+  }
+
+  pub fn max_supply_utilization(&self) -> Decimal {
+    self.max_supply_utilization
+  }
+
+  pub fn min_collateral_liquidity(&self) -> Decimal {
+    self.min_collateral_liquidity
+  }
+
+  pub fn max_supply(&self) -> Decimal {
+    self.max_supply
+  }
+
+  pub fn historic_medians(&self) -> u32 {
+    self.historic_medians
+  }
+
+  pub fn with_reserve_factor(mut self, reserve_factor: Decimal) -> Self {
+    self.reserve_factor = reserve_factor;
+    self
+  }
+
+  pub fn with_collateral_weight(mut self, collateral_weight: Decimal) -> Self {
+    self.collateral_weight = collateral_weight;
+    self
+  }
+
+  pub fn with_liquidation_threshold(mut self, liquidation_threshold: Decimal) -> Self {
+    self.liquidation_threshold = liquidation_threshold;
+    self
+  }
+
+  pub fn with_base_borrow_rate(mut self, base_borrow_rate: Decimal) -> Self {
+    self.base_borrow_rate = base_borrow_rate;
+    self
+  }
+
+  pub fn with_kink_borrow_rate(mut self, kink_borrow_rate: Decimal) -> Self {
+    self.kink_borrow_rate = kink_borrow_rate;
+    self
+  }
+
+  pub fn with_max_borrow_rate(mut self, max_borrow_rate: Decimal) -> Self {
+    self.max_borrow_rate = max_borrow_rate;
+    self
+  }
+
+  pub fn with_kink_utilization(mut self, kink_utilization: Decimal) -> Self {
+    self.kink_utilization = kink_utilization;
+    self
+  }
+
+  pub fn with_liquidation_incentive(mut self, liquidation_incentive: Decimal) -> Self {
+    self.liquidation_incentive = liquidation_incentive;
+    self
+  }
+
+  pub fn with_enable_msg_supply(mut self, enable_msg_supply: bool) -> Self {
+    self.enable_msg_supply = Some(enable_msg_supply);
+    self
+  }
+
+  pub fn with_enable_msg_borrow(mut self, enable_msg_borrow: bool) -> Self {
+    self.enable_msg_borrow = Some(enable_msg_borrow);
+    self
+  }
+
+  pub fn with_blacklist(mut self, blacklist: bool) -> Self {
+    self.blacklist = Some(blacklist);
+    self
+  }
+
+  pub fn with_max_collateral_share(mut self, max_collateral_share: Decimal) -> Self {
+    self.max_collateral_share = max_collateral_share;
+    self
+  }
+
+  pub fn with_max_supply_utilization(mut self, max_supply_utilization: Decimal) -> Self {
+    self.max_supply_utilization = max_supply_utilization;
+    self
+  }
+
+  pub fn with_min_collateral_liquidity(mut self, min_collateral_liquidity: Decimal) -> Self {
+    self.min_collateral_liquidity = min_collateral_liquidity;
+    self
+  }
+
+  pub fn with_max_supply(mut self, max_supply: Decimal) -> Self {
+    self.max_supply = max_supply;
+    self
+  }
+
+  pub fn with_historic_medians(mut self, historic_medians: u32) -> Self {
+    self.historic_medians = historic_medians;
+    self
+  }
 }
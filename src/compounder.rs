@@ -0,0 +1,349 @@
+use cosmwasm_std::{
+  Coin, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128,
+};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::msg_metoken::{RedeemParams, SwapParams};
+use cw_umee_types::query_incentive::{CurrentRatesParams, PendingRewardsParams};
+use cw_umee_types::query_metoken::{Index, MetokenIndexesParams};
+use cw_umee_types::{
+  AccountBalancesParams, BondParams, ClaimParams, StructUmeeMsg, StructUmeeQuery,
+  SupplyCollateralParams, UmeeQuerier,
+};
+
+use crate::looping::submsg;
+use crate::msg::CompounderResponse;
+use crate::ownership::assert_owner;
+use crate::state::{CompoundSwap, Compounder, COMPOUNDER};
+
+// the reply ids of the compounder submsgs
+pub const COMPOUND_CLAIM_REPLY_ID: u64 = 5;
+pub const COMPOUND_SUPPLY_REPLY_ID: u64 = 6;
+pub const COMPOUND_SWAP_REPLY_ID: u64 = 11;
+
+fn load_compounder(deps: Deps) -> Result<Compounder, ContractError> {
+  COMPOUNDER
+    .may_load(deps.storage)?
+    .ok_or_else(|| ContractError::CustomError {
+      val: String::from("compounder is not set"),
+    })
+}
+
+// collateral returns the collateral uTokens of the denom of the contract.
+fn collateral(deps: Deps, env: &Env, denom: &str) -> StdResult<Uint128> {
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let balances = umee.account_balances(AccountBalancesParams {
+    address: env.contract.address.clone(),
+  })?;
+  let u_denom = format!("u/{}", denom);
+  Ok(
+    balances
+      .collateral
+      .iter()
+      .filter(|coin| coin.denom == u_denom)
+      .map(|coin| coin.amount)
+      .sum(),
+  )
+}
+
+fn add_coin(coins: &mut Vec<Coin>, coin: Coin) {
+  match coins.iter_mut().find(|c| c.denom == coin.denom) {
+    Some(c) => c.amount += coin.amount,
+    None => coins.push(coin),
+  }
+}
+
+// swap_route returns the meToken index that swaps the reward into the bonded
+// denom, the bonded meToken when it accepts the reward, else an index that
+// accepts both the reward and the bonded denom.
+fn swap_route(indexes: &[Index], reward: &str, bonded: &str) -> Option<String> {
+  let accepts = |index: &Index, denom: &str| {
+    index
+      .accepted_assets
+      .iter()
+      .any(|asset| asset.denom == denom)
+  };
+  indexes
+    .iter()
+    .find(|index| index.denom == bonded && accepts(index, reward))
+    .or_else(|| {
+      indexes
+        .iter()
+        .find(|index| accepts(index, reward) && accepts(index, bonded))
+    })
+    .map(|index| index.denom.clone())
+}
+
+// try_set_compounder sets the denom of the bonded asset and the min seconds
+// of rewards between compounds, only the owner can set them. The totals of
+// the compounder are kept.
+pub fn try_set_compounder(
+  deps: DepsMut,
+  info: MessageInfo,
+  denom: String,
+  min_interval: u64,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let compounder = match COMPOUNDER.may_load(deps.storage)? {
+    Some(compounder) => Compounder {
+      denom,
+      min_interval,
+      ..compounder
+    },
+    None => Compounder {
+      denom,
+      min_interval,
+      last_reward_time: None,
+      compounds: 0,
+      compounded: Uint128::zero(),
+      bonded: Uint128::zero(),
+      pending: Uint128::zero(),
+      reward_balances: vec![],
+      swaps: vec![],
+      idle_rewards: vec![],
+    },
+  };
+  COMPOUNDER.save(deps.storage, &compounder)?;
+  Ok(Response::new().add_attribute("method", "set_compounder"))
+}
+
+// try_compound claims the incentive rewards of the contract, swaps the
+// rewards of other denoms into the bonded denom through the meToken indexes,
+// supplies the rewards as collateral and bonds the collateral, once the last
+// reward time of the incentive module is min_interval seconds past the one
+// of the previous compound. The rewards without a meToken route are kept by
+// the contract as idle rewards.
+pub fn try_compound(deps: DepsMut, env: Env) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut compounder = load_compounder(deps.as_ref())?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let last_reward_time = umee.last_reward_time()?.time;
+  if let Some(previous) = compounder.last_reward_time {
+    if last_reward_time - previous < compounder.min_interval as i64 {
+      return Err(ContractError::CustomError {
+        val: format!(
+          "compound interval not elapsed: {} of {} seconds",
+          last_reward_time - previous,
+          compounder.min_interval
+        ),
+      });
+    }
+  }
+
+  // the claimed rewards are the balances over the balances before the claim
+  let rewards = umee
+    .pending_rewards(PendingRewardsParams {
+      address: env.contract.address.to_string(),
+    })?
+    .rewards;
+  compounder.reward_balances = rewards
+    .into_iter()
+    .filter(|reward| reward.denom != compounder.denom)
+    .map(|reward| {
+      deps
+        .querier
+        .query_balance(&env.contract.address, reward.denom)
+    })
+    .collect::<StdResult<_>>()?;
+  compounder.pending = deps
+    .querier
+    .query_balance(&env.contract.address, compounder.denom.clone())?
+    .amount;
+  compounder.last_reward_time = Some(last_reward_time);
+  COMPOUNDER.save(deps.storage, &compounder)?;
+  let claim = submsg(
+    StructUmeeMsg::claim(ClaimParams {}),
+    COMPOUND_CLAIM_REPLY_ID,
+  )?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "compound")
+      .add_submessage(claim),
+  )
+}
+
+// reply_compound_claim queues the swaps of the claimed rewards of other
+// denoms, the rewards without a meToken route are kept as idle rewards.
+pub fn reply_compound_claim(
+  deps: DepsMut,
+  env: Env,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut compounder = load_compounder(deps.as_ref())?;
+  let mut claimed = vec![];
+  for before in std::mem::take(&mut compounder.reward_balances) {
+    let balance = deps
+      .querier
+      .query_balance(&env.contract.address, before.denom.clone())?;
+    let amount = balance.amount.saturating_sub(before.amount);
+    if !amount.is_zero() {
+      claimed.push(Coin::new(amount.u128(), before.denom));
+    }
+  }
+
+  let mut res = Response::new().add_attribute("method", "compound_claim");
+  if !claimed.is_empty() {
+    let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+    let umee = UmeeQuerier::custom(&querier);
+    let indexes = umee
+      .metoken_indexes(MetokenIndexesParams {
+        metoken_denom: String::new(),
+      })?
+      .registry;
+    for reward in claimed {
+      match swap_route(&indexes, &reward.denom, &compounder.denom) {
+        Some(metoken_denom) => {
+          compounder.swaps.push(CompoundSwap::Swap {
+            asset: reward,
+            metoken_denom: metoken_denom.clone(),
+          });
+          if metoken_denom != compounder.denom {
+            compounder.swaps.push(CompoundSwap::Redeem {
+              metoken_denom,
+              before: Uint128::zero(),
+            });
+          }
+        }
+        None => {
+          res = res.add_attribute("idle", reward.to_string());
+          add_coin(&mut compounder.idle_rewards, reward);
+        }
+      }
+    }
+  }
+  compound_next(deps, &env, compounder, res)
+}
+
+// reply_compound_swap sends the next queued swap.
+pub fn reply_compound_swap(
+  deps: DepsMut,
+  env: Env,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let compounder = load_compounder(deps.as_ref())?;
+  let res = Response::new().add_attribute("method", "compound_swap");
+  compound_next(deps, &env, compounder, res)
+}
+
+// compound_next sends the first queued swap, the redeem of a swap redeems
+// the meTokens over the balance before the swap. Once the swaps are done,
+// the bonded denom balance over the balance before the claim is supplied as
+// collateral.
+fn compound_next(
+  deps: DepsMut,
+  env: &Env,
+  mut compounder: Compounder,
+  res: Response<StructUmeeMsg>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let contract = &env.contract.address;
+  while !compounder.swaps.is_empty() {
+    let msg = match compounder.swaps.remove(0) {
+      CompoundSwap::Swap {
+        asset,
+        metoken_denom,
+      } => {
+        if let Some(CompoundSwap::Redeem { before, .. }) = compounder.swaps.first_mut() {
+          *before = deps
+            .querier
+            .query_balance(contract, metoken_denom.clone())?
+            .amount;
+        }
+        StructUmeeMsg::metoken_swap(SwapParams {
+          asset,
+          metoken_denom,
+        })
+      }
+      CompoundSwap::Redeem {
+        metoken_denom,
+        before,
+      } => {
+        let metokens = deps
+          .querier
+          .query_balance(contract, metoken_denom.clone())?
+          .amount
+          .saturating_sub(before);
+        if metokens.is_zero() {
+          continue;
+        }
+        StructUmeeMsg::metoken_redeem(RedeemParams {
+          metoken: Coin::new(metokens.u128(), metoken_denom),
+          asset_denom: compounder.denom.clone(),
+        })
+      }
+    };
+    COMPOUNDER.save(deps.storage, &compounder)?;
+    return Ok(res.add_submessage(submsg(msg, COMPOUND_SWAP_REPLY_ID)?));
+  }
+
+  let balance = deps
+    .querier
+    .query_balance(contract, compounder.denom.clone())?
+    .amount;
+  let claimed = balance.saturating_sub(compounder.pending);
+  if claimed.is_zero() {
+    COMPOUNDER.save(deps.storage, &compounder)?;
+    return Ok(res.add_attribute("compound", "done"));
+  }
+
+  // the bonded uTokens are the collateral over the collateral before the supply
+  compounder.pending = collateral(deps.as_ref(), env, &compounder.denom)?;
+  compounder.compounded += claimed;
+  COMPOUNDER.save(deps.storage, &compounder)?;
+  let supply = submsg(
+    StructUmeeMsg::supply_collateral(SupplyCollateralParams {
+      asset: Coin::new(claimed.u128(), compounder.denom),
+    }),
+    COMPOUND_SUPPLY_REPLY_ID,
+  )?;
+  Ok(res.add_attribute("claimed", claimed).add_submessage(supply))
+}
+
+// reply_compound_supply bonds the collateral uTokens of the rewards.
+pub fn reply_compound_supply(
+  deps: DepsMut,
+  env: Env,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut compounder = load_compounder(deps.as_ref())?;
+  let u_tokens =
+    collateral(deps.as_ref(), &env, &compounder.denom)?.saturating_sub(compounder.pending);
+  let res = Response::new().add_attribute("method", "compound_supply");
+  if u_tokens.is_zero() {
+    return Ok(res.add_attribute("compound", "done"));
+  }
+
+  compounder.bonded += u_tokens;
+  compounder.compounds += 1;
+  compounder.pending = Uint128::zero();
+  COMPOUNDER.save(deps.storage, &compounder)?;
+  let bond = StructUmeeMsg::bond(BondParams {
+    u_token: Coin::new(u_tokens.u128(), format!("u/{}", compounder.denom)),
+  })?;
+  Ok(res.add_submessages(bond.messages))
+}
+
+// returns the compounder totals with the rewards the contract can claim, the
+// current rates of the bonded uToken and the idle rewards.
+pub fn query_compounder(deps: Deps, env: Env) -> StdResult<CompounderResponse> {
+  let compounder = COMPOUNDER
+    .may_load(deps.storage)?
+    .ok_or_else(|| StdError::not_found("compounder"))?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let pending_rewards = umee
+    .pending_rewards(PendingRewardsParams {
+      address: env.contract.address.to_string(),
+    })?
+    .rewards;
+  let current_rates = umee.current_rates(CurrentRatesParams {
+    u_token: format!("u/{}", compounder.denom),
+  })?;
+  Ok(CompounderResponse {
+    denom: compounder.denom,
+    min_interval: compounder.min_interval,
+    last_reward_time: compounder.last_reward_time,
+    compounds: compounder.compounds,
+    compounded: compounder.compounded,
+    bonded: compounder.bonded,
+    pending_rewards,
+    current_rates,
+    idle_rewards: compounder.idle_rewards,
+  })
+}
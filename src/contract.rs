@@ -1,11 +1,12 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{
-  entry_point, Addr, Binary, ContractResult, Deps, DepsMut, Env, MessageInfo, QueryRequest,
+  entry_point, Binary, ContractResult, Deps, DepsMut, Env, MessageInfo, QueryRequest, Reply,
   Response, StdError, StdResult, SystemResult,
 };
 use cosmwasm_std::{from_json, to_json_binary, to_json_vec};
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw_umee_types::error::ContractError;
+use cw_umee_types::querier::query_name;
 use cw_umee_types::query_incentive::{
   AccountBondsParams, AccountBondsResponse, ActualRatesParams, ActualRatesResponse,
   CompletedIncentiveProgramsParams, CompletedIncentiveProgramsResponse, CurrentRatesParams,
@@ -29,20 +30,53 @@ use cw_umee_types::query_oracle::{
   MedianDeviationsParams, MedianDeviationsParamsResponse, MediansParams, MediansParamsResponse,
 };
 use cw_umee_types::{
-  AccountBalancesParams, AccountBalancesResponse, AccountSummaryParams, ActiveExchangeRatesParams,
-  ActiveExchangeRatesResponse, AggregatePrevoteParams, AggregatePrevoteResponse,
-  AggregatePrevotesParams, AggregatePrevotesResponse, AggregateVoteParams, AggregateVoteResponse,
-  AggregateVotesParams, AggregateVotesResponse, ExchangeRatesParams, ExchangeRatesResponse,
-  FeederDelegationParams, FeederDelegationResponse, LeverageParametersParams,
-  LeverageParametersResponse, LiquidationTargetsParams, LiquidationTargetsResponse,
-  MarketSummaryParams, MarketSummaryResponse, MissCounterParams, MissCounterResponse,
-  OracleParametersParams, OracleParametersResponse, RegisteredTokensParams,
-  RegisteredTokensResponse, SlashWindowParams, SlashWindowResponse, StructUmeeMsg, StructUmeeQuery,
-  UmeeMsg, UmeeMsgLeverage, UmeeQuery, UmeeQueryIncentive, UmeeQueryLeverage, UmeeQueryOracle,
+  msg_batch, AccountBalancesParams, AccountBalancesResponse, AccountSummaryParams,
+  AccountSummaryResponse, ActiveExchangeRatesParams, ActiveExchangeRatesResponse,
+  AggregatePrevoteParams, AggregatePrevoteResponse, AggregatePrevotesParams,
+  AggregatePrevotesResponse, AggregateVoteParams, AggregateVoteResponse, AggregateVotesParams,
+  AggregateVotesResponse, ExchangeRatesParams, ExchangeRatesResponse, FeederDelegationParams,
+  FeederDelegationResponse, LeverageParametersParams, LeverageParametersResponse,
+  LiquidationTargetsParams, LiquidationTargetsResponse, MarketSummaryParams, MarketSummaryResponse,
+  MissCounterParams, MissCounterResponse, OracleParametersParams, OracleParametersResponse,
+  RegisteredTokensParams, RegisteredTokensResponse, SlashWindowParams, SlashWindowResponse,
+  StructUmeeMsg, StructUmeeQuery, UmeeMsg, UmeeMsgIncentive, UmeeMsgLeverage, UmeeMsgMeToken,
+  UmeeQuery, UmeeQueryIncentive, UmeeQueryLeverage, UmeeQueryOracle,
 };
 
-use crate::msg::{ExecuteMsg, InstantiateMsg, OwnerResponse, QueryMsg};
+use crate::compounder::{
+  query_compounder, reply_compound_claim, reply_compound_supply, reply_compound_swap, try_compound,
+  try_set_compounder, COMPOUND_CLAIM_REPLY_ID, COMPOUND_SUPPLY_REPLY_ID, COMPOUND_SWAP_REPLY_ID,
+};
+use crate::liquidator::{
+  query_liquidator, reply_liquidate, try_liquidate, try_set_liquidator, LIQUIDATE_REPLY_ID,
+};
+use crate::looping::{
+  query_looping, reply_loop_borrow, reply_loop_supply, reply_unwind_repay, reply_unwind_withdraw,
+  try_deleverage, try_loop_deposit, LOOP_BORROW_REPLY_ID, LOOP_SUPPLY_REPLY_ID,
+  UNWIND_REPAY_REPLY_ID, UNWIND_WITHDRAW_REPLY_ID,
+};
+use crate::migrate::{migrate_state, Version, MIGRATIONS};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, OwnerResponse, QueryMsg};
+use crate::ownership::{
+  query_ownership, try_accept_ownership, try_cancel_ownership_transfer, try_propose_owner,
+  try_renounce_ownership,
+};
+use crate::pause::{
+  assert_not_paused, query_pause_status, try_pause, try_set_guardian, try_unpause, Operation,
+};
+use crate::results::{
+  batch_step, query_leverage_result, reply_leverage, send_leverage, LEVERAGE_REPLY_ID,
+};
+use crate::roles::{
+  assert_umee_role, incentive_msg_name, leverage_msg_name, metoken_msg_name, query_roles,
+  try_grant_role, try_revoke_role, umee_msg_name,
+};
 use crate::state::{State, STATE};
+use crate::vault::{
+  assert_vault_backed, query_balance, query_shares, query_token_info, query_vault,
+  reply_vault_supply, reply_vault_withdraw, try_deposit, try_harvest, try_send, try_set_vault,
+  try_transfer, try_withdraw, VAULT_SUPPLY_REPLY_ID, VAULT_WITHDRAW_REPLY_ID,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:umee-cosmwasm";
@@ -58,7 +92,7 @@ pub fn instantiate(
   _: InstantiateMsg,
 ) -> Result<Response, ContractError> {
   let state = State {
-    owner: info.sender.clone(),
+    owner: Some(info.sender.clone()),
   };
   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
   STATE.save(deps.storage, &state)?;
@@ -70,46 +104,207 @@ pub fn instantiate(
   )
 }
 
+// migrates a deployed contract to this version, it refuses the contracts
+// stored by another name and the downgrades, then runs the state migrations
+// of the versions between the stored one and this one
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _: MigrateMsg) -> Result<Response, ContractError> {
+  let stored = get_contract_version(deps.storage)?;
+  if stored.contract != CONTRACT_NAME {
+    return Err(ContractError::CustomError {
+      val: format!("cannot migrate from contract {}", stored.contract),
+    });
+  }
+  if Version::parse(&stored.version)? > Version::parse(CONTRACT_VERSION)? {
+    return Err(ContractError::CustomError {
+      val: format!(
+        "cannot downgrade from version {} to {}",
+        stored.version, CONTRACT_VERSION
+      ),
+    });
+  }
+  let migrated = migrate_state(deps.branch(), &stored.version, CONTRACT_VERSION, MIGRATIONS)?;
+  set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("method", "migrate")
+      .add_attribute("from_version", stored.version)
+      .add_attribute("to_version", CONTRACT_VERSION)
+      .add_attribute("migrations", migrated.join(",")),
+  )
+}
+
 // executes changes to the state of the contract, it receives messages DepsMut
 // that contains the contract state with write permissions
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
   msg: ExecuteMsg,
 ) -> Result<Response<StructUmeeMsg>, ContractError> {
   match msg {
-    // receives the new owner and tries to change it in the contract state
-    ExecuteMsg::ChangeOwner { new_owner } => try_change_owner(deps, info, new_owner),
+    // proposes the new owner, that accepts the ownership in its own msg
+    ExecuteMsg::ProposeOwner { new_owner, expiry } => {
+      try_propose_owner(deps, env, info, new_owner, expiry)
+    }
+    ExecuteMsg::AcceptOwnership {} => try_accept_ownership(deps, env, info),
+    ExecuteMsg::CancelOwnershipTransfer {} => try_cancel_ownership_transfer(deps, info),
+    ExecuteMsg::RenounceOwnership {} => try_renounce_ownership(deps, info),
+    ExecuteMsg::GrantRole { address, role } => try_grant_role(deps, info, address, role),
+    ExecuteMsg::RevokeRole { address, role } => try_revoke_role(deps, info, address, role),
+    ExecuteMsg::SetGuardian { guardian } => try_set_guardian(deps, info, guardian),
+    ExecuteMsg::Pause(flags) => try_pause(deps, info, flags),
+    ExecuteMsg::Unpause(flags) => try_unpause(deps, info, flags),
     ExecuteMsg::Umee(UmeeMsg::Leverage(execute_leverage_msg)) => {
-      execute_leverage(execute_leverage_msg)
+      execute_leverage(deps, env, info, execute_leverage_msg)
+    }
+    ExecuteMsg::Umee(UmeeMsg::Metoken(execute_metoken_msg)) => {
+      execute_metoken(deps, info, execute_metoken_msg)
+    }
+    ExecuteMsg::Umee(UmeeMsg::Incentive(execute_incentive_msg)) => {
+      execute_incentive(deps, info, execute_incentive_msg)
+    }
+    ExecuteMsg::Batch(umee_msgs) => execute_batch(deps, env, info, umee_msgs),
+    ExecuteMsg::Supply(supply_params) => {
+      assert_umee_role(deps.as_ref(), &info.sender, "supply")?;
+      assert_not_paused(deps.storage, Operation::Deposits)?;
+      StructUmeeMsg::supply(supply_params)
+    }
+    ExecuteMsg::SetLiquidator { budget, min_profit } => {
+      try_set_liquidator(deps, info, budget, min_profit)
+    }
+    ExecuteMsg::Liquidate { limit } => {
+      assert_umee_role(deps.as_ref(), &info.sender, "liquidate")?;
+      assert_not_paused(deps.storage, Operation::Liquidations)?;
+      try_liquidate(deps, limit)
+    }
+    ExecuteMsg::LoopDeposit {
+      target_leverage,
+      max_loops,
+    } => {
+      assert_not_paused(deps.storage, Operation::Deposits)?;
+      assert_not_paused(deps.storage, Operation::Borrows)?;
+      try_loop_deposit(deps, info, target_leverage, max_loops)
+    }
+    ExecuteMsg::Deleverage { denom, max_loops } => {
+      try_deleverage(deps, env, info, denom, max_loops)
+    }
+    ExecuteMsg::SetVault { denom } => try_set_vault(deps, info, denom),
+    ExecuteMsg::Deposit {} => {
+      assert_not_paused(deps.storage, Operation::Deposits)?;
+      try_deposit(deps, info)
+    }
+    ExecuteMsg::Withdraw { shares } => try_withdraw(deps, env, info, shares),
+    ExecuteMsg::Harvest {} => try_harvest(deps, info),
+    ExecuteMsg::Transfer { recipient, amount } => try_transfer(deps, info, recipient, amount),
+    ExecuteMsg::Send {
+      contract,
+      amount,
+      msg,
+    } => try_send(deps, info, contract, amount, msg),
+    ExecuteMsg::SetCompounder {
+      denom,
+      min_interval,
+    } => try_set_compounder(deps, info, denom, min_interval),
+    ExecuteMsg::Compound {} => {
+      assert_not_paused(deps.storage, Operation::Deposits)?;
+      try_compound(deps, env)
     }
-    ExecuteMsg::Supply(supply_params) => StructUmeeMsg::supply(supply_params),
   }
 }
 
-// tries to change the owner, but it could fail and respond as Unauthorized
-pub fn try_change_owner(
+// handles the replies of the submsgs, every step of the looping vault and
+// the compounder replies on success to send the next step, the leverage and
+// liquidate msgs reply with their results, the vault supplies with the
+// uTokens received and the vault withdraws with the tokens received
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(
   deps: DepsMut,
+  env: Env,
+  msg: Reply,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  match msg.id {
+    LOOP_SUPPLY_REPLY_ID => reply_loop_supply(deps, env, msg.result),
+    LOOP_BORROW_REPLY_ID => reply_loop_borrow(deps),
+    UNWIND_WITHDRAW_REPLY_ID => reply_unwind_withdraw(deps, env, msg.result),
+    UNWIND_REPAY_REPLY_ID => reply_unwind_repay(deps, env),
+    COMPOUND_CLAIM_REPLY_ID => reply_compound_claim(deps, env),
+    COMPOUND_SUPPLY_REPLY_ID => reply_compound_supply(deps, env),
+    COMPOUND_SWAP_REPLY_ID => reply_compound_swap(deps, env),
+    LEVERAGE_REPLY_ID => reply_leverage(deps, msg.result),
+    LIQUIDATE_REPLY_ID => reply_liquidate(deps, msg.result),
+    VAULT_SUPPLY_REPLY_ID => reply_vault_supply(deps, msg.result),
+    VAULT_WITHDRAW_REPLY_ID => reply_vault_withdraw(deps, msg.result),
+    id => Err(ContractError::CustomError {
+      val: format!("unknown reply id: {}", id),
+    }),
+  }
+}
+
+// assert_umee_allowed fails unless the sender has the role of the umee msg
+// and its operation is not paused
+fn assert_umee_allowed(
+  deps: Deps,
+  info: &MessageInfo,
+  msg_name: &str,
+) -> Result<(), ContractError> {
+  assert_umee_role(deps, &info.sender, msg_name)?;
+  if let Some(operation) = Operation::umee(msg_name) {
+    assert_not_paused(deps.storage, operation)?;
+  }
+  Ok(())
+}
+
+// execute_batch sends the umee msgs in order in a single response, all the msgs
+// are checked before any is sent and the leverage msgs reply with their results
+fn execute_batch(
+  deps: DepsMut,
+  env: Env,
   info: MessageInfo,
-  new_owner: Addr,
+  umee_msgs: Vec<UmeeMsg>,
 ) -> Result<Response<StructUmeeMsg>, ContractError> {
-  STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-    if info.sender != state.owner {
-      return Err(ContractError::Unauthorized {});
-    }
-    state.owner = new_owner;
-    Ok(state)
-  })?;
-  Ok(Response::<StructUmeeMsg>::new().add_attribute("method", "change_owner"))
+  if umee_msgs.is_empty() {
+    return Err(ContractError::CustomError {
+      val: String::from("empty batch"),
+    });
+  }
+  for umee_msg in &umee_msgs {
+    assert_umee_allowed(deps.as_ref(), &info, umee_msg_name(umee_msg))?;
+  }
+  let leverage_msgs: Vec<&UmeeMsgLeverage> = umee_msgs
+    .iter()
+    .filter_map(|umee_msg| match umee_msg {
+      UmeeMsg::Leverage(leverage_msg) => Some(leverage_msg),
+      _ => None,
+    })
+    .collect();
+  assert_vault_backed(deps.as_ref(), &env, &leverage_msgs)?;
+  let steps = umee_msgs
+    .into_iter()
+    .map(|umee_msg| batch_step(deps.storage, umee_msg))
+    .collect::<StdResult<Vec<_>>>()?;
+  msg_batch(steps)
 }
 
-// execute_leverage handles the execution of every msg of leverage umee native modules
+// execute_leverage handles the execution of every msg of leverage umee native modules,
+// the sender must have the role of the msg and its operation must not be paused,
+// and it can't take the uTokens of the vault.
+// The msg is sent as a submsg and its reply parses the result
 fn execute_leverage(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
   execute_leverage_msg: UmeeMsgLeverage,
 ) -> Result<Response<StructUmeeMsg>, ContractError> {
-  match execute_leverage_msg {
+  assert_umee_allowed(
+    deps.as_ref(),
+    &info,
+    leverage_msg_name(&execute_leverage_msg),
+  )?;
+  assert_vault_backed(deps.as_ref(), &env, &[&execute_leverage_msg])?;
+  let res = match execute_leverage_msg.clone() {
     UmeeMsgLeverage::Supply(supply_params) => StructUmeeMsg::supply(supply_params),
     UmeeMsgLeverage::Withdraw(withdraw_params) => StructUmeeMsg::withdraw(withdraw_params),
     UmeeMsgLeverage::MaxWithdraw(max_withdraw_params) => {
@@ -128,6 +323,46 @@ fn execute_leverage(
     UmeeMsgLeverage::SupplyCollateral(supply_collateralize_params) => {
       StructUmeeMsg::supply_collateral(supply_collateralize_params)
     }
+  };
+  send_leverage(deps.storage, execute_leverage_msg, res)
+}
+
+// execute_metoken handles the execution of every msg of metoken umee native module,
+// the sender must have the role of the msg and its operation must not be paused
+fn execute_metoken(
+  deps: DepsMut,
+  info: MessageInfo,
+  execute_metoken_msg: UmeeMsgMeToken,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_umee_allowed(deps.as_ref(), &info, metoken_msg_name(&execute_metoken_msg))?;
+  match execute_metoken_msg {
+    UmeeMsgMeToken::Swap(swap_params) => StructUmeeMsg::metoken_swap(swap_params),
+    UmeeMsgMeToken::Redeem(redeem_params) => StructUmeeMsg::metoken_redeem(redeem_params),
+  }
+}
+
+// execute_incentive handles the execution of every msg of incentive umee native module,
+// the sender must have the role of the msg and its operation must not be paused
+fn execute_incentive(
+  deps: DepsMut,
+  info: MessageInfo,
+  execute_incentive_msg: UmeeMsgIncentive,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_umee_allowed(
+    deps.as_ref(),
+    &info,
+    incentive_msg_name(&execute_incentive_msg),
+  )?;
+  match execute_incentive_msg {
+    UmeeMsgIncentive::Bond(bond_params) => StructUmeeMsg::bond(bond_params),
+    UmeeMsgIncentive::BeginUnbonding(begin_unbonding_params) => {
+      StructUmeeMsg::begin_unbonding(begin_unbonding_params)
+    }
+    UmeeMsgIncentive::EmergencyUnbond(emergency_unbond_params) => {
+      StructUmeeMsg::emergency_unbond(emergency_unbond_params)
+    }
+    UmeeMsgIncentive::Claim(claim_params) => StructUmeeMsg::claim(claim_params),
+    UmeeMsgIncentive::Sponsor(sponsor_params) => StructUmeeMsg::sponsor(sponsor_params),
   }
 }
 
@@ -138,7 +373,7 @@ fn execute_leverage(
 //   "data": ...
 // }
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
   match msg {
     // returns OwnerResponse the current contract owner
     // expected json input:
@@ -153,6 +388,54 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     // }
     QueryMsg::GetOwner {} => to_json_binary(&query_owner(deps)?),
 
+    // returns OwnershipResponse the owner and the pending owner
+    // expected json input:
+    // {
+    //   "get_ownership": {}
+    // }
+    // successful json output:
+    // {
+    //   "data": {
+    //     "owner": "umee1y6xz2ggfc0pcsmyjlekh0j9pxh6hk87ymc9due",
+    //     "pending_owner": "umee1cc5rrlssfkn3x0hmqx7wxevfyh86xq8d4u6g7j",
+    //     "pending_expiry": {
+    //       "at_height": 1200000
+    //     }
+    //   }
+    // }
+    QueryMsg::GetOwnership {} => to_json_binary(&query_ownership(deps)?),
+
+    // returns RolesResponse the roles of the address
+    // expected json input:
+    // {
+    //   "roles": {
+    //     "address": "umee1y6xz2ggfc0pcsmyjlekh0j9pxh6hk87ymc9due"
+    //   }
+    // }
+    QueryMsg::Roles { address } => to_json_binary(&query_roles(deps, address)?),
+
+    // returns PauseStatusResponse the paused operations and the guardian
+    // expected json input:
+    // {
+    //   "pause_status": {}
+    // }
+    QueryMsg::PauseStatus {} => to_json_binary(&query_pause_status(deps)?),
+
+    // returns LeverageResult the result of the last leverage msg
+    // expected json input:
+    // {
+    //   "leverage_result": {}
+    // }
+    // successful json output:
+    // {
+    //   "data": {
+    //     "supply": {
+    //       "received": { "denom": "u/uumee", "amount": "1000000" }
+    //     }
+    //   }
+    // }
+    QueryMsg::LeverageResult {} => to_json_binary(&query_leverage_result(deps)?),
+
     // queries for anything availabe from the blockchain native modules
     // "iterator, staking, stargate, custom"
     // example json input for custom module:
@@ -172,9 +455,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     //     ...
     //   }
     // }
-    QueryMsg::Chain(request) => query_chain(deps, &request),
+    QueryMsg::Chain(request) => Ok(query_chain(deps, &request)?),
 
-    QueryMsg::Umee(umee_query_box) => query_umee(deps, _env, *umee_query_box),
+    QueryMsg::Umee(umee_query_box) => query_umee(deps, env, *umee_query_box),
 
     // consumes the query_chain wrapping the JSON to call directly
     // the ExchangeRates query from the oracle umee native module
@@ -204,6 +487,59 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     QueryMsg::LeverageParameters(leverage_parameters_params) => to_json_binary(
       &query_leverage_parameters(deps, leverage_parameters_params)?,
     ),
+
+    // returns LiquidatorResponse the liquidator budget and totals
+    // expected json input:
+    // {
+    //   "liquidator": {}
+    // }
+    QueryMsg::Liquidator {} => to_json_binary(&query_liquidator(deps)?),
+
+    // returns LoopingResponse the looped denom and the contract leverage
+    // expected json input:
+    // {
+    //   "looping": {}
+    // }
+    QueryMsg::Looping {} => to_json_binary(&query_looping(deps, env)?),
+
+    // returns VaultResponse the vault position and its share price
+    // expected json input:
+    // {
+    //   "vault": {}
+    // }
+    QueryMsg::Vault {} => to_json_binary(&query_vault(deps)?),
+
+    // returns SharesResponse the vault shares of the address
+    // expected json input:
+    // {
+    //   "shares": {
+    //     "address": "umee1y6xz2ggfc0pcsmyjlekh0j9pxh6hk87ymc9due"
+    //   }
+    // }
+    QueryMsg::Shares { address } => to_json_binary(&query_shares(deps, address)?),
+
+    // returns the cw20 BalanceResponse of the vault shares of the address
+    // expected json input:
+    // {
+    //   "balance": {
+    //     "address": "umee1y6xz2ggfc0pcsmyjlekh0j9pxh6hk87ymc9due"
+    //   }
+    // }
+    QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+
+    // returns the cw20 TokenInfoResponse of the vault shares token
+    // expected json input:
+    // {
+    //   "token_info": {}
+    // }
+    QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+
+    // returns CompounderResponse the compounder totals and pending rewards
+    // expected json input:
+    // {
+    //   "compounder": {}
+    // }
+    QueryMsg::Compounder {} => to_json_binary(&query_compounder(deps, env)?),
   }
 }
 
@@ -256,25 +592,38 @@ fn query_umee(deps: Deps, _env: Env, umee_msg: UmeeQuery) -> StdResult<Binary> {
 }
 
 // returns the current owner of the contract from the state
+// a renounced contract has no owner, the GetOwnership query returns it
 fn query_owner(deps: Deps) -> StdResult<OwnerResponse> {
   let state = STATE.load(deps.storage)?;
-  Ok(OwnerResponse { owner: state.owner })
+  match state.owner {
+    Some(owner) => Ok(OwnerResponse { owner }),
+    None => Err(StdError::not_found("owner")),
+  }
 }
 
 // query_chain queries for any availabe query in the chain native modules
-fn query_chain(deps: Deps, request: &QueryRequest<StructUmeeQuery>) -> StdResult<Binary> {
+fn query_chain(
+  deps: Deps,
+  request: &QueryRequest<StructUmeeQuery>,
+) -> Result<Binary, ContractError> {
   let raw = to_json_vec(request).map_err(|serialize_err| {
     StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
   })?;
   match deps.querier.raw_query(&raw) {
-    SystemResult::Err(system_err) => Err(StdError::generic_err(format!(
-      "Querier system error: {}",
-      system_err
-    ))),
-    SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::generic_err(format!(
-      "Querier contract error: {}",
-      contract_err
-    ))),
+    SystemResult::Err(system_err) => Err(ContractError::QuerierSystem {
+      message: system_err.to_string(),
+    }),
+    SystemResult::Ok(ContractResult::Err(message)) => {
+      let (module, query) = match request {
+        QueryRequest::Custom(umee_query) => query_name(umee_query),
+        _ => (String::from("chain"), String::from("request")),
+      };
+      Err(ContractError::QuerierContract {
+        module,
+        query,
+        message,
+      })
+    }
     SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
   }
 }
@@ -379,7 +728,7 @@ fn query_metoken_indexprice(
   let response: MetokenIndexPricesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MetokenIndexPricesResponse>(&binary) {
@@ -403,7 +752,7 @@ fn query_metoken_indexbalances(
   let response: MetokenIndexbalancesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MetokenIndexbalancesResponse>(&binary) {
@@ -427,7 +776,7 @@ fn query_metoken_redeemfee(
   let response: MetokenRedeemfeeResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MetokenRedeemfeeResponse>(&binary) {
@@ -451,7 +800,7 @@ fn query_metoken_swapfee(
   let response: MetokenSwapfeeResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MetokenSwapfeeResponse>(&binary) {
@@ -475,7 +824,7 @@ fn query_metoken_indexes(
   let response: MetokenIndexesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MetokenIndexesResponse>(&binary) {
@@ -499,7 +848,7 @@ fn query_metoken_params(
   let response: MetokenParametersResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MetokenParametersResponse>(&binary) {
@@ -524,7 +873,7 @@ fn query_last_reward_time(
   let response: LastRewardTimeResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<LastRewardTimeResponse>(&binary) {
@@ -546,7 +895,7 @@ fn query_actutal_rates(deps: Deps, params: ActualRatesParams) -> StdResult<Actua
   let response: ActualRatesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<ActualRatesResponse>(&binary) {
@@ -568,7 +917,7 @@ fn query_current_rates(deps: Deps, params: CurrentRatesParams) -> StdResult<Curr
   let response: CurrentRatesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<CurrentRatesResponse>(&binary) {
@@ -593,7 +942,7 @@ fn query_incentive_program(
   let response: IncentiveProgramResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<IncentiveProgramResponse>(&binary) {
@@ -618,7 +967,7 @@ fn query_upcoming_incentive_programs(
   let response: UpcomingIncentiveProgramsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<UpcomingIncentiveProgramsResponse>(&binary) {
@@ -643,7 +992,7 @@ fn query_ongoing_incentive_programs(
   let response: OngoingIncentiveProgramsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<OngoingIncentiveProgramsResponse>(&binary) {
@@ -668,7 +1017,7 @@ fn query_completed_incentive_programs(
   let response: CompletedIncentiveProgramsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<CompletedIncentiveProgramsResponse>(&binary) {
@@ -693,7 +1042,7 @@ fn query_pending_rewards(
   let response: PendingRewardsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<PendingRewardsResponse>(&binary) {
@@ -715,7 +1064,7 @@ fn query_account_bonds(deps: Deps, params: AccountBondsParams) -> StdResult<Acco
   let response: AccountBondsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<AccountBondsResponse>(&binary) {
@@ -740,7 +1089,7 @@ fn query_total_unbonding(
   let response: TotalUnbondingResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<TotalUnbondingResponse>(&binary) {
@@ -762,7 +1111,7 @@ fn query_total_bonded(deps: Deps, params: TotalBondedParams) -> StdResult<TotalB
   let response: TotalBondedResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<TotalBondedResponse>(&binary) {
@@ -787,7 +1136,7 @@ fn query_incentive_params(
   let incentive_params_response: IncentiveParametersResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<IncentiveParametersResponse>(&binary) {
@@ -873,7 +1222,7 @@ fn query_registered_tokens(
   let registered_tokens_response: RegisteredTokensResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<RegisteredTokensResponse>(&binary) {
@@ -902,7 +1251,7 @@ fn query_leverage_parameters(
   let leverage_parameters_response: LeverageParametersResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<LeverageParametersResponse>(&binary) {
@@ -929,7 +1278,7 @@ fn query_account_balances(
   let account_balances_response: AccountBalancesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<AccountBalancesResponse>(&binary) {
@@ -950,16 +1299,16 @@ fn query_account_balances(
 fn query_account_summary(
   deps: Deps,
   account_summary_params: AccountSummaryParams,
-) -> StdResult<AccountSummaryParams> {
+) -> StdResult<AccountSummaryResponse> {
   let request = QueryRequest::Custom(StructUmeeQuery::account_summary(account_summary_params));
 
-  let account_summary_response: AccountSummaryParams;
+  let account_summary_response: AccountSummaryResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
-      match from_json::<AccountSummaryParams>(&binary) {
+      match from_json::<AccountSummaryResponse>(&binary) {
         Err(err) => {
           return Err(err);
         }
@@ -985,7 +1334,7 @@ fn query_liquidation_targets(
   let liquidation_targets_response: LiquidationTargetsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<LiquidationTargetsResponse>(&binary) {
@@ -1006,7 +1355,7 @@ fn query_bad_debts(deps: Deps, bad_debts_params: BadDebtsParams) -> StdResult<Ba
   let bad_debts_response: BadDebtsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<BadDebtsResponse>(&binary) {
@@ -1031,7 +1380,7 @@ fn query_max_withdraw(
   let max_withdraw_response: MaxWithdrawResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MaxWithdrawResponse>(&binary) {
@@ -1056,7 +1405,7 @@ fn query_max_borrow(
   let max_borrow_response: MaxBorrowResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MaxBorrowResponse>(&binary) {
@@ -1083,7 +1432,7 @@ fn query_market_summary(
   let market_summary_response: MarketSummaryResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MarketSummaryResponse>(&binary) {
@@ -1111,7 +1460,7 @@ fn query_exchange_rates(
   let exchange_rates_resp: ExchangeRatesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<ExchangeRatesResponse>(&binary) {
@@ -1141,7 +1490,7 @@ fn query_active_exchange_rates(
   let active_exchange_rates_resp: ActiveExchangeRatesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<ActiveExchangeRatesResponse>(&binary) {
@@ -1169,7 +1518,7 @@ fn query_feeder_delegation(
   let feeder_delegation_resp: FeederDelegationResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<FeederDelegationResponse>(&binary) {
@@ -1197,7 +1546,7 @@ fn query_miss_counter(
   let miss_counter_resp: MissCounterResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MissCounterResponse>(&binary) {
@@ -1225,7 +1574,7 @@ fn query_slash_window(
   let slash_window_resp: SlashWindowResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<SlashWindowResponse>(&binary) {
@@ -1253,7 +1602,7 @@ fn query_aggregate_prevote(
   let aggregate_prevote_resp: AggregatePrevoteResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<AggregatePrevoteResponse>(&binary) {
@@ -1283,7 +1632,7 @@ fn query_aggregate_prevotes(
   let aggregate_prevotes_resp: AggregatePrevotesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<AggregatePrevotesResponse>(&binary) {
@@ -1311,7 +1660,7 @@ fn query_aggregate_vote(
   let aggregate_vote_resp: AggregateVoteResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<AggregateVoteResponse>(&binary) {
@@ -1339,7 +1688,7 @@ fn query_aggregate_votes(
   let aggregate_votes_resp: AggregateVotesResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<AggregateVotesResponse>(&binary) {
@@ -1367,7 +1716,7 @@ fn query_oracle_parameters(
   let oracle_parameters_resp: OracleParametersResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<OracleParametersResponse>(&binary) {
@@ -1388,7 +1737,7 @@ fn query_medians(deps: Deps, medians_params: MediansParams) -> StdResult<Medians
   let medians_response: MediansParamsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MediansParamsResponse>(&binary) {
@@ -1414,7 +1763,7 @@ fn query_median_deviations(
   let median_deviations_response: MedianDeviationsParamsResponse;
   match query_chain(deps, &request) {
     Err(err) => {
-      return Err(err);
+      return Err(err.into());
     }
     Ok(binary) => {
       match from_json::<MedianDeviationsParamsResponse>(&binary) {
@@ -1434,10 +1783,83 @@ fn query_median_deviations(
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::msg::{
+    CompounderResponse, LiquidatorResponse, LoopingResponse, OwnershipResponse,
+    PauseStatusResponse, RolesResponse, SharesResponse, VaultResponse,
+  };
+  use crate::state::{Expiry, PauseFlags, Role};
+  #[allow(deprecated)]
+  use cosmwasm_std::from_binary;
   use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-  use cosmwasm_std::{coins, from_binary};
+  use cosmwasm_std::{coin, coins, Addr, Decimal, Decimal256, QuerierWrapper, Uint128};
+  use cw20::{BalanceResponse, TokenInfoResponse};
+  use cw_multi_test::{BankSudo, ContractWrapper, Executor, SudoMsg};
+  use cw_umee_types::multitest::{mock_token, umee_app, UmeeApp, METOKEN_MODULE_ADDR};
+  use cw_umee_types::query_incentive::CurrentRatesResponse;
+  use cw_umee_types::query_metoken::{AcceptedAsset, Fee, Index};
+  use cw_umee_types::{
+    parse_chain_error, AccountPosition, BondParams, BorrowParams, BorrowResult, ChainError,
+    CollateralizeParams, DecollateralizeParams, EmergencyUnbondParams, HealthConstraint,
+    LeverageResult, MsgMaxBorrowParams, MsgMaxWithdrawParams, RepayParams, SponsorParams,
+    SupplyCollateralParams, SupplyCollateralResult, SupplyParams, SwapParams, UmeeQuerier,
+    WithdrawParams,
+  };
+
+  // the multitest entry points adapt the contract entry points, that use the
+  // default Deps, to the StructUmeeQuery deps of the UmeeApp.
+  fn multitest_instantiate(
+    deps: DepsMut<StructUmeeQuery>,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+  ) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let deps = DepsMut {
+      storage: deps.storage,
+      api: deps.api,
+      querier: QuerierWrapper::new(&*deps.querier),
+    };
+    let res = instantiate(deps, env, info, msg)?;
+    Ok(Response::new().add_attributes(res.attributes))
+  }
+
+  fn multitest_execute(
+    deps: DepsMut<StructUmeeQuery>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+  ) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let deps = DepsMut {
+      storage: deps.storage,
+      api: deps.api,
+      querier: QuerierWrapper::new(&*deps.querier),
+    };
+    execute(deps, env, info, msg)
+  }
+
+  fn multitest_query(deps: Deps<StructUmeeQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let deps = Deps {
+      storage: deps.storage,
+      api: deps.api,
+      querier: QuerierWrapper::new(&*deps.querier),
+    };
+    query(deps, env, msg)
+  }
+
+  fn multitest_reply(
+    deps: DepsMut<StructUmeeQuery>,
+    env: Env,
+    msg: Reply,
+  ) -> Result<Response<StructUmeeMsg>, ContractError> {
+    let deps = DepsMut {
+      storage: deps.storage,
+      api: deps.api,
+      querier: QuerierWrapper::new(&*deps.querier),
+    };
+    reply(deps, env, msg)
+  }
 
   #[test]
+  #[allow(deprecated)]
   fn proper_initialization() {
     let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
@@ -1455,6 +1877,7 @@ mod tests {
   }
 
   #[test]
+  #[allow(deprecated)]
   fn change_owner() {
     let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
@@ -1469,25 +1892,1782 @@ mod tests {
 
     let new_owner = "new_owner";
 
-    // only the original creator can change the owner the counter
+    // only the original creator can propose the new owner
     let auth_info = mock_info(new_owner, &coins(2, "token"));
-    let msg = ExecuteMsg::ChangeOwner {
-      new_owner: cosmwasm_std::Addr::unchecked(new_owner),
+    let propose = |expiry| ExecuteMsg::ProposeOwner {
+      new_owner: Addr::unchecked(new_owner),
+      expiry,
     };
-    let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+    let res = execute(deps.as_mut(), mock_env(), auth_info, propose(None));
     match res {
       Err(ContractError::Unauthorized {}) => {}
       _ => panic!("Must return unauthorized error"),
     }
 
-    let auth_info = mock_info(first_owner, &coins(2, "token"));
-    let msg = ExecuteMsg::ChangeOwner {
-      new_owner: cosmwasm_std::Addr::unchecked(new_owner),
-    };
-    let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+    // the proposal expires at the height
+    let env = mock_env();
+    let expiry = Expiry::AtHeight(env.block.height + 10);
+    let auth_info = mock_info(first_owner, &[]);
+    execute(
+      deps.as_mut(),
+      env.clone(),
+      auth_info,
+      propose(Some(expiry.clone())),
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwnership {}).unwrap();
+    let value: OwnershipResponse = from_json(&res).unwrap();
+    assert_eq!(value.pending_owner, Some(Addr::unchecked(new_owner)));
+    assert_eq!(value.pending_expiry, Some(expiry));
+
+    // only the pending owner accepts, before the expiry
+    let accept = ExecuteMsg::AcceptOwnership {};
+    let res = execute(
+      deps.as_mut(),
+      env.clone(),
+      mock_info("other", &[]),
+      accept.clone(),
+    );
+    match res {
+      Err(ContractError::Unauthorized {}) => {}
+      _ => panic!("Must return unauthorized error"),
+    }
+    let mut expired = env.clone();
+    expired.block.height += 10;
+    execute(
+      deps.as_mut(),
+      expired,
+      mock_info(new_owner, &[]),
+      accept.clone(),
+    )
+    .unwrap_err();
+
+    // the canceled proposal can't be accepted
+    let auth_info = mock_info(first_owner, &[]);
+    execute(deps.as_mut(), env.clone(), auth_info.clone(), propose(None)).unwrap();
+    let cancel = ExecuteMsg::CancelOwnershipTransfer {};
+    execute(deps.as_mut(), env.clone(), auth_info.clone(), cancel).unwrap();
+    execute(
+      deps.as_mut(),
+      env.clone(),
+      mock_info(new_owner, &[]),
+      accept.clone(),
+    )
+    .unwrap_err();
+
+    execute(deps.as_mut(), env.clone(), auth_info, propose(None)).unwrap();
+    execute(
+      deps.as_mut(),
+      env.clone(),
+      mock_info(new_owner, &[]),
+      accept,
+    )
+    .unwrap();
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
     let value: OwnerResponse = from_binary(&res).unwrap();
     assert_eq!(new_owner, value.owner);
+
+    // the renounced contract has no owner to propose another
+    let renounce = ExecuteMsg::RenounceOwnership {};
+    let auth_info = mock_info(first_owner, &[]);
+    execute(deps.as_mut(), env.clone(), auth_info, renounce.clone()).unwrap_err();
+    let auth_info = mock_info(new_owner, &[]);
+    execute(deps.as_mut(), env.clone(), auth_info.clone(), renounce).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwnership {}).unwrap();
+    let value: OwnershipResponse = from_json(&res).unwrap();
+    assert_eq!(value.owner, None);
+    assert_eq!(value.pending_owner, None);
+    execute(deps.as_mut(), env, auth_info, propose(None)).unwrap_err();
+  }
+
+  #[test]
+  fn leverage_roles() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    let owner = mock_info("creator", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), InstantiateMsg {}).unwrap();
+
+    let supply = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Supply(SupplyParams {
+      asset: coin(100, "uumee"),
+    })));
+    let borrow = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(BorrowParams {
+      asset: coin(50, "uumee"),
+    })));
+
+    // the owner executes every leverage msg, other callers none by default
+    execute(deps.as_mut(), mock_env(), owner.clone(), supply.clone()).unwrap();
+    execute(deps.as_mut(), mock_env(), owner.clone(), borrow.clone()).unwrap();
+    let operator = mock_info("operator", &[]);
+    let res = execute(deps.as_mut(), mock_env(), operator.clone(), supply.clone());
+    match res {
+      Err(ContractError::Unauthorized {}) => {}
+      _ => panic!("Must return unauthorized error"),
+    }
+    let res = execute(
+      deps.as_mut(),
+      mock_env(),
+      operator.clone(),
+      ExecuteMsg::Supply(SupplyParams {
+        asset: coin(100, "uumee"),
+      }),
+    );
+    match res {
+      Err(ContractError::Unauthorized {}) => {}
+      _ => panic!("Must return unauthorized error"),
+    }
+
+    // only the owner grants roles
+    let grant = |address: &str, role| ExecuteMsg::GrantRole {
+      address: Addr::unchecked(address),
+      role,
+    };
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      operator.clone(),
+      grant("operator", Role::Operator {}),
+    )
+    .unwrap_err();
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      grant(
+        "operator",
+        Role::Allowed {
+          msg: String::from("swap"),
+        },
+      ),
+    )
+    .unwrap_err();
+
+    // an operator supplies but doesn't borrow
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      grant("operator", Role::Operator {}),
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), operator.clone(), supply.clone()).unwrap();
+    execute(deps.as_mut(), mock_env(), operator.clone(), borrow.clone()).unwrap_err();
+
+    // an allowed address executes only its msg
+    let allowed = Role::Allowed {
+      msg: String::from("borrow"),
+    };
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      grant("borrower", allowed.clone()),
+    )
+    .unwrap();
+    let borrower = mock_info("borrower", &[]);
+    execute(deps.as_mut(), mock_env(), borrower.clone(), borrow.clone()).unwrap();
+    execute(deps.as_mut(), mock_env(), borrower.clone(), supply).unwrap_err();
+
+    let res = query(
+      deps.as_ref(),
+      mock_env(),
+      QueryMsg::Roles {
+        address: Addr::unchecked("borrower"),
+      },
+    )
+    .unwrap();
+    let value: RolesResponse = from_json(&res).unwrap();
+    assert!(!value.owner);
+    assert!(!value.operator);
+    assert_eq!(value.allowed, vec![String::from("borrow")]);
+
+    // the revoked address can't borrow anymore
+    let revoke = ExecuteMsg::RevokeRole {
+      address: Addr::unchecked("borrower"),
+      role: allowed,
+    };
+    execute(deps.as_mut(), mock_env(), owner, revoke).unwrap();
+    execute(deps.as_mut(), mock_env(), borrower, borrow).unwrap_err();
+    let res = query(
+      deps.as_ref(),
+      mock_env(),
+      QueryMsg::Roles {
+        address: Addr::unchecked("operator"),
+      },
+    )
+    .unwrap();
+    let value: RolesResponse = from_json(&res).unwrap();
+    assert!(value.operator);
+    assert!(value.allowed.is_empty());
+  }
+
+  #[test]
+  fn pause_circuit_breaker() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    let owner = mock_info("creator", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), InstantiateMsg {}).unwrap();
+    let guardian = mock_info("guardian", &[]);
+
+    let supply = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Supply(SupplyParams {
+      asset: coin(100, "uumee"),
+    })));
+    let borrow = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(BorrowParams {
+      asset: coin(50, "uumee"),
+    })));
+    let repay = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Repay(RepayParams {
+      asset: coin(50, "uumee"),
+    })));
+    let pause_borrows = PauseFlags {
+      borrows: true,
+      ..PauseFlags::default()
+    };
+
+    // only the guardian set by the owner and the owner pause
+    let res = execute(
+      deps.as_mut(),
+      mock_env(),
+      guardian.clone(),
+      ExecuteMsg::Pause(pause_borrows),
+    );
+    match res {
+      Err(ContractError::Unauthorized {}) => {}
+      _ => panic!("Must return unauthorized error"),
+    }
+    let set_guardian = ExecuteMsg::SetGuardian {
+      guardian: Some(Addr::unchecked("guardian")),
+    };
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      guardian.clone(),
+      set_guardian.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), owner.clone(), set_guardian).unwrap();
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      guardian.clone(),
+      ExecuteMsg::Pause(pause_borrows),
+    )
+    .unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap();
+    let value: PauseStatusResponse = from_json(&res).unwrap();
+    assert_eq!(value.guardian, Some(Addr::unchecked("guardian")));
+    assert!(value.borrows);
+    assert!(!value.deposits);
+    assert!(!value.liquidations);
+
+    // the paused borrows are rejected, the other msgs go through
+    let res = execute(deps.as_mut(), mock_env(), owner.clone(), borrow.clone());
+    match res {
+      Err(ContractError::Paused { operation }) => assert_eq!(operation, "borrows"),
+      _ => panic!("Must return paused error"),
+    }
+    execute(deps.as_mut(), mock_env(), owner.clone(), supply.clone()).unwrap();
+    execute(deps.as_mut(), mock_env(), owner.clone(), repay.clone()).unwrap();
+
+    // pausing the deposits keeps the borrows paused, the repays never pause
+    let pause_all = PauseFlags {
+      deposits: true,
+      borrows: false,
+      liquidations: true,
+    };
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      ExecuteMsg::Pause(pause_all),
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), owner.clone(), supply.clone()).unwrap_err();
+    execute(deps.as_mut(), mock_env(), owner.clone(), borrow.clone()).unwrap_err();
+    execute(deps.as_mut(), mock_env(), owner.clone(), repay).unwrap();
+
+    // only the owner unpauses
+    let unpause = ExecuteMsg::Unpause(PauseFlags {
+      deposits: true,
+      borrows: true,
+      liquidations: false,
+    });
+    execute(deps.as_mut(), mock_env(), guardian, unpause.clone()).unwrap_err();
+    execute(deps.as_mut(), mock_env(), owner.clone(), unpause).unwrap();
+    execute(deps.as_mut(), mock_env(), owner.clone(), supply).unwrap();
+    execute(deps.as_mut(), mock_env(), owner, borrow).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap();
+    let value: PauseStatusResponse = from_json(&res).unwrap();
+    assert!(!value.deposits);
+    assert!(!value.borrows);
+    assert!(value.liquidations);
+  }
+
+  #[test]
+  fn metoken_swap() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    let info = mock_info("creator", &coins(2, "token"));
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), InstantiateMsg {}).unwrap();
+
+    let msg = ExecuteMsg::Umee(UmeeMsg::Metoken(UmeeMsgMeToken::Swap(SwapParams {
+      asset: coin(100, "uusdt"),
+      metoken_denom: String::from("me/USD"),
+    })));
+    // only the owner and the allowed addresses swap the funds of the contract
+    let res = execute(
+      deps.as_mut(),
+      mock_env(),
+      mock_info("anyone", &[]),
+      msg.clone(),
+    );
+    match res {
+      Err(ContractError::Unauthorized {}) => {}
+      _ => panic!("Must return unauthorized error"),
+    }
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(1, res.messages.len());
+    assert_eq!(res.attributes[0].value, "metoken_swap");
+  }
+
+  #[test]
+  fn incentive_dispatch() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    let owner = mock_info("creator", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), InstantiateMsg {}).unwrap();
+    let operator = mock_info("operator", &[]);
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      ExecuteMsg::GrantRole {
+        address: operator.sender.clone(),
+        role: Role::Operator {},
+      },
+    )
+    .unwrap();
+
+    let bond = ExecuteMsg::Umee(UmeeMsg::Incentive(UmeeMsgIncentive::Bond(BondParams {
+      u_token: coin(100, "u/uumee"),
+    })));
+    let emergency_unbond = ExecuteMsg::Umee(UmeeMsg::Incentive(UmeeMsgIncentive::EmergencyUnbond(
+      EmergencyUnbondParams {
+        u_token: coin(100, "u/uumee"),
+      },
+    )));
+    let sponsor = ExecuteMsg::Umee(UmeeMsg::Incentive(UmeeMsgIncentive::Sponsor(
+      SponsorParams { program: 1 },
+    )));
+
+    // every incentive msg is sent as the custom msg of the incentive module
+    let res = execute(deps.as_mut(), mock_env(), owner.clone(), bond.clone()).unwrap();
+    assert_eq!(1, res.messages.len());
+    assert_eq!(res.attributes[0].value, "bond");
+    let res = execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      emergency_unbond.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[0].value, "emergency_unbond");
+
+    // the operators bond but don't unbond or spend the funds of the contract
+    execute(deps.as_mut(), mock_env(), operator.clone(), bond.clone()).unwrap();
+    for msg in [emergency_unbond, sponsor.clone()] {
+      let res = execute(deps.as_mut(), mock_env(), operator.clone(), msg);
+      match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+      }
+    }
+    let res = execute(
+      deps.as_mut(),
+      mock_env(),
+      mock_info("anyone", &[]),
+      bond.clone(),
+    );
+    match res {
+      Err(ContractError::Unauthorized {}) => {}
+      _ => panic!("Must return unauthorized error"),
+    }
+
+    // an allowed address executes the msg of its role
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      ExecuteMsg::GrantRole {
+        address: operator.sender.clone(),
+        role: Role::Allowed {
+          msg: String::from("sponsor"),
+        },
+      },
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), operator, sponsor).unwrap();
+
+    // the bonds are deposits of the circuit breaker
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      owner.clone(),
+      ExecuteMsg::Pause(PauseFlags {
+        deposits: true,
+        ..PauseFlags::default()
+      }),
+    )
+    .unwrap();
+    let res = execute(deps.as_mut(), mock_env(), owner, bond);
+    match res {
+      Err(ContractError::Paused { operation }) => assert_eq!(operation, "deposits"),
+      _ => panic!("Must return paused error"),
+    }
+  }
+
+  #[test]
+  fn leverage_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::percent(50))
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let creator = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        creator.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: contract.to_string(),
+        amount: coins(1_000_000, "uumee"),
+      }))
+      .unwrap();
+
+    let msg = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::SupplyCollateral(
+      SupplyCollateralParams {
+        asset: coin(1_000_000, "uumee"),
+      },
+    )));
+    app
+      .execute_contract(creator.clone(), contract.clone(), &msg, &[])
+      .unwrap();
+    // the reply parses the uTokens collateralized by the chain
+    let result: LeverageResult = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::LeverageResult {})
+      .unwrap();
+    assert_eq!(
+      result,
+      LeverageResult::SupplyCollateral(SupplyCollateralResult {
+        collateralized: coin(1_000_000, "u/uumee"),
+      })
+    );
+    let msg = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(BorrowParams {
+      asset: coin(500_000, "uumee"),
+    })));
+    let res = app
+      .execute_contract(creator.clone(), contract.clone(), &msg, &[])
+      .unwrap();
+    let result: LeverageResult = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+      result,
+      LeverageResult::Borrow(BorrowResult {
+        borrowed: coin(500_000, "uumee"),
+      })
+    );
+
+    let balances: AccountBalancesResponse = app
+      .wrap()
+      .query_wasm_smart(
+        &contract,
+        &QueryMsg::Umee(Box::new(UmeeQuery::Leverage(
+          UmeeQueryLeverage::AccountBalances(AccountBalancesParams {
+            address: contract.clone(),
+          }),
+        ))),
+      )
+      .unwrap();
+    assert_eq!(balances.supplied, coins(1_000_000, "uumee"));
+    assert_eq!(balances.collateral, coins(1_000_000, "u/uumee"));
+    assert_eq!(balances.borrowed, coins(500_000, "uumee"));
+
+    // the borrow limit is 70% of the collateral value
+    let max_borrow: MaxBorrowResponse = app
+      .wrap()
+      .query_wasm_smart(
+        &contract,
+        &QueryMsg::Umee(Box::new(UmeeQuery::Leverage(UmeeQueryLeverage::MaxBorrow(
+          MaxBorrowParams {
+            address: contract.clone(),
+            denom: String::from("uumee"),
+          },
+        )))),
+      )
+      .unwrap();
+    assert_eq!(max_borrow.tokens, coins(200_000, "uumee"));
+
+    let msg = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(BorrowParams {
+      asset: coin(200_001, "uumee"),
+    })));
+    app
+      .execute_contract(creator, contract.clone(), &msg, &[])
+      .unwrap_err();
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 500_000);
+
+    // the position evaluated off chain matches the account summary
+    let wrapper = app.wrap();
+    let querier = UmeeQuerier::custom(&wrapper);
+    let position = AccountPosition::new(
+      &querier
+        .account_balances(AccountBalancesParams {
+          address: contract.clone(),
+        })
+        .unwrap(),
+      &querier.registered_tokens().unwrap(),
+      &querier
+        .exchange_rates(ExchangeRatesParams {
+          denom: String::new(),
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    let summary = querier
+      .account_summary(AccountSummaryParams {
+        address: contract.clone(),
+      })
+      .unwrap();
+    let health = position.summary().unwrap();
+    assert_eq!(health.collateral_value, summary.collateral_value);
+    assert_eq!(health.borrowed_value, summary.borrowed_value);
+    assert_eq!(health.borrow_limit, summary.borrow_limit);
+    assert_eq!(health.liquidation_threshold, summary.liquidation_threshold);
+    assert_eq!(health.health_factor, Some(Decimal256::percent(160)));
+
+    let borrow = |amount| {
+      UmeeMsgLeverage::Borrow(BorrowParams {
+        asset: coin(amount, "uumee"),
+      })
+    };
+    assert!(position.simulate(&borrow(200_000)).unwrap().is_healthy());
+    assert!(!position.simulate(&borrow(200_001)).unwrap().is_healthy());
+    let decollateralize = UmeeMsgLeverage::Decollateralize(DecollateralizeParams {
+      asset: coin(300_000, "u/uumee"),
+    });
+    assert!(!position.simulate(&decollateralize).unwrap().is_healthy());
+
+    // the chain and simulation failures are typed
+    let decollateralize = UmeeMsgLeverage::Decollateralize(DecollateralizeParams {
+      asset: coin(1_000_001, "u/uumee"),
+    });
+    match position.simulate(&decollateralize) {
+      Err(ContractError::InsufficientCollateral { asset }) => {
+        assert_eq!(asset, coin(1_000_001, "u/uumee"))
+      }
+      res => panic!("Must return insufficient collateral error: {:?}", res),
+    }
+    match querier.market_summary(MarketSummaryParams {
+      denom: String::from("uatom"),
+    }) {
+      Err(ContractError::QuerierContract { module, query, .. }) => {
+        assert_eq!(module, "leverage");
+        assert_eq!(query, "market_summary");
+      }
+      res => panic!("Must return querier contract error: {:?}", res),
+    }
+  }
+
+  #[test]
+  fn account_summary_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(
+          storage,
+          mock_token("uumee", "UMEE", 6).with_historic_medians(24),
+        )
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::percent(50))
+        .unwrap();
+      router
+        .custom
+        .update_oracle(storage, 0, |oracle| {
+          oracle.set_median("UMEE", Decimal256::percent(40));
+          Ok(())
+        })
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let creator = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        creator.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: contract.to_string(),
+        amount: coins(1_000_000, "uumee"),
+      }))
+      .unwrap();
+    app
+      .execute_contract(
+        creator,
+        contract.clone(),
+        &ExecuteMsg::Batch(vec![
+          UmeeMsg::Leverage(UmeeMsgLeverage::Supply(SupplyParams {
+            asset: coin(1_000_000, "uumee"),
+          })),
+          UmeeMsg::Leverage(UmeeMsgLeverage::Collateralize(CollateralizeParams {
+            asset: coin(1_000_000, "u/uumee"),
+          })),
+          UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(BorrowParams {
+            asset: coin(200_000, "uumee"),
+          })),
+        ]),
+        &[],
+      )
+      .unwrap();
+
+    // the contract returns the chain response with its health metrics
+    let summary: AccountSummaryResponse = app
+      .wrap()
+      .query_wasm_smart(
+        &contract,
+        &QueryMsg::Umee(Box::new(UmeeQuery::Leverage(
+          UmeeQueryLeverage::AccountSummary(AccountSummaryParams {
+            address: contract.clone(),
+          }),
+        ))),
+      )
+      .unwrap();
+    let wrapper = app.wrap();
+    let querier = UmeeQuerier::custom(&wrapper);
+    let chain = querier
+      .account_summary(AccountSummaryParams {
+        address: contract.clone(),
+      })
+      .unwrap();
+    assert_eq!(summary, chain);
+    assert_eq!(summary.collateral_value, Decimal256::percent(50));
+    assert_eq!(summary.borrowed_value, Decimal256::percent(10));
+    assert_eq!(summary.borrow_limit, Decimal256::percent(35));
+    assert_eq!(summary.liquidation_threshold, Decimal256::percent(40));
+
+    // the collateral is at the lower historic median, the borrow at the
+    // higher spot price
+    assert_eq!(
+      summary.spendable_borrow_limit,
+      Some(Decimal256::percent(18))
+    );
+    assert_eq!(
+      summary.historic_liquidation_threshold,
+      Some(Decimal256::percent(32))
+    );
+  }
+
+  #[test]
+  fn historic_simulation() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(
+          storage,
+          mock_token("uumee", "UMEE", 6).with_historic_medians(3),
+        )
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::percent(50))
+        .unwrap();
+      router
+        .custom
+        .update_oracle(storage, 0, |oracle| {
+          oracle.set_medians(
+            "UMEE",
+            vec![
+              Decimal256::percent(40),
+              Decimal256::percent(45),
+              Decimal256::percent(35),
+            ],
+          );
+          oracle.set_median_deviation("UMEE", Decimal256::percent(5));
+          Ok(())
+        })
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let creator = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        creator.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: contract.to_string(),
+        amount: coins(1_000_000, "uumee"),
+      }))
+      .unwrap();
+    let borrow = |amount| {
+      UmeeMsgLeverage::Borrow(BorrowParams {
+        asset: coin(amount, "uumee"),
+      })
+    };
+    app
+      .execute_contract(
+        creator.clone(),
+        contract.clone(),
+        &ExecuteMsg::Batch(vec![
+          UmeeMsg::Leverage(UmeeMsgLeverage::Supply(SupplyParams {
+            asset: coin(1_000_000, "uumee"),
+          })),
+          UmeeMsg::Leverage(UmeeMsgLeverage::Collateralize(CollateralizeParams {
+            asset: coin(1_000_000, "u/uumee"),
+          })),
+        ]),
+        &[],
+      )
+      .unwrap();
+    let wrapper = app.wrap();
+    let querier = UmeeQuerier::custom(&wrapper);
+    let position = AccountPosition::new(
+      &querier
+        .account_balances(AccountBalancesParams {
+          address: contract.clone(),
+        })
+        .unwrap(),
+      &querier.registered_tokens().unwrap(),
+      &querier
+        .exchange_rates(ExchangeRatesParams {
+          denom: String::new(),
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    position.simulate_historic(&borrow(1)).unwrap_err();
+    let position = position.with_historic_medians(&querier).unwrap();
+
+    // the collateral at the historic median and the borrow at the spot
+    // price fail the chain check, each price set alone is healthy
+    let simulation = position.simulate_historic(&borrow(600_000)).unwrap();
+    assert!(simulation.spot.is_healthy());
+    assert!(simulation.historic.is_healthy());
+    assert_eq!(simulation.borrowed_value, Decimal256::percent(30));
+    assert_eq!(simulation.borrow_limit, Decimal256::percent(28));
+    assert_eq!(simulation.failed, Some(HealthConstraint::MixedPrices));
+    assert_eq!(simulation.volatile, vec![String::from("uumee")]);
+    let simulation = position.simulate_historic(&borrow(800_000)).unwrap();
+    assert_eq!(simulation.failed, Some(HealthConstraint::SpotPrices));
+    assert!(position
+      .simulate_historic(&borrow(560_000))
+      .unwrap()
+      .is_healthy());
+
+    // the chain agrees with the simulation
+    let batch_borrow = |amount| ExecuteMsg::Batch(vec![UmeeMsg::Leverage(borrow(amount))]);
+    let err = app
+      .execute_contract(
+        creator.clone(),
+        contract.clone(),
+        &batch_borrow(600_000),
+        &[],
+      )
+      .unwrap_err();
+    assert!(matches!(
+      parse_chain_error(&err.root_cause().to_string()),
+      Some(ChainError::Undercollateralized { .. })
+    ));
+    app
+      .execute_contract(creator, contract, &batch_borrow(560_000), &[])
+      .unwrap();
+  }
+
+  #[test]
+  fn batch_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::percent(50))
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let creator = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        creator.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: contract.to_string(),
+        amount: coins(1_000_000, "uumee"),
+      }))
+      .unwrap();
+    let balances = |app: &UmeeApp| -> AccountBalancesResponse {
+      app
+        .wrap()
+        .query_wasm_smart(
+          &contract,
+          &QueryMsg::Umee(Box::new(UmeeQuery::Leverage(
+            UmeeQueryLeverage::AccountBalances(AccountBalancesParams {
+              address: contract.clone(),
+            }),
+          ))),
+        )
+        .unwrap()
+    };
+    let supply_collateral_borrow = |borrow| {
+      ExecuteMsg::Batch(vec![
+        UmeeMsg::Leverage(UmeeMsgLeverage::Supply(SupplyParams {
+          asset: coin(1_000_000, "uumee"),
+        })),
+        UmeeMsg::Leverage(UmeeMsgLeverage::Collateralize(CollateralizeParams {
+          asset: coin(1_000_000, "u/uumee"),
+        })),
+        UmeeMsg::Leverage(UmeeMsgLeverage::Borrow(BorrowParams {
+          asset: coin(borrow, "uumee"),
+        })),
+      ])
+    };
+
+    // every msg of the batch is gated as if executed alone
+    app
+      .execute_contract(
+        Addr::unchecked("other"),
+        contract.clone(),
+        &supply_collateral_borrow(500_000),
+        &[],
+      )
+      .unwrap_err();
+    app
+      .execute_contract(
+        creator.clone(),
+        contract.clone(),
+        &ExecuteMsg::Batch(vec![]),
+        &[],
+      )
+      .unwrap_err();
+
+    // the borrow over the limit reverts the supply and the collateralize
+    let err = app
+      .execute_contract(
+        creator.clone(),
+        contract.clone(),
+        &supply_collateral_borrow(700_001),
+        &[],
+      )
+      .unwrap_err();
+    assert_eq!(
+      parse_chain_error(&err.root_cause().to_string()),
+      Some(ChainError::Undercollateralized {
+        borrowed_value: Some(Decimal256::from_ratio(3_500_005u128, 10_000_000u128)),
+        borrow_limit: Some(Decimal256::percent(35)),
+      })
+    );
+    let res = balances(&app);
+    assert!(res.supplied.is_empty());
+    assert!(res.collateral.is_empty());
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 1_000_000);
+
+    let res = app
+      .execute_contract(
+        creator,
+        contract.clone(),
+        &supply_collateral_borrow(500_000),
+        &[],
+      )
+      .unwrap();
+    let steps: Vec<_> = res
+      .events
+      .iter()
+      .filter(|event| event.ty == "umee_leverage")
+      .filter_map(|event| event.attributes.iter().find(|attr| attr.key == "action"))
+      .map(|attr| attr.value.as_str())
+      .collect();
+    assert_eq!(steps, vec!["supply", "collateralize", "borrow"]);
+
+    // the leverage steps reply with their results, the last is kept
+    let result: LeverageResult = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+      result,
+      LeverageResult::Borrow(BorrowResult {
+        borrowed: coin(500_000, "uumee"),
+      })
+    );
+    let replies = res
+      .events
+      .iter()
+      .filter(|event| event.ty == "reply")
+      .count();
+    assert_eq!(replies, 3);
+    let pending = app
+      .wrap()
+      .query_wasm_raw(&contract, b"pending_leverage".to_vec())
+      .unwrap();
+    assert_eq!(pending, Some(b"[]".to_vec()));
+
+    let res = balances(&app);
+    assert_eq!(res.supplied, coins(1_000_000, "uumee"));
+    assert_eq!(res.collateral, coins(1_000_000, "u/uumee"));
+    assert_eq!(res.borrowed, coins(500_000, "uumee"));
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 500_000);
+  }
+
+  #[test]
+  fn liquidator_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      for (denom, symbol, price) in [("uumee", "UMEE", 1u128), ("uatom", "ATOM", 10u128)] {
+        router
+          .custom
+          .register_token(storage, mock_token(denom, symbol, 6))
+          .unwrap();
+        router
+          .custom
+          .set_price(storage, denom, Decimal256::from_ratio(price, 1u128))
+          .unwrap();
+      }
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let owner = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        owner.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    let supplier = Addr::unchecked("supplier");
+    let borrower = Addr::unchecked("borrower");
+    for (to, coin) in [
+      (&supplier, coin(1_000_000, "uatom")),
+      (&borrower, coin(1_000_000, "uumee")),
+      (&contract, coin(100_000, "uatom")),
+    ] {
+      app
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+          to_address: to.to_string(),
+          amount: vec![coin],
+        }))
+        .unwrap();
+    }
+    let send = |app: &mut UmeeApp, sender: &Addr, res: Response<StructUmeeMsg>| {
+      app
+        .execute(sender.clone(), res.messages[0].msg.clone())
+        .unwrap();
+    };
+    send(
+      &mut app,
+      &supplier,
+      StructUmeeMsg::supply(SupplyParams {
+        asset: coin(1_000_000, "uatom"),
+      })
+      .unwrap(),
+    );
+    send(
+      &mut app,
+      &borrower,
+      StructUmeeMsg::supply_collateral(SupplyCollateralParams {
+        asset: coin(1_000_000, "uumee"),
+      })
+      .unwrap(),
+    );
+    send(
+      &mut app,
+      &borrower,
+      StructUmeeMsg::borrow(BorrowParams {
+        asset: coin(60_000, "uatom"),
+      })
+      .unwrap(),
+    );
+
+    // only the owner sets the budget
+    let set_liquidator = ExecuteMsg::SetLiquidator {
+      budget: coins(100_000, "uatom"),
+      min_profit: Decimal256::zero(),
+    };
+    app
+      .execute_contract(borrower.clone(), contract.clone(), &set_liquidator, &[])
+      .unwrap_err();
+    app
+      .execute_contract(owner.clone(), contract.clone(), &set_liquidator, &[])
+      .unwrap();
+
+    // the borrower is healthy, there is nothing to liquidate, only the owner
+    // and the operators liquidate
+    let liquidate = ExecuteMsg::Liquidate { limit: None };
+    app
+      .execute_contract(owner.clone(), contract.clone(), &liquidate, &[])
+      .unwrap();
+    app
+      .execute_contract(borrower.clone(), contract.clone(), &liquidate, &[])
+      .unwrap_err();
+
+    // the atom price raises the 0.6 borrowed value to 0.84, over the 0.8
+    // liquidation threshold of the umee collateral
+    app.init_modules(|router, _, storage| {
+      router
+        .custom
+        .set_price(storage, "uatom", Decimal256::from_ratio(14u128, 1u128))
+        .unwrap();
+    });
+    app
+      .execute_contract(owner.clone(), contract.clone(), &liquidate, &[])
+      .unwrap();
+
+    let status: LiquidatorResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Liquidator {})
+      .unwrap();
+    assert_eq!(status.liquidations, 1);
+    assert_eq!(status.budget, coins(40_000, "uatom"));
+    assert_eq!(status.repaid, coins(60_000, "uatom"));
+    assert_eq!(status.rewarded, coins(924_000, "u/uumee"));
+    assert_eq!(status.profit, Decimal256::permille(84));
+    let reward = app.wrap().query_balance(&contract, "u/uumee").unwrap();
+    assert_eq!(reward.amount.u128(), 924_000);
+    let res = app
+      .wrap()
+      .query_wasm_raw(&contract, b"pending_liquidations".to_vec())
+      .unwrap();
+    assert_eq!(res, Some(b"[]".to_vec()));
+
+    // the repaid borrower is no longer a target
+    app
+      .execute_contract(owner, contract.clone(), &liquidate, &[])
+      .unwrap();
+    let status: LiquidatorResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Liquidator {})
+      .unwrap();
+    assert_eq!(status.liquidations, 1);
+  }
+
+  #[test]
+  fn looping_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::one())
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let owner = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        owner.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: owner.to_string(),
+        amount: coins(3_000_000, "uumee"),
+      }))
+      .unwrap();
+
+    // the 70% collateral weight caps the leverage under 1 / 0.3
+    let loop_deposit = |target_leverage| ExecuteMsg::LoopDeposit {
+      target_leverage,
+      max_loops: None,
+    };
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &loop_deposit(Decimal256::percent(400)),
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap_err();
+    app
+      .execute_contract(
+        Addr::unchecked("anyone"),
+        contract.clone(),
+        &loop_deposit(Decimal256::percent(250)),
+        &[],
+      )
+      .unwrap_err();
+
+    // the borrows of 700_000, 490_000 and 310_000 reach the 2.5 leverage
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &loop_deposit(Decimal256::percent(250)),
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.denom, "uumee");
+    assert_eq!(looping.supplied.u128(), 2_500_000);
+    assert_eq!(looping.borrowed.u128(), 1_500_000);
+    assert_eq!(looping.leverage, Decimal256::percent(250));
+
+    // the deleverage repays the borrow and withdraws the collateral
+    let deleverage = ExecuteMsg::Deleverage {
+      denom: String::from("uumee"),
+      max_loops: None,
+    };
+    app
+      .execute_contract(owner.clone(), contract.clone(), &deleverage, &[])
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.supplied, Uint128::zero());
+    assert_eq!(looping.borrowed, Uint128::zero());
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 1_000_000);
+
+    // with the borrow at the borrow limit nothing can be withdrawn, the
+    // deleverage repays from the 250_000 idle balance first
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &loop_deposit(Decimal256::percent(250)),
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap();
+    let max_borrow = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::MaxBorrow(
+      MsgMaxBorrowParams {
+        denom: coin(0, "uumee"),
+      },
+    )));
+    app
+      .execute_contract(owner.clone(), contract.clone(), &max_borrow, &[])
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.borrowed.u128(), 1_750_000);
+    app
+      .execute_contract(owner.clone(), contract.clone(), &deleverage, &[])
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.supplied, Uint128::zero());
+    assert_eq!(looping.borrowed, Uint128::zero());
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 2_000_000);
+
+    // the repay that clears the borrow on the last loop still withdraws the
+    // collateral left
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &loop_deposit(Decimal256::percent(110)),
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.borrowed.u128(), 100_000);
+    let deleverage = ExecuteMsg::Deleverage {
+      denom: String::from("uumee"),
+      max_loops: Some(0),
+    };
+    app
+      .execute_contract(owner.clone(), contract.clone(), &deleverage, &[])
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.supplied, Uint128::zero());
+    assert_eq!(looping.borrowed, Uint128::zero());
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 3_000_000);
+  }
+
+  #[test]
+  fn looping_vault_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::one())
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let owner = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        owner.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    let alice = Addr::unchecked("alice");
+    for to in [&owner, &alice] {
+      app
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+          to_address: to.to_string(),
+          amount: coins(1_000_000, "uumee"),
+        }))
+        .unwrap();
+    }
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &ExecuteMsg::SetVault {
+          denom: String::from("uumee"),
+        },
+        &[],
+      )
+      .unwrap();
+
+    // the vault uTokens stay in the wallet of the contract, the loop only
+    // counts its own collateral
+    app
+      .execute_contract(
+        alice.clone(),
+        contract.clone(),
+        &ExecuteMsg::Deposit {},
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap();
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &ExecuteMsg::LoopDeposit {
+          target_leverage: Decimal256::percent(250),
+          max_loops: None,
+        },
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.supplied.u128(), 2_500_000);
+    assert_eq!(looping.borrowed.u128(), 1_500_000);
+
+    // the deleverage withdraws the collateral of the loop, not the vault
+    // uTokens the chain withdraws first
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &ExecuteMsg::Deleverage {
+          denom: String::from("uumee"),
+          max_loops: None,
+        },
+        &[],
+      )
+      .unwrap();
+    let looping: LoopingResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Looping {})
+      .unwrap();
+    assert_eq!(looping.supplied, Uint128::zero());
+    assert_eq!(looping.borrowed, Uint128::zero());
+    let vault: VaultResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Vault {})
+      .unwrap();
+    let u_tokens = app.wrap().query_balance(&contract, "u/uumee").unwrap();
+    assert_eq!(vault.u_tokens.u128(), 1_000_000);
+    assert_eq!(u_tokens.amount, vault.u_tokens);
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 1_000_000);
+
+    // the vault shares are still withdrawable
+    app
+      .execute_contract(
+        alice.clone(),
+        contract.clone(),
+        &ExecuteMsg::Withdraw {
+          shares: Uint128::new(1_000_000),
+        },
+        &[],
+      )
+      .unwrap();
+    let balance = app.wrap().query_balance(&alice, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 1_000_000);
+  }
+
+  #[test]
+  fn vault_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::one())
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let owner = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        owner.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    for (to, amount) in [(&alice, 1_000_000u128), (&bob, 500_000)] {
+      app
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+          to_address: to.to_string(),
+          amount: coins(amount, "uumee"),
+        }))
+        .unwrap();
+    }
+
+    // only the owner sets the vault
+    let set_vault = ExecuteMsg::SetVault {
+      denom: String::from("uumee"),
+    };
+    app
+      .execute_contract(alice.clone(), contract.clone(), &set_vault, &[])
+      .unwrap_err();
+    app
+      .execute_contract(owner.clone(), contract.clone(), &set_vault, &[])
+      .unwrap();
+
+    // the deposits issue a share per uToken at the 1 exchange rate
+    let deposit = ExecuteMsg::Deposit {};
+    app
+      .execute_contract(
+        alice.clone(),
+        contract.clone(),
+        &deposit,
+        &coins(1_000_000, "uumee"),
+      )
+      .unwrap();
+    app
+      .execute_contract(
+        bob.clone(),
+        contract.clone(),
+        &deposit,
+        &coins(500_000, "uumee"),
+      )
+      .unwrap();
+    let vault: VaultResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Vault {})
+      .unwrap();
+    assert_eq!(vault.total_shares.u128(), 1_500_000);
+    assert_eq!(vault.u_tokens.u128(), 1_500_000);
+    assert_eq!(vault.share_price, Decimal256::one());
+
+    // the uTokens of the contract out of the vault don't price the shares
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: contract.to_string(),
+        amount: vec![coin(1_000_000, "u/uumee"), coin(300_000, "uumee")],
+      }))
+      .unwrap();
+    let vault: VaultResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Vault {})
+      .unwrap();
+    assert_eq!(vault.u_tokens.u128(), 1_500_000);
+    assert_eq!(vault.share_price, Decimal256::one());
+
+    // the harvest supplies the tokens sent by the owner, not the idle
+    // balance of the contract
+    let harvest = ExecuteMsg::Harvest {};
+    app
+      .sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: owner.to_string(),
+        amount: coins(300_000, "uumee"),
+      }))
+      .unwrap();
+    app
+      .execute_contract(alice.clone(), contract.clone(), &harvest, &[])
+      .unwrap_err();
+    app
+      .execute_contract(owner.clone(), contract.clone(), &harvest, &[])
+      .unwrap_err();
+    app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &harvest,
+        &coins(300_000, "uumee"),
+      )
+      .unwrap();
+    let vault: VaultResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Vault {})
+      .unwrap();
+    assert_eq!(vault.tokens.u128(), 1_800_000);
+    assert_eq!(vault.harvested.u128(), 300_000);
+    assert_eq!(
+      vault.share_price,
+      Decimal256::from_ratio(1_800_001u128, 1_500_001u128)
+    );
+    let balance = app.wrap().query_balance(&contract, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 300_000);
+
+    // the shares are burned for the tokens they are worth, the virtual share
+    // rounds them down
+    let withdraw = |shares: u128| ExecuteMsg::Withdraw {
+      shares: Uint128::new(shares),
+    };
+    app
+      .execute_contract(bob.clone(), contract.clone(), &withdraw(500_001), &[])
+      .unwrap_err();
+    app
+      .execute_contract(alice.clone(), contract.clone(), &withdraw(1_000_000), &[])
+      .unwrap();
+    let balance = app.wrap().query_balance(&alice, "uumee").unwrap();
+    assert_eq!(balance.amount.u128(), 1_199_999);
+    let shares: SharesResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Shares { address: bob })
+      .unwrap();
+    assert_eq!(shares.shares.u128(), 500_000);
+    assert_eq!(shares.tokens.u128(), 600_000);
+
+    // the shares are the cw20 token of the contract
+    let token_info: TokenInfoResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::TokenInfo {})
+      .unwrap();
+    assert_eq!(token_info.symbol, "vUMEE");
+    assert_eq!(token_info.decimals, 6);
+    assert_eq!(token_info.total_supply.u128(), 500_000);
+    let transfer = ExecuteMsg::Transfer {
+      recipient: String::from("carol"),
+      amount: Uint128::new(200_000),
+    };
+    app
+      .execute_contract(alice.clone(), contract.clone(), &transfer, &[])
+      .unwrap_err();
+    app
+      .execute_contract(Addr::unchecked("bob"), contract.clone(), &transfer, &[])
+      .unwrap();
+    let balance = |address: &str| -> Uint128 {
+      let balance: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+          &contract,
+          &QueryMsg::Balance {
+            address: address.to_string(),
+          },
+        )
+        .unwrap();
+      balance.balance
+    };
+    assert_eq!(balance("bob").u128(), 300_000);
+    assert_eq!(balance("carol").u128(), 200_000);
+
+    // the owner can't withdraw the vault uTokens, only the 1_000_000 out of
+    // the vault
+    let owner_withdraw = |amount: u128| {
+      ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::Withdraw(
+        WithdrawParams {
+          asset: coin(amount, "u/uumee"),
+        },
+      )))
+    };
+    let err = app
+      .execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &owner_withdraw(1_000_001),
+        &[],
+      )
+      .unwrap_err();
+    assert!(err.root_cause().to_string().contains("vault uTokens"));
+    let max_withdraw = ExecuteMsg::Umee(UmeeMsg::Leverage(UmeeMsgLeverage::MaxWithdraw(
+      MsgMaxWithdrawParams {
+        denom: String::from("uumee"),
+      },
+    )));
+    let err = app
+      .execute_contract(owner.clone(), contract.clone(), &max_withdraw, &[])
+      .unwrap_err();
+    assert!(err.root_cause().to_string().contains("vault uTokens"));
+    let vault: VaultResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Vault {})
+      .unwrap();
+    let u_tokens = app.wrap().query_balance(&contract, "u/uumee").unwrap();
+    assert!(u_tokens.amount >= vault.u_tokens);
+  }
+
+  #[test]
+  fn compounder_multitest() {
+    let mut app = umee_app(|router, _, storage| {
+      router
+        .custom
+        .register_token(storage, mock_token("uumee", "UMEE", 6))
+        .unwrap();
+      router
+        .custom
+        .set_price(storage, "uumee", Decimal256::one())
+        .unwrap();
+      router
+        .custom
+        .set_current_rates(
+          storage,
+          CurrentRatesResponse {
+            reference_bond: coin(1_000_000, "u/uumee"),
+            rewards: coins(50_000, "uumee"),
+          },
+        )
+        .unwrap();
+
+      // the atom rewards swap into umee through the meToken basket of both
+      let accepted = |denom: &str| AcceptedAsset {
+        denom: denom.to_string(),
+        reserve_portion: Decimal::percent(20),
+        target_allocation: Decimal::percent(50),
+      };
+      router
+        .custom
+        .register_metoken_index(
+          storage,
+          Index {
+            denom: String::from("me/UMEE"),
+            max_supply: 0,
+            exponent: 6,
+            fee: Fee {
+              min_fee: Decimal::percent(1),
+              balanced_fee: Decimal::percent(2),
+              max_fee: Decimal::percent(5),
+            },
+            accepted_assets: vec![accepted("uumee"), accepted("uatom")],
+          },
+        )
+        .unwrap();
+      router
+        .bank
+        .init_balance(
+          storage,
+          &Addr::unchecked(METOKEN_MODULE_ADDR),
+          coins(1_000_000, "uumee"),
+        )
+        .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+      ContractWrapper::new(multitest_execute, multitest_instantiate, multitest_query)
+        .with_reply(multitest_reply),
+    ));
+    let owner = Addr::unchecked("creator");
+    let contract = app
+      .instantiate_contract(
+        code_id,
+        owner.clone(),
+        &InstantiateMsg {},
+        &[],
+        "umee",
+        None,
+      )
+      .unwrap();
+    let add_rewards = |app: &mut UmeeApp, time: i64| {
+      app.init_modules(|router, _, storage| {
+        router
+          .custom
+          .add_pending_rewards(
+            storage,
+            &contract,
+            vec![
+              coin(100_000, "uumee"),
+              coin(5_000, "uatom"),
+              coin(1_000, "uosmo"),
+            ],
+          )
+          .unwrap();
+        router.custom.set_last_reward_time(storage, time).unwrap();
+      });
+    };
+
+    // only the owner sets the compounder
+    let set_compounder = ExecuteMsg::SetCompounder {
+      denom: String::from("uumee"),
+      min_interval: 3600,
+    };
+    app
+      .execute_contract(
+        Addr::unchecked("anyone"),
+        contract.clone(),
+        &set_compounder,
+        &[],
+      )
+      .unwrap_err();
+    app
+      .execute_contract(owner.clone(), contract.clone(), &set_compounder, &[])
+      .unwrap();
+
+    // the umee rewards are bonded with the atom rewards swapped and redeemed
+    // for umee less the fees, the osmo rewards without a basket are idle
+    add_rewards(&mut app, 1_000);
+    let status: CompounderResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Compounder {})
+      .unwrap();
+    assert_eq!(
+      status.pending_rewards,
+      vec![
+        coin(100_000, "uumee"),
+        coin(5_000, "uatom"),
+        coin(1_000, "uosmo")
+      ]
+    );
+    assert_eq!(status.current_rates.rewards, coins(50_000, "uumee"));
+    let compound = ExecuteMsg::Compound {};
+    app
+      .execute_contract(owner.clone(), contract.clone(), &compound, &[])
+      .unwrap();
+    let status: CompounderResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Compounder {})
+      .unwrap();
+    assert_eq!(status.compounds, 1);
+    assert_eq!(status.compounded.u128(), 104_901);
+    assert_eq!(status.bonded.u128(), 104_901);
+    assert_eq!(status.last_reward_time, Some(1_000));
+    assert!(status.pending_rewards.is_empty());
+    assert_eq!(status.idle_rewards, coins(1_000, "uosmo"));
+    let balances = app.wrap().query_all_balances(&contract).unwrap();
+    assert_eq!(balances, coins(1_000, "uosmo"));
+
+    // the rewards of less than the min interval wait for the next compound
+    add_rewards(&mut app, 2_000);
+    app
+      .execute_contract(owner.clone(), contract.clone(), &compound, &[])
+      .unwrap_err();
+    add_rewards(&mut app, 4_600);
+    app
+      .execute_contract(owner, contract.clone(), &compound, &[])
+      .unwrap();
+    let status: CompounderResponse = app
+      .wrap()
+      .query_wasm_smart(&contract, &QueryMsg::Compounder {})
+      .unwrap();
+    assert_eq!(status.compounds, 2);
+    assert_eq!(status.compounded.u128(), 314_702);
+    assert_eq!(status.idle_rewards, coins(3_000, "uosmo"));
+    let wrapper = app.wrap();
+    let umee = UmeeQuerier::custom(&wrapper);
+    let bonds = umee
+      .account_bonds(AccountBondsParams {
+        address: contract.to_string(),
+      })
+      .unwrap();
+    assert_eq!(bonds.bonded, coins(314_702, "u/uumee"));
+  }
+
+  #[test]
+  fn migrate_versions() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+    let info = mock_info("creator", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+    // an older version migrates to this one
+    set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(res.attributes[1].value, "0.0.1");
+    let version = get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, CONTRACT_VERSION);
+
+    // the downgrades and other contracts are refused
+    set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    set_contract_version(deps.as_mut().storage, "crates.io:other", "0.0.1").unwrap();
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
   }
 }
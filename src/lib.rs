@@ -1,3 +1,12 @@
+pub mod compounder;
 pub mod contract;
+pub mod liquidator;
+pub mod looping;
+pub mod migrate;
 pub mod msg;
+pub mod ownership;
+pub mod pause;
+pub mod results;
+pub mod roles;
 pub mod state;
+pub mod vault;
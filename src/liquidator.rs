@@ -0,0 +1,249 @@
+use cosmwasm_std::{
+  Coin, Decimal256, Deps, DepsMut, MessageInfo, QuerierWrapper, Response, StdResult, SubMsgResult,
+  Uint128,
+};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::{
+  parse_reply, AccountBalancesParams, AccountPosition, ExchangeRatesParams, LiquidateResult,
+  LiquidationPlan, MarketSummaryParams, MarketSummaryResponse, StructUmeeMsg, StructUmeeQuery,
+  UmeeQuerier,
+};
+use std::collections::BTreeMap;
+
+use crate::looping::submsg;
+use crate::msg::LiquidatorResponse;
+use crate::ownership::assert_owner;
+use crate::state::{Liquidator, PendingLiquidation, LIQUIDATOR, PENDING_LIQUIDATIONS};
+
+// the reply id of the liquidate msgs of the liquidator
+pub const LIQUIDATE_REPLY_ID: u64 = 8;
+
+// the targets liquidated by a Liquidate msg without a limit
+const DEFAULT_LIQUIDATION_LIMIT: u32 = 10;
+
+fn default_liquidator() -> Liquidator {
+  Liquidator {
+    budget: vec![],
+    min_profit: Decimal256::zero(),
+    liquidations: 0,
+    repaid: vec![],
+    rewarded: vec![],
+    profit: Decimal256::zero(),
+  }
+}
+
+// add_coin adds the coin to the coins of the same denom
+fn add_coin(coins: &mut Vec<Coin>, coin: &Coin) {
+  match coins.iter_mut().find(|c| c.denom == coin.denom) {
+    Some(c) => c.amount += coin.amount,
+    None => coins.push(coin.clone()),
+  }
+}
+
+// try_set_liquidator sets the budget and the min profit of the liquidator,
+// only the owner can set them. The totals of the liquidator are kept.
+pub fn try_set_liquidator(
+  deps: DepsMut,
+  info: MessageInfo,
+  budget: Vec<Coin>,
+  min_profit: Decimal256,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let mut liquidator = LIQUIDATOR
+    .may_load(deps.storage)?
+    .unwrap_or_else(default_liquidator);
+  liquidator.budget = budget
+    .into_iter()
+    .filter(|coin| !coin.amount.is_zero())
+    .collect();
+  liquidator.min_profit = min_profit;
+  LIQUIDATOR.save(deps.storage, &liquidator)?;
+  Ok(Response::new().add_attribute("method", "set_liquidator"))
+}
+
+// realized_value returns the value of the realized amount of a planned coin,
+// in proportion to the planned value.
+fn realized_value(planned: &Coin, value: Decimal256, realized: Uint128) -> Decimal256 {
+  if planned.amount.is_zero() {
+    return Decimal256::zero();
+  }
+  value * Decimal256::from_ratio(realized, planned.amount)
+}
+
+// try_liquidate liquidates the liquidation targets of the leverage module,
+// for each borrower it picks the most profitable repay and reward denoms
+// the budget can pay and sends the liquidate msg. The profit is the value
+// of the reward over the repayment at the oracle prices, the liquidations
+// under the min profit are skipped.
+//
+// The planned repayment is reserved from the budget and each liquidate msg
+// replies with the amounts the chain repaid and rewarded, that are the ones
+// recorded in the totals.
+pub fn try_liquidate(
+  deps: DepsMut,
+  limit: Option<u32>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut liquidator =
+    LIQUIDATOR
+      .may_load(deps.storage)?
+      .ok_or_else(|| ContractError::CustomError {
+        val: String::from("liquidator budget is not set"),
+      })?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let params = umee.leverage_parameters()?.params;
+  let registry = umee.registered_tokens()?;
+  let prices = umee.exchange_rates(ExchangeRatesParams {
+    denom: String::new(),
+  })?;
+  let mut summaries: BTreeMap<String, MarketSummaryResponse> = BTreeMap::new();
+
+  let mut pending = PENDING_LIQUIDATIONS
+    .may_load(deps.storage)?
+    .unwrap_or_default();
+  let mut res = Response::new().add_attribute("method", "liquidate");
+  let limit = limit.unwrap_or(DEFAULT_LIQUIDATION_LIMIT) as usize;
+  for target in umee.liquidation_targets()?.targets.into_iter().take(limit) {
+    let borrower = deps.api.addr_validate(&target)?;
+    let balances = umee.account_balances(AccountBalancesParams {
+      address: borrower.clone(),
+    })?;
+    let mut position = AccountPosition::new(&balances, &registry, &prices)?;
+    let mut reward_denoms = vec![];
+    for collateral in position.collateral() {
+      let denom = collateral
+        .denom
+        .strip_prefix("u/")
+        .unwrap_or(&collateral.denom)
+        .to_string();
+      if !summaries.contains_key(&denom) {
+        let summary = umee.market_summary(MarketSummaryParams {
+          denom: denom.clone(),
+        })?;
+        summaries.insert(denom.clone(), summary);
+      }
+      position = position.with_market_summary(&denom, &summaries[&denom])?;
+      reward_denoms.push(collateral.denom);
+      reward_denoms.push(denom);
+    }
+
+    let mut best: Option<LiquidationPlan> = None;
+    for budget in &liquidator.budget {
+      for reward_denom in &reward_denoms {
+        // the pairs the borrower can't be liquidated for are skipped
+        let plan =
+          match position.liquidation(&params, &budget.denom, reward_denom, Some(budget.amount)) {
+            Ok(plan) => plan,
+            Err(_) => continue,
+          };
+        if plan.profit < liquidator.min_profit {
+          continue;
+        }
+        if let Some(best) = &best {
+          if plan.profit <= best.profit {
+            continue;
+          }
+        }
+        best = Some(plan);
+      }
+    }
+    let plan = match best {
+      Some(plan) => plan,
+      None => continue,
+    };
+
+    for budget in liquidator.budget.iter_mut() {
+      if budget.denom == plan.repayment.denom {
+        budget.amount = budget.amount.saturating_sub(plan.repayment.amount);
+      }
+    }
+    liquidator.budget.retain(|coin| !coin.amount.is_zero());
+
+    let liquidate = StructUmeeMsg::liquidate(plan.liquidate_params(borrower.clone()));
+    res = res
+      .add_submessage(submsg(liquidate, LIQUIDATE_REPLY_ID)?)
+      .add_attribute("borrower", borrower.clone());
+    pending.push(PendingLiquidation {
+      borrower,
+      repayment: plan.repayment,
+      reward: plan.reward,
+      repayment_value: plan.repayment_value,
+      reward_value: plan.reward_value,
+    });
+  }
+
+  LIQUIDATOR.save(deps.storage, &liquidator)?;
+  PENDING_LIQUIDATIONS.save(deps.storage, &pending)?;
+  Ok(res)
+}
+
+// reply_liquidate records the liquidation the chain executed, the part of
+// the planned repayment that was not repaid returns to the budget and the
+// profit is the value of the reward over the repayment at the plan prices.
+pub fn reply_liquidate(
+  deps: DepsMut,
+  result: SubMsgResult,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut pending = PENDING_LIQUIDATIONS
+    .may_load(deps.storage)?
+    .unwrap_or_default();
+  if pending.is_empty() {
+    return Err(ContractError::CustomError {
+      val: String::from("no liquidation in flight"),
+    });
+  }
+  let liquidation = pending.remove(0);
+  PENDING_LIQUIDATIONS.save(deps.storage, &pending)?;
+  let result: LiquidateResult = parse_reply(result)?;
+
+  let mut liquidator = LIQUIDATOR.load(deps.storage)?;
+  let unused = liquidation
+    .repayment
+    .amount
+    .saturating_sub(result.repaid.amount);
+  if !unused.is_zero() {
+    add_coin(
+      &mut liquidator.budget,
+      &Coin::new(unused.u128(), &liquidation.repayment.denom),
+    );
+  }
+  add_coin(&mut liquidator.repaid, &result.repaid);
+  add_coin(&mut liquidator.rewarded, &result.reward);
+  let repaid_value = realized_value(
+    &liquidation.repayment,
+    liquidation.repayment_value,
+    result.repaid.amount,
+  );
+  let reward_value = realized_value(
+    &liquidation.reward,
+    liquidation.reward_value,
+    result.reward.amount,
+  );
+  liquidator.profit += reward_value.saturating_sub(repaid_value);
+  liquidator.liquidations += 1;
+  LIQUIDATOR.save(deps.storage, &liquidator)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("method", "liquidate_result")
+      .add_attribute("borrower", liquidation.borrower)
+      .add_attribute("repaid", result.repaid.to_string())
+      .add_attribute("reward", result.reward.to_string()),
+  )
+}
+
+// returns the liquidator budget and totals, they are empty until the owner
+// sets the budget.
+pub fn query_liquidator(deps: Deps) -> StdResult<LiquidatorResponse> {
+  let liquidator = LIQUIDATOR
+    .may_load(deps.storage)?
+    .unwrap_or_else(default_liquidator);
+  Ok(LiquidatorResponse {
+    budget: liquidator.budget,
+    min_profit: liquidator.min_profit,
+    liquidations: liquidator.liquidations,
+    repaid: liquidator.repaid,
+    rewarded: liquidator.rewarded,
+    profit: liquidator.profit,
+  })
+}
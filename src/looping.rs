@@ -0,0 +1,410 @@
+use cosmwasm_std::{
+  Addr, Coin, Decimal256, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError,
+  StdResult, SubMsg, SubMsgResult, Uint128,
+};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::query_leverage::{MaxBorrowParams, MaxWithdrawParams};
+use cw_umee_types::{
+  parse_reply, AccountBalancesParams, BorrowParams, DecollateralizeParams, MarketSummaryParams,
+  RepayParams, StructUmeeMsg, StructUmeeQuery, SupplyCollateralParams, SupplyCollateralResult,
+  UmeeQuerier, WithdrawParams, WithdrawResult,
+};
+
+use crate::msg::LoopingResponse;
+use crate::ownership::assert_owner;
+use crate::state::{Looping, LOOPING};
+use std::convert::TryFrom;
+
+// the reply ids of the looping submsgs
+pub const LOOP_SUPPLY_REPLY_ID: u64 = 1;
+pub const LOOP_BORROW_REPLY_ID: u64 = 2;
+pub const UNWIND_WITHDRAW_REPLY_ID: u64 = 3;
+pub const UNWIND_REPAY_REPLY_ID: u64 = 4;
+
+// the loops of a LoopDeposit or Deleverage msg without max_loops
+const DEFAULT_MAX_LOOPS: u32 = 10;
+
+// submsg sends the msg of a StructUmeeMsg constructor response as a submsg
+// replying on success to the id.
+pub(crate) fn submsg(
+  res: Result<Response<StructUmeeMsg>, ContractError>,
+  id: u64,
+) -> Result<SubMsg<StructUmeeMsg>, ContractError> {
+  match res?.messages.into_iter().next() {
+    Some(msg) => Ok(SubMsg::reply_on_success(msg.msg, id)),
+    None => Err(ContractError::InvalidUmeeMsg {}),
+  }
+}
+
+fn amount_of(coins: &[Coin], denom: &str) -> Uint128 {
+  coins
+    .iter()
+    .filter(|coin| coin.denom == denom)
+    .map(|coin| coin.amount)
+    .sum()
+}
+
+// LoopPosition is the tokens the collateral of the loop is worth and the
+// borrowed tokens of the looped denom.
+struct LoopPosition {
+  supplied: Uint128,
+  borrowed: Uint128,
+}
+
+// loop_position values the collateral uTokens of the loop at the uToken
+// exchange rate, the supplies of the vault and the compounder are not part
+// of the loop equity.
+fn loop_position(
+  umee: &UmeeQuerier<StructUmeeQuery>,
+  address: &Addr,
+  looping: &Looping,
+) -> StdResult<LoopPosition> {
+  let balances = umee.account_balances(AccountBalancesParams {
+    address: address.clone(),
+  })?;
+  let summary = umee.market_summary(MarketSummaryParams {
+    denom: looping.denom.clone(),
+  })?;
+  let supplied = Decimal256::from_ratio(looping.collateral, 1u128) * summary.utoken_exchange_rate();
+  Ok(LoopPosition {
+    supplied: Uint128::try_from(supplied.to_uint_floor()).map_err(StdError::from)?,
+    borrowed: amount_of(&balances.borrowed, &looping.denom),
+  })
+}
+
+fn utoken_denom(denom: &str) -> String {
+  format!("u/{}", denom)
+}
+
+// try_loop_deposit supplies the deposit as collateral and starts the loop
+// that borrows the same denom and supplies it back as collateral, until the
+// supplied tokens reach the target leverage over the equity of the contract
+// or the max loops. The target must be under the max leverage of the
+// collateral weight of the token, 1 / (1 - collateral_weight).
+pub fn try_loop_deposit(
+  deps: DepsMut,
+  info: MessageInfo,
+  target_leverage: Decimal256,
+  max_loops: Option<u32>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let deposit = match info.funds.as_slice() {
+    [deposit] if !deposit.amount.is_zero() => deposit.clone(),
+    _ => {
+      return Err(ContractError::CustomError {
+        val: String::from("loop deposit must be a single coin"),
+      })
+    }
+  };
+  if target_leverage < Decimal256::one() {
+    return Err(ContractError::CustomError {
+      val: String::from("target leverage must be at least 1"),
+    });
+  }
+
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let token = umee
+    .registered_tokens()?
+    .registry
+    .into_iter()
+    .find(|token| token.base_denom() == deposit.denom)
+    .ok_or_else(|| ContractError::CustomError {
+      val: format!("not a registered Token: {}", deposit.denom),
+    })?;
+  for (operation, enabled) in [
+    ("supply", token.enable_msg_supply()),
+    ("borrow", token.enable_msg_borrow()),
+  ] {
+    if token.blacklist() || !enabled {
+      return Err(ContractError::MarketDisabled {
+        denom: deposit.denom,
+        operation: operation.to_string(),
+      });
+    }
+  }
+  let collateral_weight = Decimal256::from(token.collateral_weight());
+  if collateral_weight >= Decimal256::one()
+    || target_leverage * (Decimal256::one() - collateral_weight) >= Decimal256::one()
+  {
+    return Err(ContractError::CustomError {
+      val: format!(
+        "target leverage {} exceeds the max leverage of {}",
+        target_leverage, deposit.denom
+      ),
+    });
+  }
+
+  // a deposit adds to the loop of the denom, another denom can only be
+  // looped once the collateral of the loop is withdrawn
+  let collateral = match LOOPING.may_load(deps.storage)? {
+    Some(looping) if looping.denom == deposit.denom => looping.collateral,
+    Some(looping) if !looping.collateral.is_zero() => {
+      return Err(ContractError::CustomError {
+        val: format!("loop of {} is not unwound", looping.denom),
+      })
+    }
+    _ => Uint128::zero(),
+  };
+  LOOPING.save(
+    deps.storage,
+    &Looping {
+      denom: deposit.denom.clone(),
+      target_leverage,
+      loops_left: max_loops.unwrap_or(DEFAULT_MAX_LOOPS),
+      pending: deposit.amount,
+      collateral,
+    },
+  )?;
+  let supply = submsg(
+    StructUmeeMsg::supply_collateral(SupplyCollateralParams { asset: deposit }),
+    LOOP_SUPPLY_REPLY_ID,
+  )?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "loop_deposit")
+      .add_submessage(supply),
+  )
+}
+
+// reply_loop_supply adds the collateralized uTokens to the loop and borrows
+// the tokens missing to reach the target leverage, bounded by the MaxBorrow
+// of the contract, or ends the loop.
+pub fn reply_loop_supply(
+  deps: DepsMut,
+  env: Env,
+  result: SubMsgResult,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let supply = parse_reply::<SupplyCollateralResult>(result)?;
+  let mut looping = LOOPING.load(deps.storage)?;
+  looping.collateral += supply.collateralized.amount;
+  LOOPING.save(deps.storage, &looping)?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let position = loop_position(&umee, &env.contract.address, &looping)?;
+
+  let equity = position.supplied.saturating_sub(position.borrowed);
+  let target_borrowed = Uint128::try_from(
+    (Decimal256::from_ratio(equity, 1u128) * (looping.target_leverage - Decimal256::one()))
+      .to_uint_floor(),
+  )
+  .map_err(StdError::from)?;
+  let max_borrow = amount_of(
+    &umee
+      .max_borrow(MaxBorrowParams {
+        address: env.contract.address.clone(),
+        denom: looping.denom.clone(),
+      })?
+      .tokens,
+    &looping.denom,
+  );
+  let amount = target_borrowed
+    .saturating_sub(position.borrowed)
+    .min(max_borrow);
+
+  let res = Response::new().add_attribute("method", "loop_supply");
+  if amount.is_zero() || looping.loops_left == 0 {
+    return Ok(res.add_attribute("loop", "done"));
+  }
+  looping.loops_left -= 1;
+  looping.pending = amount;
+  LOOPING.save(deps.storage, &looping)?;
+  let borrow = submsg(
+    StructUmeeMsg::borrow(BorrowParams {
+      asset: Coin::new(amount.u128(), looping.denom),
+    }),
+    LOOP_BORROW_REPLY_ID,
+  )?;
+  Ok(res.add_submessage(borrow))
+}
+
+// reply_loop_borrow supplies the borrowed tokens back as collateral.
+pub fn reply_loop_borrow(deps: DepsMut) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let looping = LOOPING.load(deps.storage)?;
+  let supply = submsg(
+    StructUmeeMsg::supply_collateral(SupplyCollateralParams {
+      asset: Coin::new(looping.pending.u128(), looping.denom),
+    }),
+    LOOP_SUPPLY_REPLY_ID,
+  )?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "loop_borrow")
+      .add_submessage(supply),
+  )
+}
+
+// try_deleverage unwinds the loop of the denom, it withdraws the collateral
+// of the loop the contract can withdraw and repays the borrow with it, until
+// the borrow is repaid and the collateral is withdrawn or the max loops.
+// When no collateral can be withdrawn the borrow is repaid from the idle
+// balance of the contract.
+pub fn try_deleverage(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  denom: String,
+  max_loops: Option<u32>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let looping = LOOPING
+    .may_load(deps.storage)?
+    .filter(|looping| looping.denom == denom)
+    .ok_or_else(|| ContractError::CustomError {
+      val: format!("no loop of {}", denom),
+    })?;
+  LOOPING.save(
+    deps.storage,
+    &Looping {
+      target_leverage: Decimal256::one(),
+      loops_left: max_loops.unwrap_or(DEFAULT_MAX_LOOPS),
+      pending: Uint128::zero(),
+      ..looping
+    },
+  )?;
+  let res = unwind_withdraw(deps, env)?;
+  Ok(res.add_attribute("method", "deleverage"))
+}
+
+// unwind_withdraw withdraws the collateral of the loop the contract can
+// withdraw, or repays the borrow with the idle balance when nothing can be
+// withdrawn.
+//
+// The chain withdraws the uTokens in the wallet of the contract first, they
+// are the supplies of the vault, and the MaxWithdraw counts them. So the
+// collateral withdrawn is the MaxWithdraw over the wallet uTokens, capped
+// to the collateral of the loop, and it is decollateralized before the
+// withdraw.
+fn unwind_withdraw(deps: DepsMut, env: Env) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut looping = LOOPING.load(deps.storage)?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let u_denom = utoken_denom(&looping.denom);
+  let max_withdraw = umee.max_withdraw(MaxWithdrawParams {
+    address: env.contract.address.clone(),
+    denom: looping.denom.clone(),
+  })?;
+  let wallet = querier
+    .query_balance(&env.contract.address, &u_denom)?
+    .amount;
+  let u_tokens = max_withdraw
+    .u_tokens
+    .amount
+    .saturating_sub(wallet)
+    .min(looping.collateral);
+  if !u_tokens.is_zero() {
+    looping.collateral -= u_tokens;
+    LOOPING.save(deps.storage, &looping)?;
+    let asset = Coin::new(u_tokens.u128(), u_denom);
+    let decollateralize = StructUmeeMsg::decollateralize(DecollateralizeParams {
+      asset: asset.clone(),
+    })?;
+    let withdraw = submsg(
+      StructUmeeMsg::withdraw(WithdrawParams { asset }),
+      UNWIND_WITHDRAW_REPLY_ID,
+    )?;
+    return Ok(
+      Response::new()
+        .add_submessages(decollateralize.messages)
+        .add_submessage(withdraw),
+    );
+  }
+
+  let position = loop_position(&umee, &env.contract.address, &looping)?;
+  let idle = querier
+    .query_balance(&env.contract.address, &looping.denom)?
+    .amount;
+  if position.borrowed.is_zero() {
+    return Ok(Response::new().add_attribute("loop", "done"));
+  }
+  if idle.is_zero() {
+    return Err(ContractError::CustomError {
+      val: format!("nothing to withdraw or repay to unwind {}", looping.denom),
+    });
+  }
+  let repay = submsg(
+    StructUmeeMsg::repay(RepayParams {
+      asset: Coin::new(idle.min(position.borrowed).u128(), looping.denom),
+    }),
+    UNWIND_REPAY_REPLY_ID,
+  )?;
+  Ok(Response::new().add_submessage(repay))
+}
+
+// reply_unwind_withdraw repays the borrow with the tokens received for the
+// withdrawn collateral, the unwind ends when there is no borrow left.
+pub fn reply_unwind_withdraw(
+  deps: DepsMut,
+  env: Env,
+  result: SubMsgResult,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let withdraw = parse_reply::<WithdrawResult>(result)?;
+  let looping = LOOPING.load(deps.storage)?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let position = loop_position(&umee, &env.contract.address, &looping)?;
+
+  let res = Response::new().add_attribute("method", "unwind_withdraw");
+  if position.borrowed.is_zero() {
+    return Ok(res.add_attribute("loop", "done"));
+  }
+  let repay = submsg(
+    StructUmeeMsg::repay(RepayParams {
+      asset: Coin::new(
+        withdraw.received.amount.min(position.borrowed).u128(),
+        looping.denom,
+      ),
+    }),
+    UNWIND_REPAY_REPLY_ID,
+  )?;
+  Ok(res.add_submessage(repay))
+}
+
+// reply_unwind_repay withdraws again, while there are loops left. Once the
+// borrow is repaid the collateral left is withdrawn without a loop.
+pub fn reply_unwind_repay(
+  deps: DepsMut,
+  env: Env,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut looping = LOOPING.load(deps.storage)?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let position = loop_position(&umee, &env.contract.address, &looping)?;
+  if looping.loops_left == 0 && !position.borrowed.is_zero() {
+    return Ok(
+      Response::new()
+        .add_attribute("method", "unwind_repay")
+        .add_attribute("loop", "done"),
+    );
+  }
+  looping.loops_left = looping.loops_left.saturating_sub(1);
+  LOOPING.save(deps.storage, &looping)?;
+  let res = unwind_withdraw(deps, env)?;
+  Ok(res.add_attribute("method", "unwind_repay"))
+}
+
+// returns the looped denom with the tokens the collateral of the loop is
+// worth and the borrowed tokens of the contract, the leverage is the
+// supplied over the equity.
+pub fn query_looping(deps: Deps, env: Env) -> StdResult<LoopingResponse> {
+  let looping = match LOOPING.may_load(deps.storage)? {
+    Some(looping) => looping,
+    None => return Ok(LoopingResponse::default()),
+  };
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let position = loop_position(&umee, &env.contract.address, &looping)?;
+  let equity = position.supplied.saturating_sub(position.borrowed);
+  let leverage = if equity.is_zero() {
+    Decimal256::zero()
+  } else {
+    Decimal256::from_ratio(position.supplied, equity)
+  };
+  Ok(LoopingResponse {
+    denom: looping.denom,
+    target_leverage: looping.target_leverage,
+    supplied: position.supplied,
+    borrowed: position.borrowed,
+    leverage,
+  })
+}
@@ -0,0 +1,107 @@
+use cosmwasm_std::{DepsMut, StdError, StdResult};
+use cw_umee_types::error::ContractError;
+
+// Migration upgrades the state to the layout of a contract version, it runs
+// when a contract older than the version migrates to it or a later one.
+pub type Migration = (&'static str, fn(DepsMut) -> Result<(), ContractError>);
+
+// MIGRATIONS are the state migrations of the contract versions, ordered by
+// version. A version that changes the layout of a stored item adds its
+// migration here, the items added with may_load defaults don't need one.
+pub const MIGRATIONS: &[Migration] = &[];
+
+// Version is the major, minor and patch numbers of a semver version, the
+// pre-release and build metadata are ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(u64, u64, u64);
+
+impl Version {
+  pub fn parse(version: &str) -> StdResult<Self> {
+    let core = version.split(['-', '+']).next().unwrap_or("");
+    let numbers = core
+      .split('.')
+      .map(|n| n.parse::<u64>())
+      .collect::<Result<Vec<_>, _>>()
+      .map_err(|_| StdError::generic_err(format!("invalid version: {}", version)))?;
+    match numbers.as_slice() {
+      [major, minor, patch] => Ok(Version(*major, *minor, *patch)),
+      _ => Err(StdError::generic_err(format!(
+        "invalid version: {}",
+        version
+      ))),
+    }
+  }
+}
+
+// migrate_state runs the migrations of the versions after from and up to
+// to, in version order, and returns the versions migrated.
+pub fn migrate_state(
+  mut deps: DepsMut,
+  from: &str,
+  to: &str,
+  migrations: &[Migration],
+) -> Result<Vec<String>, ContractError> {
+  let from = Version::parse(from)?;
+  let to = Version::parse(to)?;
+  let mut pending = migrations
+    .iter()
+    .map(|(version, migration)| Ok((Version::parse(version)?, *version, *migration)))
+    .collect::<StdResult<Vec<_>>>()?;
+  pending.sort_by_key(|(version, _, _)| *version);
+
+  let mut migrated = vec![];
+  for (version, name, migration) in pending {
+    if version > from && version <= to {
+      migration(deps.branch())?;
+      migrated.push(name.to_string());
+    }
+  }
+  Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cosmwasm_std::testing::mock_dependencies;
+  use cw_storage_plus::Item;
+
+  const APPLIED: Item<String> = Item::new("applied");
+
+  fn append(deps: DepsMut, suffix: &str) -> Result<(), ContractError> {
+    let applied = APPLIED.may_load(deps.storage)?.unwrap_or_default();
+    APPLIED.save(deps.storage, &format!("{}{}", applied, suffix))?;
+    Ok(())
+  }
+
+  #[test]
+  fn version_parse() {
+    assert_eq!(Version::parse("0.1.2").unwrap(), Version(0, 1, 2));
+    // the pre-release and build metadata are ignored
+    assert_eq!(Version::parse("1.2.3-rc.1").unwrap(), Version(1, 2, 3));
+    assert_eq!(Version::parse("1.2.3+build.5").unwrap(), Version(1, 2, 3));
+    assert!(Version::parse("0.10.0").unwrap() > Version::parse("0.9.9").unwrap());
+    Version::parse("1.2").unwrap_err();
+    Version::parse("1.2.x").unwrap_err();
+    Version::parse("").unwrap_err();
+  }
+
+  #[test]
+  fn migrate_state_order() {
+    let mut deps = mock_dependencies();
+
+    // the migrations after the stored version run in version order
+    let migrations: &[Migration] = &[
+      ("0.3.0", |deps| append(deps, "c")),
+      ("0.1.0", |deps| append(deps, "a")),
+      ("0.2.0-rc.1", |deps| append(deps, "b")),
+    ];
+    let migrated = migrate_state(deps.as_mut(), "0.1.0", "0.3.0", migrations).unwrap();
+    assert_eq!(migrated, vec!["0.2.0-rc.1", "0.3.0"]);
+    assert_eq!(APPLIED.load(&deps.storage).unwrap(), "bc");
+
+    // nothing runs up to the stored version
+    let migrated = migrate_state(deps.as_mut(), "0.3.0", "0.3.0", migrations).unwrap();
+    assert!(migrated.is_empty());
+    migrate_state(deps.as_mut(), "0.1", "0.3.0", migrations).unwrap_err();
+  }
+}
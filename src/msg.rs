@@ -1,4 +1,5 @@
-use cosmwasm_std::{Addr, QueryRequest};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal256, QueryRequest, Uint128};
+use cw_umee_types::query_incentive::CurrentRatesResponse;
 use cw_umee_types::{
   ExchangeRatesParams, LeverageParametersParams, RegisteredTokensParams, StructUmeeQuery,
   SupplyParams, UmeeMsg, UmeeQuery,
@@ -6,16 +7,113 @@ use cw_umee_types::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::{Expiry, PauseFlags, Role};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {}
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-  // updates the state owner
-  ChangeOwner { new_owner: Addr },
+  // proposes the new owner, that has to accept the ownership before the
+  // optional expiry, only the owner can propose it
+  ProposeOwner {
+    new_owner: Addr,
+    expiry: Option<Expiry>,
+  },
+  // makes the pending owner the owner, only the pending owner can accept
+  AcceptOwnership {},
+  // removes the pending owner, only the owner can cancel the transfer
+  CancelOwnershipTransfer {},
+  // leaves the contract without owner, only the owner can renounce
+  RenounceOwnership {},
+  // grants the role to the address, only the owner can grant roles
+  GrantRole {
+    address: Addr,
+    role: Role,
+  },
+  // revokes the role of the address, only the owner can revoke roles
+  RevokeRole {
+    address: Addr,
+    role: Role,
+  },
+  // sets the guardian that can pause the contract, only the owner can set it
+  SetGuardian {
+    guardian: Option<Addr>,
+  },
+  // pauses the flagged operations, the guardian and the owner can pause
+  Pause(PauseFlags),
+  // resumes the flagged operations, only the owner can unpause
+  Unpause(PauseFlags),
+  // executes the umee msg, the leverage msgs are gated by the role of the
+  // sender
   Umee(UmeeMsg),
+  // executes the umee msgs in order in one transaction, every msg is gated as
+  // if executed alone, the leverage msgs reply with their results and any
+  // failed msg reverts the batch
+  Batch(Vec<UmeeMsg>),
+  // supplies the asset, gated as the leverage supply msg
   Supply(SupplyParams),
+  // sets the budget of the liquidator, only the owner can set it
+  SetLiquidator {
+    budget: Vec<Coin>,
+    min_profit: Decimal256,
+  },
+  // liquidates the targets with the liquidator budget, up to limit targets,
+  // only the owner, the operators and the addresses allowed to liquidate
+  Liquidate {
+    limit: Option<u32>,
+  },
+  // supplies the funds as collateral and loops borrowing and supplying the
+  // same denom up to the target leverage, only the owner can loop
+  LoopDeposit {
+    target_leverage: Decimal256,
+    max_loops: Option<u32>,
+  },
+  // unwinds the loop of the denom withdrawing collateral and repaying the
+  // borrow, only the owner can deleverage
+  Deleverage {
+    denom: String,
+    max_loops: Option<u32>,
+  },
+  // sets the denom of the vault, only the owner can set it before the
+  // first deposit
+  SetVault {
+    denom: String,
+  },
+  // supplies the funds on behalf of the sender for vault shares
+  Deposit {},
+  // burns the shares of the sender for the tokens they are worth
+  Withdraw {
+    shares: Uint128,
+  },
+  // supplies the vault tokens sent with the msg, like the claimed rewards,
+  // into the vault position, only the owner can harvest
+  Harvest {},
+  // transfers the vault shares of the sender to the recipient, the cw20
+  // Transfer of the shares token
+  Transfer {
+    recipient: String,
+    amount: Uint128,
+  },
+  // transfers the vault shares of the sender to the contract and calls its
+  // Receive with the msg, the cw20 Send of the shares token
+  Send {
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+  },
+  // sets the bonded denom and the min interval in seconds of the
+  // compounder, only the owner can set them
+  SetCompounder {
+    denom: String,
+    min_interval: u64,
+  },
+  // claims the incentive rewards and bonds them back into the bonded denom
+  Compound {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -23,6 +121,14 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
   // GetOwner returns the current owner of the contract
   GetOwner {},
+  // GetOwnership returns the owner and the pending owner of the contract
+  GetOwnership {},
+  // Roles returns the roles of the address
+  Roles { address: Addr },
+  // PauseStatus returns the paused operations and the guardian
+  PauseStatus {},
+  // LeverageResult returns the result of the last leverage msg
+  LeverageResult {},
   // make requests directly to the blockchain using the struct
   Chain(Box<QueryRequest<StructUmeeQuery>>),
   // wraps to use the enums
@@ -31,6 +137,21 @@ pub enum QueryMsg {
   ExchangeRates(ExchangeRatesParams),
   RegisteredTokens(RegisteredTokensParams),
   LeverageParameters(LeverageParametersParams),
+  // Liquidator returns the budget and the totals of the liquidator
+  Liquidator {},
+  // Looping returns the looped denom and the leverage of the contract
+  Looping {},
+  // Vault returns the vault position and the price of its shares
+  Vault {},
+  // Shares returns the vault shares of the address and their worth
+  Shares { address: Addr },
+  // Balance returns the vault shares of the address, the cw20 Balance of the
+  // shares token
+  Balance { address: String },
+  // TokenInfo returns the cw20 TokenInfo of the shares token
+  TokenInfo {},
+  // Compounder returns the compounder totals and the pending rewards
+  Compounder {},
 }
 
 // returns the current contract owner
@@ -38,3 +159,92 @@ pub enum QueryMsg {
 pub struct OwnerResponse {
   pub owner: Addr,
 }
+
+// returns the contract owner, none once renounced, and the pending owner
+// with the expiry of the proposal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipResponse {
+  pub owner: Option<Addr>,
+  pub pending_owner: Option<Addr>,
+  pub pending_expiry: Option<Expiry>,
+}
+
+// returns if the address is the owner or an operator and the umee msgs it
+// is allowed to execute
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RolesResponse {
+  pub owner: bool,
+  pub operator: bool,
+  pub allowed: Vec<String>,
+}
+
+// returns the guardian and the operations paused by the circuit breaker
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseStatusResponse {
+  pub guardian: Option<Addr>,
+  pub deposits: bool,
+  pub borrows: bool,
+  pub liquidations: bool,
+}
+
+// returns the liquidator budget and the totals of its liquidations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidatorResponse {
+  pub budget: Vec<Coin>,
+  pub min_profit: Decimal256,
+  pub liquidations: u64,
+  pub repaid: Vec<Coin>,
+  pub rewarded: Vec<Coin>,
+  pub profit: Decimal256,
+}
+
+// returns the looped denom, the supplied and borrowed tokens of the contract
+// and its leverage, the supplied over the supplied minus the borrowed
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct LoopingResponse {
+  pub denom: String,
+  pub target_leverage: Decimal256,
+  pub supplied: Uint128,
+  pub borrowed: Uint128,
+  pub leverage: Decimal256,
+}
+
+// returns the vault position, the uTokens of the vault and their worth in
+// tokens, and the tokens per share
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultResponse {
+  pub denom: String,
+  pub total_shares: Uint128,
+  pub u_tokens: Uint128,
+  pub tokens: Uint128,
+  pub share_price: Decimal256,
+  pub harvested: Uint128,
+}
+
+// returns the vault shares of an address and the uTokens and tokens they
+// are worth
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharesResponse {
+  pub shares: Uint128,
+  pub u_tokens: Uint128,
+  pub tokens: Uint128,
+}
+
+// returns the compounder settings and totals, the last reward time of the
+// previous compound, the incentive rewards the contract can claim and the
+// current rewards per year of a reference bond of the bonded uToken. The
+// rewards of other denoms are only compounded when a meToken index swaps
+// them into the bonded denom, the bonded meToken accepting them or an index
+// accepting both, the others are kept by the contract as the idle rewards.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompounderResponse {
+  pub denom: String,
+  pub min_interval: u64,
+  pub last_reward_time: Option<i64>,
+  pub compounds: u64,
+  pub compounded: Uint128,
+  pub bonded: Uint128,
+  pub pending_rewards: Vec<Coin>,
+  pub current_rates: CurrentRatesResponse,
+  pub idle_rewards: Vec<Coin>,
+}
@@ -0,0 +1,115 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::StructUmeeMsg;
+
+use crate::msg::OwnershipResponse;
+use crate::state::{Expiry, PendingOwner, PENDING_OWNER, STATE};
+
+// assert_owner fails as Unauthorized unless the sender is the owner, a
+// renounced contract has no owner.
+pub fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+  let state = STATE.load(storage)?;
+  if state.owner.as_ref() != Some(sender) {
+    return Err(ContractError::Unauthorized {});
+  }
+  Ok(())
+}
+
+// try_propose_owner proposes the new owner, that becomes the owner when it
+// accepts the ownership before the expiry. It replaces the pending proposal.
+pub fn try_propose_owner(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  new_owner: Addr,
+  expiry: Option<Expiry>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let new_owner = deps.api.addr_validate(new_owner.as_str())?;
+  if let Some(expiry) = &expiry {
+    if expiry.is_expired(&env) {
+      return Err(ContractError::CustomError {
+        val: format!("ownership expiry already passed: {}", expiry),
+      });
+    }
+  }
+  PENDING_OWNER.save(
+    deps.storage,
+    &PendingOwner {
+      owner: new_owner.clone(),
+      expiry,
+    },
+  )?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "propose_owner")
+      .add_attribute("pending_owner", new_owner),
+  )
+}
+
+// try_accept_ownership makes the pending owner the owner, only the pending
+// owner can accept before the expiry.
+pub fn try_accept_ownership(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let pending = match PENDING_OWNER.may_load(deps.storage)? {
+    Some(pending) if pending.owner == info.sender => pending,
+    _ => return Err(ContractError::Unauthorized {}),
+  };
+  if let Some(expiry) = &pending.expiry {
+    if expiry.is_expired(&env) {
+      return Err(ContractError::CustomError {
+        val: format!("ownership proposal expired: {}", expiry),
+      });
+    }
+  }
+  STATE.update(deps.storage, |mut state| -> StdResult<_> {
+    state.owner = Some(pending.owner.clone());
+    Ok(state)
+  })?;
+  PENDING_OWNER.remove(deps.storage);
+  Ok(
+    Response::new()
+      .add_attribute("method", "accept_ownership")
+      .add_attribute("owner", pending.owner),
+  )
+}
+
+// try_cancel_ownership_transfer removes the pending proposal, only the
+// owner can cancel it.
+pub fn try_cancel_ownership_transfer(
+  deps: DepsMut,
+  info: MessageInfo,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  PENDING_OWNER.remove(deps.storage);
+  Ok(Response::new().add_attribute("method", "cancel_ownership_transfer"))
+}
+
+// try_renounce_ownership leaves the contract without owner, the owner only
+// msgs can't be executed anymore.
+pub fn try_renounce_ownership(
+  deps: DepsMut,
+  info: MessageInfo,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  STATE.update(deps.storage, |mut state| -> StdResult<_> {
+    state.owner = None;
+    Ok(state)
+  })?;
+  PENDING_OWNER.remove(deps.storage);
+  Ok(Response::new().add_attribute("method", "renounce_ownership"))
+}
+
+// returns the owner and the pending owner with its expiry.
+pub fn query_ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+  let state = STATE.load(deps.storage)?;
+  let pending = PENDING_OWNER.may_load(deps.storage)?;
+  Ok(OwnershipResponse {
+    owner: state.owner,
+    pending_owner: pending.as_ref().map(|pending| pending.owner.clone()),
+    pending_expiry: pending.and_then(|pending| pending.expiry),
+  })
+}
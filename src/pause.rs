@@ -0,0 +1,138 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, MessageInfo, Response, StdResult, Storage};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::StructUmeeMsg;
+
+use crate::msg::PauseStatusResponse;
+use crate::ownership::assert_owner;
+use crate::state::{PauseFlags, GUARDIAN, PAUSE};
+
+// Operation is an operation the circuit breaker can pause.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+  Deposits,
+  Borrows,
+  Liquidations,
+}
+
+impl Operation {
+  // umee returns the operation of the umee msg, the msgs that withdraw,
+  // repay, unbond, claim or redeem are never paused.
+  pub fn umee(msg_name: &str) -> Option<Self> {
+    match msg_name {
+      "supply" | "collateralize" | "supply_collateral" | "bond" | "metoken_swap" => {
+        Some(Operation::Deposits)
+      }
+      "borrow" | "max_borrow" => Some(Operation::Borrows),
+      "liquidate" => Some(Operation::Liquidations),
+      _ => None,
+    }
+  }
+
+  fn name(&self) -> &'static str {
+    match self {
+      Operation::Deposits => "deposits",
+      Operation::Borrows => "borrows",
+      Operation::Liquidations => "liquidations",
+    }
+  }
+}
+
+fn load_flags(storage: &dyn Storage) -> StdResult<PauseFlags> {
+  Ok(PAUSE.may_load(storage)?.unwrap_or_default())
+}
+
+// assert_not_paused fails as Paused when the operation is paused.
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+  let flags = load_flags(storage)?;
+  let paused = match operation {
+    Operation::Deposits => flags.deposits,
+    Operation::Borrows => flags.borrows,
+    Operation::Liquidations => flags.liquidations,
+  };
+  if paused {
+    return Err(ContractError::Paused {
+      operation: operation.name().to_string(),
+    });
+  }
+  Ok(())
+}
+
+// try_set_guardian sets the guardian of the contract, or removes it, only
+// the owner can set it.
+pub fn try_set_guardian(
+  deps: DepsMut,
+  info: MessageInfo,
+  guardian: Option<Addr>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let res = Response::new().add_attribute("method", "set_guardian");
+  match guardian {
+    Some(guardian) => {
+      let guardian = deps.api.addr_validate(guardian.as_str())?;
+      GUARDIAN.save(deps.storage, &guardian)?;
+      Ok(res.add_attribute("guardian", guardian))
+    }
+    None => {
+      GUARDIAN.remove(deps.storage);
+      Ok(res)
+    }
+  }
+}
+
+// try_pause pauses the flagged operations, the others keep their flags.
+// The guardian and the owner can pause.
+pub fn try_pause(
+  deps: DepsMut,
+  info: MessageInfo,
+  pause: PauseFlags,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let guardian = GUARDIAN.may_load(deps.storage)?;
+  if guardian.as_ref() != Some(&info.sender) {
+    assert_owner(deps.storage, &info.sender)?;
+  }
+  let mut flags = load_flags(deps.storage)?;
+  flags.deposits |= pause.deposits;
+  flags.borrows |= pause.borrows;
+  flags.liquidations |= pause.liquidations;
+  PAUSE.save(deps.storage, &flags)?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "pause")
+      .add_attribute("deposits", flags.deposits.to_string())
+      .add_attribute("borrows", flags.borrows.to_string())
+      .add_attribute("liquidations", flags.liquidations.to_string()),
+  )
+}
+
+// try_unpause resumes the flagged operations, only the owner can unpause
+// so a compromised guardian can't undo a pause.
+pub fn try_unpause(
+  deps: DepsMut,
+  info: MessageInfo,
+  unpause: PauseFlags,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let mut flags = load_flags(deps.storage)?;
+  flags.deposits &= !unpause.deposits;
+  flags.borrows &= !unpause.borrows;
+  flags.liquidations &= !unpause.liquidations;
+  PAUSE.save(deps.storage, &flags)?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "unpause")
+      .add_attribute("deposits", flags.deposits.to_string())
+      .add_attribute("borrows", flags.borrows.to_string())
+      .add_attribute("liquidations", flags.liquidations.to_string()),
+  )
+}
+
+// returns the paused operations and the guardian of the contract.
+pub fn query_pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+  let flags = load_flags(deps.storage)?;
+  Ok(PauseStatusResponse {
+    guardian: GUARDIAN.may_load(deps.storage)?,
+    deposits: flags.deposits,
+    borrows: flags.borrows,
+    liquidations: flags.liquidations,
+  })
+}
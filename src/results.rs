@@ -0,0 +1,81 @@
+use cosmwasm_std::{
+  to_json_binary, Deps, DepsMut, Response, StdError, StdResult, Storage, SubMsgResult,
+};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::{BatchStep, LeverageResult, StructUmeeMsg, UmeeMsg, UmeeMsgLeverage};
+
+use crate::looping::submsg;
+use crate::state::{LEVERAGE_RESULT, PENDING_LEVERAGE};
+
+// the reply id of the leverage msgs executed by the contract
+pub const LEVERAGE_REPLY_ID: u64 = 7;
+
+// send_leverage sends the msg of the leverage constructor response as a
+// submsg, the reply parses its result.
+pub fn send_leverage(
+  storage: &mut dyn Storage,
+  msg: UmeeMsgLeverage,
+  res: Result<Response<StructUmeeMsg>, ContractError>,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let res = res?;
+  let attributes = res.attributes.clone();
+  let leverage = submsg(Ok(res), LEVERAGE_REPLY_ID)?;
+  push_leverage(storage, msg)?;
+  Ok(
+    Response::new()
+      .add_attributes(attributes)
+      .add_submessage(leverage),
+  )
+}
+
+// batch_step returns the step of the umee msg of a batch, the leverage
+// steps reply to parse their results like the leverage msgs sent alone.
+pub fn batch_step(storage: &mut dyn Storage, msg: UmeeMsg) -> StdResult<BatchStep> {
+  match &msg {
+    UmeeMsg::Leverage(leverage_msg) => {
+      push_leverage(storage, leverage_msg.clone())?;
+      Ok(BatchStep::with_reply(msg, LEVERAGE_REPLY_ID))
+    }
+    _ => Ok(BatchStep::new(msg)),
+  }
+}
+
+fn push_leverage(storage: &mut dyn Storage, msg: UmeeMsgLeverage) -> StdResult<()> {
+  let mut pending = PENDING_LEVERAGE.may_load(storage)?.unwrap_or_default();
+  pending.push(msg);
+  PENDING_LEVERAGE.save(storage, &pending)
+}
+
+// reply_leverage parses the result of the leverage msg, it is kept as the
+// last result and set as the data of the response.
+pub fn reply_leverage(
+  deps: DepsMut,
+  result: SubMsgResult,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut pending = PENDING_LEVERAGE.may_load(deps.storage)?.unwrap_or_default();
+  if pending.is_empty() {
+    return Err(ContractError::CustomError {
+      val: String::from("no leverage msg in flight"),
+    });
+  }
+  let msg = pending.remove(0);
+  PENDING_LEVERAGE.save(deps.storage, &pending)?;
+  let response = result
+    .into_result()
+    .map_err(|err| ContractError::CustomError { val: err })?;
+  let res = Response::new().add_attribute("method", "leverage_result");
+  match LeverageResult::parse(&msg, &response)? {
+    Some(result) => {
+      LEVERAGE_RESULT.save(deps.storage, &result)?;
+      Ok(res.set_data(to_json_binary(&result)?))
+    }
+    None => Ok(res),
+  }
+}
+
+// returns the result of the last leverage msg executed by the contract.
+pub fn query_leverage_result(deps: Deps) -> StdResult<LeverageResult> {
+  LEVERAGE_RESULT
+    .may_load(deps.storage)?
+    .ok_or_else(|| StdError::not_found("leverage result"))
+}
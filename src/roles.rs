@@ -0,0 +1,167 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Empty, MessageInfo, Response, StdResult};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::msg::UmeeMsg;
+use cw_umee_types::{StructUmeeMsg, UmeeMsgIncentive, UmeeMsgLeverage, UmeeMsgMeToken};
+
+use crate::msg::RolesResponse;
+use crate::ownership::assert_owner;
+use crate::state::{Role, ALLOWLIST, OPERATORS, STATE};
+
+// the names of the umee msgs, as in the json of the leverage and incentive
+// msgs, the metoken msgs are prefixed with metoken_
+pub const UMEE_MSGS: &[&str] = &[
+  "supply",
+  "withdraw",
+  "max_withdraw",
+  "collateralize",
+  "decollateralize",
+  "borrow",
+  "max_borrow",
+  "repay",
+  "liquidate",
+  "supply_collateral",
+  "bond",
+  "begin_unbonding",
+  "emergency_unbond",
+  "claim",
+  "sponsor",
+  "metoken_swap",
+  "metoken_redeem",
+];
+
+pub fn leverage_msg_name(msg: &UmeeMsgLeverage) -> &'static str {
+  match msg {
+    UmeeMsgLeverage::Supply(_) => "supply",
+    UmeeMsgLeverage::Withdraw(_) => "withdraw",
+    UmeeMsgLeverage::MaxWithdraw(_) => "max_withdraw",
+    UmeeMsgLeverage::Collateralize(_) => "collateralize",
+    UmeeMsgLeverage::Decollateralize(_) => "decollateralize",
+    UmeeMsgLeverage::Borrow(_) => "borrow",
+    UmeeMsgLeverage::MaxBorrow(_) => "max_borrow",
+    UmeeMsgLeverage::Repay(_) => "repay",
+    UmeeMsgLeverage::Liquidate(_) => "liquidate",
+    UmeeMsgLeverage::SupplyCollateral(_) => "supply_collateral",
+  }
+}
+
+pub fn incentive_msg_name(msg: &UmeeMsgIncentive) -> &'static str {
+  match msg {
+    UmeeMsgIncentive::Bond(_) => "bond",
+    UmeeMsgIncentive::BeginUnbonding(_) => "begin_unbonding",
+    UmeeMsgIncentive::EmergencyUnbond(_) => "emergency_unbond",
+    UmeeMsgIncentive::Claim(_) => "claim",
+    UmeeMsgIncentive::Sponsor(_) => "sponsor",
+  }
+}
+
+pub fn metoken_msg_name(msg: &UmeeMsgMeToken) -> &'static str {
+  match msg {
+    UmeeMsgMeToken::Swap(_) => "metoken_swap",
+    UmeeMsgMeToken::Redeem(_) => "metoken_redeem",
+  }
+}
+
+pub fn umee_msg_name(msg: &UmeeMsg) -> &'static str {
+  match msg {
+    UmeeMsg::Leverage(msg) => leverage_msg_name(msg),
+    UmeeMsg::Incentive(msg) => incentive_msg_name(msg),
+    UmeeMsg::Metoken(msg) => metoken_msg_name(msg),
+  }
+}
+
+// operator_msg returns if the operators can execute the umee msg, the msgs
+// that move funds into the leverage module, bond them or claim the rewards.
+// The msgs that take funds out of the modules, open a borrow, spend the
+// funds of the contract or swap them are left to the owner and the
+// allowlist.
+fn operator_msg(name: &str) -> bool {
+  matches!(
+    name,
+    "supply" | "collateralize" | "supply_collateral" | "repay" | "liquidate" | "bond" | "claim"
+  )
+}
+
+// assert_umee_role fails as Unauthorized unless the sender is the owner, an
+// operator of an operator msg or is allowed to execute the umee msg.
+pub fn assert_umee_role(deps: Deps, sender: &Addr, msg_name: &str) -> Result<(), ContractError> {
+  let state = STATE.load(deps.storage)?;
+  if state.owner.as_ref() == Some(sender)
+    || (operator_msg(msg_name) && OPERATORS.has(deps.storage, sender))
+    || ALLOWLIST.has(deps.storage, (msg_name, sender))
+  {
+    return Ok(());
+  }
+  Err(ContractError::Unauthorized {})
+}
+
+fn validate_role(role: &Role) -> Result<(), ContractError> {
+  if let Role::Allowed { msg } = role {
+    if !UMEE_MSGS.contains(&msg.as_str()) {
+      return Err(ContractError::CustomError {
+        val: format!("unknown umee msg: {}", msg),
+      });
+    }
+  }
+  Ok(())
+}
+
+// try_grant_role grants the role to the address, only the owner can grant
+// roles.
+pub fn try_grant_role(
+  deps: DepsMut,
+  info: MessageInfo,
+  address: Addr,
+  role: Role,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  validate_role(&role)?;
+  let address = deps.api.addr_validate(address.as_str())?;
+  match &role {
+    Role::Operator {} => OPERATORS.save(deps.storage, &address, &Empty {})?,
+    Role::Allowed { msg } => ALLOWLIST.save(deps.storage, (msg, &address), &Empty {})?,
+  }
+  Ok(
+    Response::new()
+      .add_attribute("method", "grant_role")
+      .add_attribute("address", address)
+      .add_attribute("role", role.to_string()),
+  )
+}
+
+// try_revoke_role revokes the role of the address, only the owner can
+// revoke roles.
+pub fn try_revoke_role(
+  deps: DepsMut,
+  info: MessageInfo,
+  address: Addr,
+  role: Role,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  validate_role(&role)?;
+  match &role {
+    Role::Operator {} => OPERATORS.remove(deps.storage, &address),
+    Role::Allowed { msg } => ALLOWLIST.remove(deps.storage, (msg, &address)),
+  }
+  Ok(
+    Response::new()
+      .add_attribute("method", "revoke_role")
+      .add_attribute("address", address)
+      .add_attribute("role", role.to_string()),
+  )
+}
+
+// returns the roles of the address and the umee msgs it is allowed to
+// execute.
+pub fn query_roles(deps: Deps, address: Addr) -> StdResult<RolesResponse> {
+  let state = STATE.load(deps.storage)?;
+  let allowed = UMEE_MSGS
+    .iter()
+    .filter(|name| ALLOWLIST.has(deps.storage, (name, &address)))
+    .map(|name| name.to_string())
+    .collect();
+  Ok(RolesResponse {
+    owner: state.owner.as_ref() == Some(&address),
+    operator: OPERATORS.has(deps.storage, &address),
+    allowed,
+  })
+}
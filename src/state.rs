@@ -1,11 +1,215 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Coin, Decimal256, Empty, Env, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
+use cw_umee_types::{LeverageResult, UmeeMsgLeverage};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+// State is the owner of the contract, that is none once the ownership is
+// renounced.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
-  pub owner: Addr,
+  pub owner: Option<Addr>,
 }
 
 pub const STATE: Item<State> = Item::new("state");
+
+// Expiry is the block height or time when a proposal expires.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Expiry {
+  AtHeight(u64),
+  AtTime(Timestamp),
+}
+
+impl Expiry {
+  pub fn is_expired(&self, env: &Env) -> bool {
+    match self {
+      Expiry::AtHeight(height) => env.block.height >= *height,
+      Expiry::AtTime(time) => env.block.time >= *time,
+    }
+  }
+}
+
+impl std::fmt::Display for Expiry {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Expiry::AtHeight(height) => write!(f, "height {}", height),
+      Expiry::AtTime(time) => write!(f, "time {}", time),
+    }
+  }
+}
+
+// PendingOwner is the proposed owner of the contract, that has to accept
+// the ownership before the expiry.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwner {
+  pub owner: Addr,
+  pub expiry: Option<Expiry>,
+}
+
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+
+// Liquidator is the state of the liquidation bot, the budget is the amount
+// of each denom that can still be repaid in liquidations and the totals
+// are the repaid and rewarded coins with the profit of the liquidations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Liquidator {
+  pub budget: Vec<Coin>,
+  pub min_profit: Decimal256,
+  pub liquidations: u64,
+  pub repaid: Vec<Coin>,
+  pub rewarded: Vec<Coin>,
+  pub profit: Decimal256,
+}
+
+pub const LIQUIDATOR: Item<Liquidator> = Item::new("liquidator");
+
+// PendingLiquidation is a liquidate msg sent by the liquidator, the planned
+// repayment is reserved from the budget until its reply records the amounts
+// the chain repaid and rewarded, valued at the prices of the plan.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingLiquidation {
+  pub borrower: Addr,
+  pub repayment: Coin,
+  pub reward: Coin,
+  pub repayment_value: Decimal256,
+  pub reward_value: Decimal256,
+}
+
+// the liquidate submsgs in flight, in the order they reply
+pub const PENDING_LIQUIDATIONS: Item<Vec<PendingLiquidation>> = Item::new("pending_liquidations");
+
+// Looping is the state of the looping vault, the denom looped to the target
+// leverage with the loops left of the current msg, the pending amount of
+// the submsg in flight, that the next step of the loop uses, and the
+// collateral uTokens supplied by the loop. The contract also holds the
+// uTokens of the vault and the compounder, the deleverage only withdraws
+// the collateral of the loop.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Looping {
+  pub denom: String,
+  pub target_leverage: Decimal256,
+  pub loops_left: u32,
+  pub pending: Uint128,
+  #[serde(default)]
+  pub collateral: Uint128,
+}
+
+pub const LOOPING: Item<Looping> = Item::new("looping");
+
+// Vault is the state of the share vault, the denom supplied on behalf of
+// the depositors, the shares issued to them, the uTokens received for the
+// vault supplies and the tokens harvested into the vault position. The
+// uTokens are tracked apart from the uToken balance of the contract, that
+// also holds the supplies of the owner, the looping and the compounder.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vault {
+  pub denom: String,
+  pub total_shares: Uint128,
+  pub u_tokens: Uint128,
+  pub harvested: Uint128,
+}
+
+pub const VAULT: Item<Vault> = Item::new("vault");
+
+// the shares of each depositor of the vault, the balances of the cw20 token
+// of the shares, that the contract itself implements as umee has no
+// tokenfactory module
+pub const SHARES: Map<&Addr, Uint128> = Map::new("shares");
+
+// the depositor of the vault withdraw in flight, its reply sends the tokens
+// received to the depositor
+pub const PENDING_VAULT_WITHDRAW: Item<Addr> = Item::new("pending_vault_withdraw");
+
+// Compounder is the state of the rewards compounder, the bonded denom the
+// rewards are compounded into and the min seconds of rewards between the
+// compounds, with the last reward time of the previous compound. The totals
+// are the claimed tokens compounded and the uTokens bonded with them, the
+// pending amount is the balance before the submsg in flight. The rewards of
+// other denoms are swapped through the queued meToken swaps, the claimed
+// rewards without a meToken route are the idle rewards, and the reward
+// balances are the balances of the other denoms before the claim.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Compounder {
+  pub denom: String,
+  pub min_interval: u64,
+  pub last_reward_time: Option<i64>,
+  pub compounds: u64,
+  pub compounded: Uint128,
+  pub bonded: Uint128,
+  pub pending: Uint128,
+  #[serde(default)]
+  pub reward_balances: Vec<Coin>,
+  #[serde(default)]
+  pub swaps: Vec<CompoundSwap>,
+  #[serde(default)]
+  pub idle_rewards: Vec<Coin>,
+}
+
+// CompoundSwap is a step of the swap of a reward into the bonded denom, the
+// swap of the reward into the meTokens of an index and, when the meToken is
+// not the bonded denom, the redeem of the meTokens received, that are the
+// meToken balance over the balance before the swap.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompoundSwap {
+  Swap {
+    asset: Coin,
+    metoken_denom: String,
+  },
+  Redeem {
+    metoken_denom: String,
+    before: Uint128,
+  },
+}
+
+pub const COMPOUNDER: Item<Compounder> = Item::new("compounder");
+
+// Role is a permission granted by the owner, an operator can execute the
+// umee msgs that move funds into the leverage module, bond them or claim the
+// rewards and an allowed address can execute the umee msg of the name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+  Operator {},
+  Allowed { msg: String },
+}
+
+impl std::fmt::Display for Role {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Role::Operator {} => write!(f, "operator"),
+      Role::Allowed { msg } => write!(f, "allowed {}", msg),
+    }
+  }
+}
+
+// the operators of the contract
+pub const OPERATORS: Map<&Addr, Empty> = Map::new("operators");
+
+// the addresses allowed to execute each leverage msg, by msg name
+pub const ALLOWLIST: Map<(&str, &Addr), Empty> = Map::new("allowlist");
+
+// PauseFlags are the operations paused by the circuit breaker, deposits
+// are the msgs that supply to the leverage module, borrows the msgs that
+// open a borrow and liquidations the liquidate msgs. The withdraws and
+// repays are never paused.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct PauseFlags {
+  pub deposits: bool,
+  pub borrows: bool,
+  pub liquidations: bool,
+}
+
+pub const PAUSE: Item<PauseFlags> = Item::new("pause");
+
+// the guardian can pause the contract, the owner unpauses it
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+
+// the leverage msgs of the submsgs in flight in the order they are sent,
+// the reply of each parses the result of the first
+pub const PENDING_LEVERAGE: Item<Vec<UmeeMsgLeverage>> = Item::new("pending_leverage");
+
+// the result of the last leverage msg executed by the contract
+pub const LEVERAGE_RESULT: Item<LeverageResult> = Item::new("leverage_result");
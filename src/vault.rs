@@ -0,0 +1,498 @@
+use cosmwasm_std::{
+  Addr, BankMsg, Binary, Coin, Decimal256, Deps, DepsMut, Env, MessageInfo, QuerierWrapper,
+  Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ReceiveMsg, TokenInfoResponse};
+use cw_umee_types::error::ContractError;
+use cw_umee_types::{
+  parse_reply, MarketSummaryParams, StructUmeeMsg, StructUmeeQuery, SupplyParams, SupplyResult,
+  UmeeMsgLeverage, UmeeQuerier, WithdrawParams, WithdrawResult,
+};
+use std::convert::TryFrom;
+
+use crate::looping::submsg;
+use crate::msg::{SharesResponse, VaultResponse};
+use crate::ownership::assert_owner;
+use crate::state::{Vault, PENDING_VAULT_WITHDRAW, SHARES, VAULT};
+
+// the reply id of the vault supplies, the reply adds the uTokens received to
+// the vault
+pub const VAULT_SUPPLY_REPLY_ID: u64 = 9;
+// the reply id of the vault withdraws, the reply sends the tokens received
+// to the depositor
+pub const VAULT_WITHDRAW_REPLY_ID: u64 = 10;
+
+// the virtual shares and uTokens added to the vault when pricing the shares,
+// a donation to the vault can't round the shares of the next deposit to zero
+const VIRTUAL_SHARES: u128 = 1;
+const VIRTUAL_U_TOKENS: u128 = 1;
+
+// VaultPosition is the uTokens of the vault and the exchange rate of the
+// uTokens to tokens of the leverage module.
+struct VaultPosition {
+  u_tokens: Uint128,
+  total_shares: Uint128,
+  exchange_rate: Decimal256,
+}
+
+impl VaultPosition {
+  // to_tokens returns the tokens the uTokens are worth, truncated as the
+  // leverage module does on withdraw.
+  fn to_tokens(&self, u_tokens: Uint128) -> StdResult<Uint128> {
+    let tokens = Decimal256::from_ratio(u_tokens, 1u128) * self.exchange_rate;
+    Ok(Uint128::try_from(tokens.to_uint_floor())?)
+  }
+
+  // to_u_tokens returns the uTokens the tokens are worth, truncated as the
+  // leverage module does on supply.
+  fn to_u_tokens(&self, tokens: Uint128) -> StdResult<Uint128> {
+    if self.exchange_rate.is_zero() {
+      return Err(StdError::generic_err("invalid uToken exchange rate"));
+    }
+    let u_tokens = Decimal256::from_ratio(tokens, 1u128) / self.exchange_rate;
+    Ok(Uint128::try_from(u_tokens.to_uint_floor())?)
+  }
+
+  // to_shares returns the shares the uTokens are worth, rounded down.
+  fn to_shares(&self, u_tokens: Uint128) -> Uint128 {
+    u_tokens.multiply_ratio(
+      self.total_shares + Uint128::new(VIRTUAL_SHARES),
+      self.u_tokens + Uint128::new(VIRTUAL_U_TOKENS),
+    )
+  }
+
+  // shares_to_u_tokens returns the uTokens the shares are worth, rounded
+  // down and capped to the uTokens of the vault.
+  fn shares_to_u_tokens(&self, shares: Uint128) -> Uint128 {
+    shares
+      .multiply_ratio(
+        self.u_tokens + Uint128::new(VIRTUAL_U_TOKENS),
+        self.total_shares + Uint128::new(VIRTUAL_SHARES),
+      )
+      .min(self.u_tokens)
+  }
+
+  // share_price returns the tokens per share.
+  fn share_price(&self) -> Decimal256 {
+    Decimal256::from_ratio(
+      self.u_tokens + Uint128::new(VIRTUAL_U_TOKENS),
+      self.total_shares + Uint128::new(VIRTUAL_SHARES),
+    ) * self.exchange_rate
+  }
+}
+
+fn utoken_denom(denom: &str) -> String {
+  format!("u/{}", denom)
+}
+
+// vault_position returns the uTokens and the shares of the vault at the
+// uToken exchange rate, the uTokens of the supplies in flight are added by
+// their reply.
+fn vault_position(deps: Deps, vault: &Vault) -> StdResult<VaultPosition> {
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let summary = umee.market_summary(MarketSummaryParams {
+    denom: vault.denom.clone(),
+  })?;
+  Ok(VaultPosition {
+    u_tokens: vault.u_tokens,
+    total_shares: vault.total_shares,
+    exchange_rate: summary.utoken_exchange_rate(),
+  })
+}
+
+// vault_supply supplies the tokens into the vault position as a submsg, its
+// reply adds the uTokens received to the vault.
+fn vault_supply(asset: Coin) -> Result<SubMsg<StructUmeeMsg>, ContractError> {
+  submsg(
+    StructUmeeMsg::supply(SupplyParams { asset }),
+    VAULT_SUPPLY_REPLY_ID,
+  )
+}
+
+// reply_vault_supply adds the uTokens received for a vault supply to the
+// vault.
+pub fn reply_vault_supply(
+  deps: DepsMut,
+  result: SubMsgResult,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let supply = parse_reply::<SupplyResult>(result)?;
+  let mut vault = load_vault(deps.as_ref())?;
+  vault.u_tokens += supply.received.amount;
+  VAULT.save(deps.storage, &vault)?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "vault_supply")
+      .add_attribute("received", supply.received.to_string()),
+  )
+}
+
+// reply_vault_withdraw sends the tokens received for the withdrawn vault
+// uTokens to the depositor.
+pub fn reply_vault_withdraw(
+  deps: DepsMut,
+  result: SubMsgResult,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let withdraw = parse_reply::<WithdrawResult>(result)?;
+  let depositor = PENDING_VAULT_WITHDRAW.load(deps.storage)?;
+  PENDING_VAULT_WITHDRAW.remove(deps.storage);
+  let res = Response::new()
+    .add_attribute("method", "vault_withdraw")
+    .add_attribute("received", withdraw.received.to_string());
+  if withdraw.received.amount.is_zero() {
+    return Ok(res);
+  }
+  Ok(res.add_message(BankMsg::Send {
+    to_address: depositor.to_string(),
+    amount: vec![withdraw.received],
+  }))
+}
+
+// wallet_u_tokens returns the uTokens of the vault denom in the wallet of the
+// contract, out of the collateral.
+fn wallet_u_tokens(deps: Deps, env: &Env, vault: &Vault) -> StdResult<Uint128> {
+  Ok(
+    deps
+      .querier
+      .query_balance(&env.contract.address, utoken_denom(&vault.denom))?
+      .amount,
+  )
+}
+
+// assert_vault_backed fails when the leverage msgs would take the vault
+// uTokens out of the wallet of the contract, where they are held with the
+// uTokens of the owner supplies. As the chain withdraws the wallet uTokens
+// first, while the vault has shares the withdraws and the collateralizes of
+// the vault denom are limited to the wallet uTokens over the vault uTokens,
+// in the order of the msgs, and its MaxWithdraw is refused.
+pub fn assert_vault_backed(
+  deps: Deps,
+  env: &Env,
+  msgs: &[&UmeeMsgLeverage],
+) -> Result<(), ContractError> {
+  let vault = match VAULT.may_load(deps.storage)? {
+    Some(vault) if !vault.total_shares.is_zero() => vault,
+    _ => return Ok(()),
+  };
+  let u_denom = utoken_denom(&vault.denom);
+  let mut available: Option<Uint128> = None;
+  for msg in msgs {
+    let (asset, spent) = match msg {
+      UmeeMsgLeverage::MaxWithdraw(params) if params.denom == vault.denom => {
+        return Err(ContractError::CustomError {
+          val: format!(
+            "max withdraw of {} would take the vault uTokens",
+            vault.denom
+          ),
+        })
+      }
+      UmeeMsgLeverage::Withdraw(params) => (&params.asset, true),
+      UmeeMsgLeverage::Collateralize(params) => (&params.asset, true),
+      UmeeMsgLeverage::Decollateralize(params) => (&params.asset, false),
+      _ => continue,
+    };
+    if asset.denom != u_denom {
+      continue;
+    }
+    let wallet = match available {
+      Some(wallet) => wallet,
+      None => wallet_u_tokens(deps, env, &vault)?.saturating_sub(vault.u_tokens),
+    };
+    available = Some(if spent {
+      wallet
+        .checked_sub(asset.amount)
+        .map_err(|_| ContractError::CustomError {
+          val: format!("{} would take the vault uTokens", asset),
+        })?
+    } else {
+      wallet + asset.amount
+    });
+  }
+  Ok(())
+}
+
+fn load_vault(deps: Deps) -> Result<Vault, ContractError> {
+  VAULT
+    .may_load(deps.storage)?
+    .ok_or_else(|| ContractError::CustomError {
+      val: String::from("vault is not set"),
+    })
+}
+
+// try_set_vault sets the denom of the vault, only the owner can set it and
+// not after shares are issued.
+pub fn try_set_vault(
+  deps: DepsMut,
+  info: MessageInfo,
+  denom: String,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  if let Some(vault) = VAULT.may_load(deps.storage)? {
+    if !vault.total_shares.is_zero() {
+      return Err(ContractError::CustomError {
+        val: format!("vault of {} has shares", vault.denom),
+      });
+    }
+  }
+  VAULT.save(
+    deps.storage,
+    &Vault {
+      denom,
+      total_shares: Uint128::zero(),
+      u_tokens: Uint128::zero(),
+      harvested: Uint128::zero(),
+    },
+  )?;
+  Ok(Response::new().add_attribute("method", "set_vault"))
+}
+
+// try_deposit supplies the deposit on behalf of the sender, the shares
+// issued are the uTokens of the deposit at the share price of the vault.
+pub fn try_deposit(
+  deps: DepsMut,
+  info: MessageInfo,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut vault = load_vault(deps.as_ref())?;
+  let deposit = match info.funds.as_slice() {
+    [deposit] if deposit.denom == vault.denom && !deposit.amount.is_zero() => deposit.clone(),
+    _ => {
+      return Err(ContractError::CustomError {
+        val: format!("vault deposit must be a single coin of {}", vault.denom),
+      })
+    }
+  };
+  let position = vault_position(deps.as_ref(), &vault)?;
+  let shares = position.to_shares(position.to_u_tokens(deposit.amount)?);
+  if shares.is_zero() {
+    return Err(ContractError::CustomError {
+      val: format!("deposit of {} is worth no shares", deposit),
+    });
+  }
+
+  vault.total_shares += shares;
+  VAULT.save(deps.storage, &vault)?;
+  SHARES.update(deps.storage, &info.sender, |v| -> StdResult<_> {
+    Ok(v.unwrap_or_default() + shares)
+  })?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "deposit")
+      .add_attribute("depositor", info.sender)
+      .add_attribute("shares", shares)
+      .add_submessage(vault_supply(deposit)?),
+  )
+}
+
+// try_withdraw burns the shares of the sender and withdraws the uTokens they
+// are worth, the reply sends the tokens received to the sender. The vault
+// uTokens must all be in the wallet of the contract, the chain would take
+// the rest of the withdraw from the collateral of the contract.
+pub fn try_withdraw(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  shares: Uint128,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let mut vault = load_vault(deps.as_ref())?;
+  let owned = SHARES
+    .may_load(deps.storage, &info.sender)?
+    .unwrap_or_default();
+  if shares.is_zero() || shares > owned {
+    return Err(ContractError::CustomError {
+      val: format!("insufficient shares: {} of {}", shares, owned),
+    });
+  }
+  let wallet = wallet_u_tokens(deps.as_ref(), &env, &vault)?;
+  if wallet < vault.u_tokens {
+    return Err(ContractError::CustomError {
+      val: format!(
+        "vault uTokens are not backed: {} of {}",
+        wallet, vault.u_tokens
+      ),
+    });
+  }
+  let position = vault_position(deps.as_ref(), &vault)?;
+  let u_tokens = position.shares_to_u_tokens(shares);
+
+  vault.total_shares -= shares;
+  vault.u_tokens -= u_tokens;
+  VAULT.save(deps.storage, &vault)?;
+  SHARES.save(deps.storage, &info.sender, &(owned - shares))?;
+  let mut res = Response::new()
+    .add_attribute("method", "withdraw")
+    .add_attribute("depositor", info.sender.clone())
+    .add_attribute("shares", shares);
+  if !u_tokens.is_zero() {
+    PENDING_VAULT_WITHDRAW.save(deps.storage, &info.sender)?;
+    let withdraw = submsg(
+      StructUmeeMsg::withdraw(WithdrawParams {
+        asset: Coin::new(u_tokens.u128(), utoken_denom(&vault.denom)),
+      }),
+      VAULT_WITHDRAW_REPLY_ID,
+    )?;
+    res = res.add_submessage(withdraw);
+  }
+  Ok(res)
+}
+
+// move_shares moves the shares from the owner to the recipient.
+fn move_shares(
+  deps: DepsMut,
+  from: &Addr,
+  to: &Addr,
+  amount: Uint128,
+) -> Result<(), ContractError> {
+  let owned = SHARES.may_load(deps.storage, from)?.unwrap_or_default();
+  if amount.is_zero() || amount > owned {
+    return Err(ContractError::CustomError {
+      val: format!("insufficient shares: {} of {}", amount, owned),
+    });
+  }
+  SHARES.save(deps.storage, from, &(owned - amount))?;
+  SHARES.update(deps.storage, to, |v| -> StdResult<_> {
+    Ok(v.unwrap_or_default() + amount)
+  })?;
+  Ok(())
+}
+
+// try_transfer transfers the shares of the sender to the recipient, as the
+// cw20 Transfer of the shares token.
+pub fn try_transfer(
+  deps: DepsMut,
+  info: MessageInfo,
+  recipient: String,
+  amount: Uint128,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let recipient = deps.api.addr_validate(&recipient)?;
+  move_shares(deps, &info.sender, &recipient, amount)?;
+  Ok(
+    Response::new()
+      .add_attribute("action", "transfer")
+      .add_attribute("from", info.sender)
+      .add_attribute("to", recipient)
+      .add_attribute("amount", amount),
+  )
+}
+
+// try_send transfers the shares of the sender to the contract and calls its
+// Receive with the msg, as the cw20 Send of the shares token.
+pub fn try_send(
+  deps: DepsMut,
+  info: MessageInfo,
+  contract: String,
+  amount: Uint128,
+  msg: Binary,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  let contract = deps.api.addr_validate(&contract)?;
+  move_shares(deps, &info.sender, &contract, amount)?;
+  let receive = Cw20ReceiveMsg {
+    sender: info.sender.to_string(),
+    amount,
+    msg,
+  };
+  Ok(
+    Response::new()
+      .add_attribute("action", "send")
+      .add_attribute("from", info.sender)
+      .add_attribute("to", contract.clone())
+      .add_attribute("amount", amount)
+      .add_message(WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg: receive.into_binary()?,
+        funds: vec![],
+      }),
+  )
+}
+
+// try_harvest supplies the vault tokens sent by the owner, like the claimed
+// rewards, into the vault position, raising the price of the shares. The
+// idle balance of the contract is not swept, it may belong to the owner
+// supplies, the looping or the compounder.
+pub fn try_harvest(
+  deps: DepsMut,
+  info: MessageInfo,
+) -> Result<Response<StructUmeeMsg>, ContractError> {
+  assert_owner(deps.storage, &info.sender)?;
+  let mut vault = load_vault(deps.as_ref())?;
+  let harvest = match info.funds.as_slice() {
+    [harvest] if harvest.denom == vault.denom && !harvest.amount.is_zero() => harvest.clone(),
+    _ => {
+      return Err(ContractError::CustomError {
+        val: format!("vault harvest must be a single coin of {}", vault.denom),
+      })
+    }
+  };
+
+  vault.harvested += harvest.amount;
+  VAULT.save(deps.storage, &vault)?;
+  Ok(
+    Response::new()
+      .add_attribute("method", "harvest")
+      .add_attribute("harvested", harvest.to_string())
+      .add_submessage(vault_supply(harvest)?),
+  )
+}
+
+// returns the vault position with the tokens per share, the first deposit
+// is priced at the uToken exchange rate.
+pub fn query_vault(deps: Deps) -> StdResult<VaultResponse> {
+  let vault = VAULT
+    .may_load(deps.storage)?
+    .ok_or_else(|| StdError::not_found("vault"))?;
+  let position = vault_position(deps, &vault)?;
+  Ok(VaultResponse {
+    tokens: position.to_tokens(position.u_tokens)?,
+    share_price: position.share_price(),
+    denom: vault.denom,
+    total_shares: vault.total_shares,
+    u_tokens: vault.u_tokens,
+    harvested: vault.harvested,
+  })
+}
+
+// returns the shares of the address, as the cw20 Balance of the shares token.
+pub fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+  let address = deps.api.addr_validate(&address)?;
+  Ok(BalanceResponse {
+    balance: SHARES.may_load(deps.storage, &address)?.unwrap_or_default(),
+  })
+}
+
+// returns the cw20 TokenInfo of the shares token, the shares have the
+// decimals of the vault uTokens.
+pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
+  let vault = VAULT
+    .may_load(deps.storage)?
+    .ok_or_else(|| StdError::not_found("vault"))?;
+  let querier = QuerierWrapper::<StructUmeeQuery>::new(&*deps.querier);
+  let umee = UmeeQuerier::custom(&querier);
+  let token = umee
+    .registered_tokens()?
+    .registry
+    .into_iter()
+    .find(|token| token.base_denom() == vault.denom)
+    .ok_or_else(|| StdError::not_found(format!("registered Token {}", vault.denom)))?;
+  Ok(TokenInfoResponse {
+    name: format!("{} vault shares", token.symbol_denom()),
+    symbol: format!("v{}", token.symbol_denom()),
+    decimals: u8::try_from(token.exponent())
+      .map_err(|err| StdError::generic_err(err.to_string()))?,
+    total_supply: vault.total_shares,
+  })
+}
+
+// returns the shares of the address and the uTokens and tokens they are
+// worth at the current exchange rate.
+pub fn query_shares(deps: Deps, address: Addr) -> StdResult<SharesResponse> {
+  let vault = VAULT
+    .may_load(deps.storage)?
+    .ok_or_else(|| StdError::not_found("vault"))?;
+  let shares = SHARES.may_load(deps.storage, &address)?.unwrap_or_default();
+  let position = vault_position(deps, &vault)?;
+  let u_tokens = position.shares_to_u_tokens(shares);
+  Ok(SharesResponse {
+    shares,
+    u_tokens,
+    tokens: position.to_tokens(u_tokens)?,
+  })
+}